@@ -35,7 +35,7 @@ impl Parse for InGroupProduction {
         let store_index_token = input.parse::<LitInt>()?;
         input.parse::<Token![:]>()?;
         let mut token_list: LinkedList<ProductionToken> = LinkedList::new();
-        while !input.is_empty() {
+        while !input.is_empty() && !input.peek(Token![|]) {
             let token = input.parse::<ProductionToken>()?;
             token_list.push_back(token);
         }
@@ -342,7 +342,31 @@ impl Group {
         return quote! {};
     }
 
-    fn get_parse_star_non_terminal(&self, enum_name: &str) -> TokenStream {
+    /// Parses one repeated item. In a `#[Synchronize]` production a failed item
+    /// is handed to the stream, which records it and skips to the next statement
+    /// when error recovery is enabled.
+    fn get_parse_repeated_item(
+        non_terminal_name: &Ident,
+        non_terminal_type: &Type,
+        type_variant: TokenStream,
+        synchronize: bool,
+    ) -> TokenStream {
+        if synchronize {
+            quote! {
+                match input.parse::<#non_terminal_type>() {
+                    Ok(nt) => #non_terminal_name.push_back((#type_variant, nt)),
+                    Err(error) => input.recover(error)?,
+                }
+            }
+        } else {
+            quote! {
+                let nt = input.parse::<#non_terminal_type>()?;
+                #non_terminal_name.push_back((#type_variant, nt));
+            }
+        }
+    }
+
+    fn get_parse_star_non_terminal(&self, enum_name: &str, synchronize: bool) -> TokenStream {
         let non_terminal = self.non_terminal.as_ref();
         if non_terminal.is_none() {
             return quote! {};
@@ -352,24 +376,29 @@ impl Group {
         let non_terminal_type = non_terminal.get_type();
         let non_terminal_name = Ident::new(non_terminal.get_name(), Span::call_site());
         let enum_ident = Ident::new(enum_name, Span::call_site());
+        let parse_item = Self::get_parse_repeated_item(
+            &non_terminal_name,
+            &non_terminal_type,
+            quote! { #enum_ident::None },
+            synchronize,
+        );
         return quote! {
             let mut #non_terminal_name = std::collections::LinkedList::new();
             while #peek_sentence {
-                let nt = input.parse::<#non_terminal_type>()?;
-                #non_terminal_name.push_back((#enum_ident::None, nt));
+                #parse_item
             }
             let #non_terminal_name: Vec<_> = #non_terminal_name.into_iter().collect();
         };
     }
 
-    fn get_parse_star(&self, enum_name: &str) -> TokenStream {
+    fn get_parse_star(&self, enum_name: &str, synchronize: bool) -> TokenStream {
         let non_terminal = self.non_terminal.as_ref();
         if non_terminal.is_none() {
             return quote! {};
         }
         let or_elements = self.or_elements.as_ref();
         if or_elements.is_none() {
-            return self.get_parse_star_non_terminal(enum_name);
+            return self.get_parse_star_non_terminal(enum_name, synchronize);
         }
         let non_terminal = non_terminal.unwrap();
         let non_terminal_name = Ident::new(non_terminal.get_name(), Span::call_site());
@@ -524,9 +553,9 @@ impl Group {
         }
     }
 
-    pub fn get_parse_sentence(&self, enum_name: &str) -> TokenStream {
+    pub fn get_parse_sentence(&self, enum_name: &str, synchronize: bool) -> TokenStream {
         match &self.postfix {
-            GroupPostfix::Star => self.get_parse_star(enum_name),
+            GroupPostfix::Star => self.get_parse_star(enum_name, synchronize),
             GroupPostfix::Plus => self.get_parse_plus(enum_name),
             GroupPostfix::Question => self.get_parse_question(enum_name),
             GroupPostfix::None => self.get_parse_no_postfix(enum_name),
@@ -92,9 +92,9 @@ impl ProductionItem {
         }
     }
 
-    pub fn get_parse_sentence(&self, enum_name: &str) -> TokenStream {
+    pub fn get_parse_sentence(&self, enum_name: &str, synchronize: bool) -> TokenStream {
         match self {
-            ProductionItem::Group(group) => group.get_parse_sentence(enum_name),
+            ProductionItem::Group(group) => group.get_parse_sentence(enum_name, synchronize),
             ProductionItem::ProductionTokenChain(production_token_chain) => {
                 production_token_chain.get_parse_sentence()
             }
@@ -221,7 +221,7 @@ impl Production {
         let items = self
             .items
             .iter()
-            .map(|item| item.get_parse_sentence(&struct_ast.type_field));
+            .map(|item| item.get_parse_sentence(&struct_ast.type_field, struct_ast.synchronize));
         quote! {
 
             impl crate::common::Visitable for #struct_name_ident {}
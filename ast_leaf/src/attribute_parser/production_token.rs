@@ -22,7 +22,10 @@ impl Parse for Terminal {
             ")" => "RightParen",
             "{" => "LeftBrace",
             "}" => "RightBrace",
+            "[" => "LeftBracket",
+            "]" => "RightBracket",
             "," => "Comma",
+            ":" => "Colon",
             "." => "Dot",
             "-" => "Minus",
             "+" => "Plus",
@@ -34,25 +37,33 @@ impl Parse for Terminal {
             ">" => "Greater",
             "<" => "Less",
             "and" => "And",
+            "break" => "Break",
+            "catch" => "Catch",
             "class" => "Class",
+            "continue" => "Continue",
             "else" => "Else",
             "false" => "False",
+            "finally" => "Finally",
             "fun" => "Fun",
             "for" => "For",
             "if" => "If",
+            "import" => "Import",
             "nil" => "Nil",
             "or" => "Or",
             "return" => "Return",
             "super" => "Super",
             "print" => "Print",
             "this" => "This",
+            "throw" => "Throw",
             "true" => "True",
+            "try" => "Try",
             "var" => "Var",
             "while" => "While",
             "==" => "EqualEqual",
             "!=" => "BangEqual",
             ">=" => "GreaterEqual",
             "<=" => "LessEqual",
+            "=>" => "Arrow",
             "STRING" => "String",
             "NUMBER" => "Number",
             "IDENT" => "Identifier",
@@ -13,11 +13,13 @@ pub struct ASTLeafStruct {
     pub non_terminal_fields: Vec<(String, Type)>,
     pub token_list_field: Option<Ident>,
     pub remap_error: Option<syn::LitStr>,
+    pub synchronize: bool,
 }
 
 const TYPE_FIELD_ATTR: &str = "Type";
 const TOKEN_LIST_FIELD_ATTR: &str = "TokenList";
 const REMAPPED_ERROR_ATTR: &str = "SyncError";
+const SYNCHRONIZE_ATTR: &str = "Synchronize";
 
 impl Parse for ASTLeafStruct {
     fn parse(input: ParseStream) -> Result<Self> {
@@ -138,7 +140,13 @@ impl Parse for ASTLeafStruct {
                 return true;
             })
             .collect();
-        struct_ast.attrs = remap_attrs;
+        let synchronize = remap_attrs
+            .iter()
+            .any(|attr| attr.path().is_ident(SYNCHRONIZE_ATTR));
+        struct_ast.attrs = remap_attrs
+            .into_iter()
+            .filter(|attr| !attr.path().is_ident(SYNCHRONIZE_ATTR))
+            .collect();
 
         Ok(ASTLeafStruct {
             name,
@@ -148,6 +156,7 @@ impl Parse for ASTLeafStruct {
             non_terminal_fields: t,
             token_list_field,
             remap_error,
+            synchronize,
         })
     }
 }
@@ -0,0 +1,39 @@
+use super::{Command, CommandUtils};
+use crate::error::{InterpreterError, Result};
+use crate::evaluation::{Analyzer, Session};
+use crate::tokenizer::scan_tokens;
+
+/// Reports every scanner and syntax error in a file without running it. When
+/// the file parses, every top-level declaration is also resolved so all the
+/// scoping errors are reported, and a clean program is checked for warnings.
+/// Warnings are printed but never change the exit code.
+pub struct CheckCommand;
+
+impl Command for CheckCommand {
+    fn run(&self, filename: &str) -> Result<()> {
+        let file_contents = CommandUtils::read_file(filename)?;
+        let (tokens, scanner_errors) = scan_tokens(filename, &file_contents);
+
+        for error in &scanner_errors {
+            CommandUtils::report_error(error, Some(error.span()), filename, &file_contents);
+        }
+        let parse_result = CommandUtils::parse_program(tokens, filename, &file_contents);
+        if let Some(error) = scanner_errors.into_iter().next() {
+            return Err(error.into());
+        }
+        let program_ast = parse_result?;
+
+        let errors = Session::new()?.build_errors(&program_ast);
+        for error in &errors {
+            CommandUtils::report_error(error, error.span(), filename, &file_contents);
+        }
+        if let Some(error) = errors.into_iter().next() {
+            return Err(InterpreterError::from(error));
+        }
+
+        for warning in Analyzer::analyze(&program_ast)? {
+            CommandUtils::report_error(&warning, Some(warning.span()), filename, &file_contents);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use super::{Command, CommandOptions, CommandUtils};
+use crate::error::{InterpreterError, Result as CommandResult};
+use crate::evaluation::{Frame, RunHook, RuntimeError, Session, StackTrace};
+use crate::syntax_analysis::{Expression, ParseStream};
+use crate::tokenizer::{scan_tokens, TokenEnum};
+
+const PROMPT: &str = "(debug) ";
+
+const HELP: &str = "\
+step, s          run to the next statement, entering calls
+next, n          run to the next statement in this call
+out, o           run until this call returns
+continue, c      run to the next breakpoint
+break, b <line>  pause before running a line
+delete, d <line> remove a breakpoint
+locals, l        show the variables of each local scope
+print, p <expr>  evaluate an expression here
+stack, bt        show the calls in progress
+quit, q          stop the program";
+
+pub struct DebugCommand {
+    breakpoints: BTreeSet<usize>,
+}
+
+impl DebugCommand {
+    /// Supports `--break=<line>`, which may be repeated. Without it the
+    /// program is paused before its first statement.
+    pub fn new(options: &CommandOptions) -> CommandResult<Self> {
+        let breakpoints = options
+            .values("break")
+            .map(|line| {
+                line.parse::<usize>().map_err(|_| {
+                    InterpreterError::Usage(format!("Invalid value for --break: {}", line))
+                })
+            })
+            .collect::<CommandResult<_>>()?;
+        Ok(Self { breakpoints })
+    }
+}
+
+impl Command for DebugCommand {
+    fn run(&self, filename: &str) -> CommandResult<()> {
+        let file_contents = CommandUtils::read_file(filename)?;
+        let tokens = CommandUtils::scan_tokens_checked(filename, &file_contents)?;
+        let program = CommandUtils::parse_program(tokens, filename, &file_contents)?;
+
+        let report = |error: RuntimeError| {
+            CommandUtils::report_error(&error, error.span(), filename, &file_contents);
+            error
+        };
+        let session = Session::new().map_err(report)?;
+        let debugger = Debugger::new(
+            &file_contents,
+            self.breakpoints.clone(),
+            Box::new(io::stdin().lock()),
+        );
+        session.set_hook(Box::new(debugger));
+        let runnable = session.build_program(&program).map_err(report)?;
+        match session.run(runnable.as_ref()) {
+            Ok(()) | Err(RuntimeError::Stopped) => Ok(()),
+            Err(error) => Err(report(error).into()),
+        }
+    }
+}
+
+/// When the debugger pauses next
+enum Resume {
+    /// At the next statement
+    Step,
+    /// At the next statement with at most this many calls in progress
+    Next(usize),
+    /// At the next statement with fewer than this many calls in progress
+    Out(usize),
+    /// Only at breakpoints
+    Continue,
+}
+
+/// Pauses the program at breakpoints and steps, reading commands from `input`
+/// until one resumes it.
+pub struct Debugger {
+    lines: Vec<String>,
+    breakpoints: BTreeSet<usize>,
+    resume: Resume,
+    input: Box<dyn BufRead>,
+    prompt: bool,
+}
+
+impl Debugger {
+    pub fn new(source: &str, breakpoints: BTreeSet<usize>, input: Box<dyn BufRead>) -> Self {
+        let resume = match breakpoints.is_empty() {
+            true => Resume::Step,
+            false => Resume::Continue,
+        };
+        Self {
+            lines: source.lines().map(str::to_string).collect(),
+            breakpoints,
+            resume,
+            input,
+            prompt: io::stdin().is_terminal(),
+        }
+    }
+
+    fn should_pause(&self, line: usize, depth: usize) -> bool {
+        let stepped = match self.resume {
+            Resume::Step => true,
+            Resume::Next(from) => depth <= from,
+            Resume::Out(from) => depth < from,
+            Resume::Continue => false,
+        };
+        stepped || self.breakpoints.contains(&line)
+    }
+
+    fn show_location(&self, frame: &Frame) {
+        let line = frame.span().line;
+        let function = match frame.call_stack().last() {
+            Some(call) => call.to_string(),
+            None => "script".to_string(),
+        };
+        frame.write_line(&format!("Paused at line {} in {}", line, function));
+        if let Some(source) = self.lines.get(line - 1) {
+            frame.write_line(&format!("{:>4} | {}", line, source.trim_end()));
+        }
+    }
+
+    fn read_command(&mut self) -> Option<String> {
+        if self.prompt {
+            print!("{}", PROMPT);
+            io::stdout().flush().ok()?;
+        }
+        let mut line = String::new();
+        match self.input.read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line.trim().to_string()),
+        }
+    }
+
+    /// Runs commands until one resumes the program.
+    fn pause(&mut self, frame: &Frame) -> Result<(), RuntimeError> {
+        self.show_location(frame);
+        let depth = frame.call_stack().len();
+        loop {
+            let Some(command) = self.read_command() else {
+                // Nobody is left to give commands
+                self.resume = Resume::Continue;
+                self.breakpoints.clear();
+                return Ok(());
+            };
+            let (name, argument) = command
+                .split_once(char::is_whitespace)
+                .map_or((command.as_str(), ""), |(name, argument)| {
+                    (name, argument.trim())
+                });
+            self.resume = match name {
+                "step" | "s" => Resume::Step,
+                "next" | "n" => Resume::Next(depth),
+                "out" | "o" => Resume::Out(depth),
+                "continue" | "c" => Resume::Continue,
+                "quit" | "q" => return Err(RuntimeError::Stopped),
+                _ => {
+                    let output = self.inspect(frame, name, argument);
+                    frame.write_line(&output);
+                    continue;
+                }
+            };
+            return Ok(());
+        }
+    }
+
+    /// Runs a command that leaves the program paused and returns what it shows.
+    fn inspect(&mut self, frame: &Frame, name: &str, argument: &str) -> String {
+        match name {
+            "break" | "b" => match argument.parse::<usize>() {
+                Ok(line) => {
+                    self.breakpoints.insert(line);
+                    format!("Breakpoint set at line {}", line)
+                }
+                Err(_) => "Usage: break <line>".to_string(),
+            },
+            "delete" | "d" => match argument.parse::<usize>() {
+                Ok(line) if self.breakpoints.remove(&line) => {
+                    format!("Breakpoint removed from line {}", line)
+                }
+                Ok(line) => format!("No breakpoint at line {}", line),
+                Err(_) => "Usage: delete <line>".to_string(),
+            },
+            "locals" | "l" => Self::show_locals(frame),
+            "print" | "p" => match Self::evaluate(frame, argument) {
+                Ok(value) => value,
+                Err(error) => error,
+            },
+            "stack" | "bt" => {
+                StackTrace::new(frame.call_stack(), Some(frame.span().line)).to_string()
+            }
+            "help" | "h" => HELP.to_string(),
+            "" => String::new(),
+            _ => format!("Unknown command: {} (try help)", name),
+        }
+    }
+
+    fn show_locals(frame: &Frame) -> String {
+        let locals = frame
+            .locals()
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect::<Vec<_>>();
+        match locals.is_empty() {
+            true => "No local variables".to_string(),
+            false => locals.join("\n"),
+        }
+    }
+
+    fn evaluate(frame: &Frame, source: &str) -> Result<String, String> {
+        let (tokens, errors) = scan_tokens("<debug>", source);
+        if let Some(error) = errors.first() {
+            return Err(error.to_string());
+        }
+        // A function would be registered with a resolver the run doesn't use
+        if tokens
+            .iter()
+            .any(|token| matches!(token.token_type, TokenEnum::Fun | TokenEnum::Arrow))
+        {
+            return Err("Functions can't be defined while debugging.".to_string());
+        }
+        let mut stream = ParseStream::new(tokens);
+        let expression = stream
+            .parse::<Expression>()
+            .map_err(|error| error.to_string())?;
+        if !stream.is_at_end() {
+            return Err("Expect a single expression.".to_string());
+        }
+        frame
+            .evaluate(&expression)
+            .map(|value| value.to_string())
+            .map_err(|error| error.to_string())
+    }
+}
+
+impl RunHook for Debugger {
+    fn before_statement(&mut self, frame: &Frame) -> Result<(), RuntimeError> {
+        if self.should_pause(frame.span().line, frame.call_stack().len()) {
+            self.pause(frame)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::BufferSink;
+    use crate::syntax_analysis::ProgramAst;
+    use std::io::Cursor;
+
+    fn debug(source: &str, breakpoints: &[usize], commands: &str) -> String {
+        let (tokens, _) = scan_tokens("test.lox", source);
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        let session = Session::new().unwrap();
+        let output = BufferSink::new();
+        session.set_output(Box::new(output.clone()));
+        let input = Box::new(Cursor::new(commands.to_string()));
+        let debugger = Debugger::new(source, breakpoints.iter().copied().collect(), input);
+        session.set_hook(Box::new(debugger));
+        let runnable = session.build_program(&program).unwrap();
+        let _ = session.run(runnable.as_ref());
+        output.contents()
+    }
+
+    const SOURCE: &str = "\
+fun add(a, b) {
+  var sum = a + b;
+  return sum;
+}
+var x = add(1, 2);
+print x;
+";
+
+    #[test]
+    fn test_steps_into_and_out_of_calls() {
+        let output = debug(SOURCE, &[], "s\ns\ns\nlocals\nstack\no\nc\n");
+        assert_eq!(
+            output,
+            "\
+Paused at line 1 in script
+   1 | fun add(a, b) {
+Paused at line 5 in script
+   5 | var x = add(1, 2);
+Paused at line 2 in add()
+   2 |   var sum = a + b;
+Paused at line 3 in add()
+   3 |   return sum;
+sum = 3
+a = 1
+b = 2
+[line 3] in add()
+[line 5] in script
+Paused at line 6 in script
+   6 | print x;
+3
+"
+        );
+    }
+
+    #[test]
+    fn test_evaluates_in_the_paused_frame() {
+        let output = debug(SOURCE, &[3], "p sum * 2\np undefined\nn\nq\n");
+        assert_eq!(
+            output,
+            "\
+Paused at line 3 in add()
+   3 |   return sum;
+6
+Undefined variable 'undefined'.
+Line: 1
+Paused at line 6 in script
+   6 | print x;
+"
+        );
+    }
+}
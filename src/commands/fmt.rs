@@ -0,0 +1,52 @@
+use std::fs;
+
+use super::{Command, CommandOptions, CommandUtils};
+use crate::error::{InterpreterError, Result};
+use crate::syntax_analysis::Formatter;
+use crate::tokenizer::scan_tokens;
+
+/// Rewrites a file in the canonical style, in place. A file with scanner or
+/// syntax errors is reported and left untouched.
+pub struct FmtCommand {
+    check: bool,
+}
+
+impl FmtCommand {
+    /// Supports `--check`, which writes nothing and fails when the file isn't
+    /// formatted, for CI.
+    pub fn new(options: &CommandOptions) -> Self {
+        Self {
+            check: options.has_flag("check"),
+        }
+    }
+}
+
+impl Command for FmtCommand {
+    fn run(&self, filename: &str) -> Result<()> {
+        let file_contents = CommandUtils::read_file(filename)?;
+        let (tokens, scanner_errors) = scan_tokens(filename, &file_contents);
+        for error in &scanner_errors {
+            CommandUtils::report_error(error, Some(error.span()), filename, &file_contents);
+        }
+        if let Some(error) = scanner_errors.into_iter().next() {
+            return Err(error.into());
+        }
+
+        let comments = tokens
+            .iter()
+            .flat_map(|token| token.comments.iter().cloned())
+            .collect();
+        let program_ast = CommandUtils::parse_program(tokens, filename, &file_contents)?;
+        let formatted = Formatter::format(&program_ast, comments);
+        if formatted == file_contents {
+            return Ok(());
+        }
+        if self.check {
+            let error = InterpreterError::Unformatted(filename.to_string());
+            eprintln!("{}", error);
+            return Err(error);
+        }
+        fs::write(filename, formatted)
+            .map_err(|err| InterpreterError::write_error(filename.to_string(), err))
+    }
+}
@@ -0,0 +1,15 @@
+use std::io;
+
+use crate::error::Result;
+use crate::lsp::Server;
+
+/// Runs a language server on stdin and stdout until the client exits.
+pub struct LspCommand;
+
+impl LspCommand {
+    pub fn run_stdio(&self) -> Result<()> {
+        let mut server = Server::new(io::stdout().lock());
+        server.serve(&mut io::stdin().lock())?;
+        Ok(())
+    }
+}
@@ -1,24 +1,167 @@
+mod check;
+mod debug;
 mod evaluate;
+mod fmt;
+mod lsp;
 mod parse;
+mod repl;
 mod run;
+mod test;
 mod tokenize;
 
 use crate::error::{InterpreterError, Result};
-use crate::syntax_analysis::ParseStream;
-use crate::tokenizer::{scan_tokens, ScannerError, Token};
+use crate::syntax_analysis::{ParseStream, ProgramAst};
+use crate::tokenizer::{scan_tokens, ScannerError, Span, Token};
+use std::fmt::Display;
 use std::fs;
 use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
 
+pub use check::CheckCommand;
+pub use debug::DebugCommand;
 pub use evaluate::EvaluateCommand;
+pub use fmt::FmtCommand;
+pub use lsp::LspCommand;
 pub use parse::ParseCommand;
+pub use repl::ReplCommand;
 pub use run::RunCommand;
+pub use test::TestCommand;
 pub use tokenize::TokenizeCommand;
 
+/// Runs the command line interpreter on `args`, the program name included,
+/// and returns the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    if args.len() == 1 || args[1] == "repl" {
+        return match ReplCommand.run_interactive() {
+            Ok(()) => 0,
+            Err(error) => error.exit_code(),
+        };
+    }
+    if args[1] == "lsp" {
+        return match LspCommand.run_stdio() {
+            Ok(()) => 0,
+            Err(error) => {
+                eprintln!("{}", error);
+                error.exit_code()
+            }
+        };
+    }
+
+    let (options, positional) = CommandOptions::parse(&args[2..]);
+    let Some(filename) = positional.first() else {
+        eprintln!("Usage: {} [<command> [--options] <filename>]", args[0]);
+        return 0;
+    };
+
+    let command_str = &args[1];
+    CommandUtils::set_snippets(!options.has_flag("no-snippet"));
+
+    let command: Result<Box<dyn Command>> = match command_str.as_str() {
+        "tokenize" => Ok(Box::new(TokenizeCommand)),
+        "parse" => Ok(Box::new(ParseCommand)),
+        "evaluate" => Ok(Box::new(EvaluateCommand)),
+        "check" => Ok(Box::new(CheckCommand)),
+        "fmt" => Ok(Box::new(FmtCommand::new(&options))),
+        "test" => Ok(Box::new(TestCommand::new(&options))),
+        "debug" => DebugCommand::new(&options).map(|c| Box::new(c) as Box<dyn Command>),
+        "run" => {
+            RunCommand::new(&options, &positional[1..]).map(|c| Box::new(c) as Box<dyn Command>)
+        }
+        _ => {
+            eprintln!("Unknown command: {}", command_str);
+            return 0;
+        }
+    };
+
+    // Execute the command and handle the result
+    match command.and_then(|command| command.run(filename)) {
+        Ok(()) => 0,
+        Err(error) => {
+            if let InterpreterError::Usage(message) = &error {
+                eprintln!("{}", message);
+            }
+            error.exit_code()
+        }
+    }
+}
+
 /// Trait for all interpreter commands
 pub trait Command {
     fn run(&self, filename: &str) -> Result<()>;
 }
 
+/// Flags that always take a value, which may also be given as the next
+/// argument: `--name value`
+const VALUE_FLAGS: [&str; 5] = [
+    "allow-read",
+    "allow-write",
+    "coverage",
+    "filter",
+    "gc-threshold",
+];
+
+/// Flags given on the command line as `--name` or `--name=value`
+#[derive(Debug, Default)]
+pub struct CommandOptions {
+    flags: Vec<(String, Option<String>)>,
+}
+
+impl CommandOptions {
+    /// Split arguments into flags and positional arguments
+    pub fn parse(args: &[String]) -> (Self, Vec<String>) {
+        let mut flags = Vec::new();
+        let mut positional = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(flag) => match flag.split_once('=') {
+                    Some((name, value)) => flags.push((name.to_string(), Some(value.to_string()))),
+                    None if VALUE_FLAGS.contains(&flag) => {
+                        flags.push((flag.to_string(), args.next().cloned()))
+                    }
+                    None => flags.push((flag.to_string(), None)),
+                },
+                None => positional.push(arg.clone()),
+            }
+        }
+        (Self { flags }, positional)
+    }
+
+    pub fn has_flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|(flag, _)| flag == name)
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .find(|(flag, _)| flag == name)
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Every value given for a flag that may be repeated
+    pub fn values<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.flags
+            .iter()
+            .filter(move |(flag, _)| flag == name)
+            .filter_map(|(_, value)| value.as_deref())
+    }
+
+    /// Parse the value of `--name=<number>`, if given
+    pub fn parse_number(&self, name: &str) -> Result<Option<usize>> {
+        self.value(name)
+            .map(|value| {
+                value.parse::<usize>().map_err(|_| {
+                    InterpreterError::Usage(format!("Invalid value for --{}: {}", name, value))
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Whether errors are followed by the source they point at. `--no-snippet`
+/// turns this off for every command.
+static SNIPPETS: AtomicBool = AtomicBool::new(true);
+
 /// Common utilities for file and token operations
 pub struct CommandUtils;
 
@@ -30,22 +173,22 @@ impl CommandUtils {
     }
 
     /// Scan tokens and return both tokens and any errors found
-    pub fn scan_tokens(file_contents: &str) -> (Vec<Token>, Vec<ScannerError>) {
+    pub fn scan_tokens(filename: &str, file_contents: &str) -> (Vec<Token>, Vec<ScannerError>) {
         if file_contents.is_empty() {
             println!("EOF  null");
             return (vec![], vec![]);
         }
 
-        scan_tokens(file_contents)
+        scan_tokens(filename, file_contents)
     }
 
     /// Scan tokens with error checking - returns error if any scanner errors found
-    pub fn scan_tokens_checked(file_contents: &str) -> Result<Vec<Token>> {
-        let (tokens, errors) = Self::scan_tokens(file_contents);
+    pub fn scan_tokens_checked(filename: &str, file_contents: &str) -> Result<Vec<Token>> {
+        let (tokens, errors) = Self::scan_tokens(filename, file_contents);
 
         // Print all scanner errors
         for error in &errors {
-            eprintln!("{}", error);
+            Self::report_error(error, Some(error.span()), filename, file_contents);
         }
 
         if !errors.is_empty() {
@@ -61,8 +204,58 @@ impl CommandUtils {
         ParseStream::new(tokens)
     }
 
+    /// Parse a whole program, printing every syntax error found - returns the first one
+    pub fn parse_program(tokens: Vec<Token>, filename: &str, source: &str) -> Result<ProgramAst> {
+        let mut parse_stream = Self::create_parse_stream(tokens);
+        ProgramAst::parse_all(&mut parse_stream).map_err(|errors| {
+            for error in &errors {
+                Self::report_error(error, error.span(), filename, source);
+            }
+            errors.into_iter().next().unwrap().into()
+        })
+    }
+
+    /// Print an error to stderr. When the error points into `filename`, the
+    /// offending source follows it, underlined, unless snippets are off.
+    pub fn report_error(error: impl Display, span: Option<&Span>, filename: &str, source: &str) {
+        eprintln!("{}", error);
+        let Some(span) = span.filter(|span| span.file.as_ref() == filename) else {
+            return;
+        };
+        if SNIPPETS.load(Ordering::Relaxed) {
+            eprintln!("{}\n", span.snippet(source));
+        }
+    }
+
+    pub fn set_snippets(enabled: bool) {
+        SNIPPETS.store(enabled, Ordering::Relaxed);
+    }
+
     /// Log debug information to stderr
     pub fn log_debug(message: &str) {
         writeln!(io::stderr(), "{}", message).unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_value_may_be_the_next_argument() {
+        let directory = std::env::temp_dir().join("lox-coverage-flag-test");
+        fs::create_dir_all(&directory).unwrap();
+        let script = directory.join("script.lox");
+        fs::write(&script, "var covered = true;\n").unwrap();
+        let report = directory.join("out.lcov");
+        let _ = fs::remove_file(&report);
+
+        let args = ["interpreter", "run", "--coverage"]
+            .into_iter()
+            .map(str::to_string)
+            .chain([report.display().to_string(), script.display().to_string()])
+            .collect::<Vec<_>>();
+        assert_eq!(run(&args), 0);
+        assert!(fs::read_to_string(&report).unwrap().contains("DA:1,1"));
+    }
+}
@@ -9,7 +9,7 @@ impl Command for ParseCommand {
         CommandUtils::log_debug("Logs from your program will appear here!");
 
         let file_contents = CommandUtils::read_file(filename)?;
-        let tokens = CommandUtils::scan_tokens_checked(&file_contents)?;
+        let tokens = CommandUtils::scan_tokens_checked(filename, &file_contents)?;
 
         let mut parse_stream = CommandUtils::create_parse_stream(tokens);
 
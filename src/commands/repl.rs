@@ -0,0 +1,165 @@
+use std::io::{self, BufRead, Write};
+
+use crate::error::{InterpreterError, Result};
+use crate::evaluation::Session;
+use crate::syntax_analysis::{Declaration, Expression, ParseError, ParseStream};
+use crate::tokenizer::{scan_tokens, ScannerError};
+
+use super::CommandUtils;
+
+const PROMPT: &str = "> ";
+const CONTINUATION_PROMPT: &str = "... ";
+
+enum ReplInput {
+    Declarations(Vec<Declaration>),
+    Expression(Expression),
+    Incomplete,
+}
+
+pub struct ReplCommand;
+
+impl ReplCommand {
+    fn parse_declarations(
+        stream: &mut ParseStream,
+    ) -> std::result::Result<Vec<Declaration>, ParseError> {
+        let mut declarations = Vec::new();
+        while !stream.is_at_end() {
+            declarations.push(stream.parse::<Declaration>()?);
+        }
+        Ok(declarations)
+    }
+
+    fn parse_expression(stream: &mut ParseStream) -> Option<Expression> {
+        let expression = stream.parse::<Expression>().ok()?;
+        stream.is_at_end().then_some(expression)
+    }
+
+    /// Parses the buffered input as declarations, falling back to a bare
+    /// expression. Errors caused only by running out of input mean the user
+    /// has not finished typing yet, unless `allow_incomplete` is false.
+    fn parse_input(name: &str, source: &str, allow_incomplete: bool) -> Result<ReplInput> {
+        let (tokens, errors) = scan_tokens(name, source);
+        if allow_incomplete
+            && errors
+                .iter()
+                .any(|error| matches!(error, ScannerError::NotTerminatedString(_)))
+        {
+            return Ok(ReplInput::Incomplete);
+        }
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error.into());
+        }
+
+        let declaration_error =
+            match Self::parse_declarations(&mut ParseStream::new(tokens.clone())) {
+                Ok(declarations) => return Ok(ReplInput::Declarations(declarations)),
+                Err(error) => error,
+            };
+
+        if let Some(expression) = Self::parse_expression(&mut ParseStream::new(tokens)) {
+            return Ok(ReplInput::Expression(expression));
+        }
+
+        if allow_incomplete && declaration_error.is_at_end_of_input() {
+            return Ok(ReplInput::Incomplete);
+        }
+        Err(declaration_error.into())
+    }
+
+    fn execute(session: &Session, input: ReplInput) -> Result<()> {
+        match input {
+            ReplInput::Declarations(declarations) => session.run_declarations(&declarations)?,
+            ReplInput::Expression(expression) => {
+                let value = session.evaluate(&expression)?;
+                session.write_line(&value.to_string());
+            }
+            ReplInput::Incomplete => {}
+        }
+        Ok(())
+    }
+
+    fn prompt(text: &str) -> Result<()> {
+        let mut stdout = io::stdout();
+        write!(stdout, "{}", text)
+            .and_then(|_| stdout.flush())
+            .map_err(|err| InterpreterError::io_error("<stdout>".to_string(), err))
+    }
+
+    /// Reads lines from stdin until end of input, running each complete entry
+    /// against one long-lived session. Entries are named `<repl:N>` so errors
+    /// only show the source of the entry they come from.
+    pub fn run_interactive(&self) -> Result<()> {
+        let session = Session::new()?;
+        let mut stdin = io::stdin().lock();
+        let mut buffer = String::new();
+        let mut entry = 1;
+
+        loop {
+            Self::prompt(if buffer.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            })?;
+
+            let mut line = String::new();
+            let read = stdin
+                .read_line(&mut line)
+                .map_err(|err| InterpreterError::io_error("<stdin>".to_string(), err))?;
+            if read == 0 {
+                println!();
+                return Ok(());
+            }
+
+            // A blank line while continuing gives up on the pending entry and
+            // reports whatever error it has.
+            let abandon = !buffer.is_empty() && line.trim().is_empty();
+            buffer.push_str(&line);
+
+            let name = format!("<repl:{}>", entry);
+            let result = match Self::parse_input(&name, &buffer, !abandon) {
+                Ok(ReplInput::Incomplete) => continue,
+                Ok(input) => Self::execute(&session, input),
+                Err(error) => Err(error),
+            };
+
+            if let Err(error) = result {
+                CommandUtils::report_error(&error, error.span(), &name, &buffer);
+            }
+            buffer.clear();
+            entry += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::BufferSink;
+
+    #[test]
+    fn test_expression_value_goes_to_output_sink() {
+        let session = Session::new().unwrap();
+        let output = BufferSink::new();
+        session.set_output(Box::new(output.clone()));
+        for entry in ["var x = 20;", "x + 1", "print x;"] {
+            let input = ReplCommand::parse_input("<repl:1>", entry, false).unwrap();
+            ReplCommand::execute(&session, input).unwrap();
+        }
+        assert_eq!(output.contents(), "21\n20\n");
+    }
+
+    #[test]
+    fn test_session_survives_assigning_undefined_variable() {
+        let session = Session::new().unwrap();
+        let output = BufferSink::new();
+        session.set_output(Box::new(output.clone()));
+        let run = |entry| {
+            let input = ReplCommand::parse_input("<repl:1>", entry, false).unwrap();
+            ReplCommand::execute(&session, input)
+        };
+        run("var x = 1;").unwrap();
+        assert!(run("nope = 1;").is_err());
+        run("print x;").unwrap();
+        assert_eq!(output.contents(), "1\n");
+    }
+}
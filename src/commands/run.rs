@@ -1,15 +1,129 @@
-use super::{Command, CommandUtils};
+use std::fs;
+
+use super::{Command, CommandOptions, CommandUtils};
 use crate::{
-    error::Result as CommandResult,
-    evaluation::{Program, RuntimeError},
+    error::{InterpreterError, Result as CommandResult},
+    evaluation::{
+        BytecodeProgram, Coverage, Profiler, Program, RunHook, RuntimeError, SandboxPolicy,
+        DEFAULT_GC_THRESHOLD,
+    },
     syntax_analysis::ProgramAst,
 };
 
-pub struct RunCommand;
+/// Where `--profile` writes the collapsed call stacks unless given a path.
+const DEFAULT_PROFILE_PATH: &str = "profile.folded";
+
+pub struct RunCommand {
+    gc_threshold: Option<usize>,
+    use_vm: bool,
+    sandbox: SandboxPolicy,
+    script_arguments: Vec<String>,
+    /// Where to write the collapsed call stacks when profiling.
+    profile: Option<String>,
+    /// Where to write the LCOV report when measuring coverage.
+    coverage: Option<String>,
+}
 
 impl RunCommand {
-    fn run_program(program_ast: ProgramAst) -> Result<(), RuntimeError> {
-        let mut program = Program::new_with_context(program_ast)?;
+    /// Supports `--gc-threshold=<allocations>`, where `0` turns automatic
+    /// collection off, `--vm` to run on the bytecode VM, which doesn't support
+    /// `import`, `throw` or `try`, and `--allow-read=<dir>` and
+    /// `--allow-write=<dir>`, which may be repeated, to let the file natives
+    /// access a directory. `--profile[=<path>]` prints call counts, call times
+    /// and statements run per line to stderr on exit and writes the call stacks
+    /// for flame graph tools to the path, `profile.folded` by default.
+    /// `--coverage=<path>` writes which lines and branches ran to the path as
+    /// LCOV. `script_arguments` are the arguments after the file name, which
+    /// the program reads with `args`.
+    pub fn new(options: &CommandOptions, script_arguments: &[String]) -> CommandResult<Self> {
+        let mut sandbox = SandboxPolicy::default();
+        for directory in options.values("allow-read") {
+            sandbox
+                .allow_read(directory)
+                .map_err(|error| Self::invalid_directory("allow-read", directory, error))?;
+        }
+        for directory in options.values("allow-write") {
+            sandbox
+                .allow_write(directory)
+                .map_err(|error| Self::invalid_directory("allow-write", directory, error))?;
+        }
+        let gc_threshold = match options.parse_number("gc-threshold")? {
+            Some(0) => None,
+            Some(threshold) => Some(threshold),
+            None => Some(DEFAULT_GC_THRESHOLD),
+        };
+        let profile = options.has_flag("profile").then(|| {
+            options
+                .value("profile")
+                .unwrap_or(DEFAULT_PROFILE_PATH)
+                .to_string()
+        });
+        let coverage = options.value("coverage").map(str::to_string);
+        if options.has_flag("coverage") && coverage.is_none() {
+            return Err(InterpreterError::Usage(
+                "--coverage needs a path: --coverage <path>".to_string(),
+            ));
+        }
+        let use_vm = options.has_flag("vm");
+        for (flag, given) in [
+            ("profile", profile.is_some()),
+            ("coverage", coverage.is_some()),
+        ] {
+            if use_vm && given {
+                return Err(InterpreterError::Usage(format!(
+                    "--{} is not supported with --vm",
+                    flag
+                )));
+            }
+        }
+        if profile.is_some() && coverage.is_some() {
+            return Err(InterpreterError::Usage(
+                "--profile and --coverage can't be used together".to_string(),
+            ));
+        }
+        Ok(Self {
+            gc_threshold,
+            use_vm,
+            sandbox,
+            script_arguments: script_arguments.to_vec(),
+            profile,
+            coverage,
+        })
+    }
+
+    fn write_report(path: &str, contents: String) -> CommandResult<()> {
+        fs::write(path, contents).map_err(|err| {
+            let error = InterpreterError::write_error(path.to_string(), err);
+            eprintln!("{}", error);
+            error
+        })
+    }
+
+    fn invalid_directory(flag: &str, directory: &str, error: std::io::Error) -> InterpreterError {
+        InterpreterError::Usage(format!(
+            "Invalid value for --{}: {}: {}",
+            flag, directory, error
+        ))
+    }
+
+    fn run_program(
+        &self,
+        program_ast: ProgramAst,
+        hook: Option<Box<dyn RunHook>>,
+    ) -> Result<(), RuntimeError> {
+        if self.use_vm {
+            let mut program = BytecodeProgram::new(&program_ast)?;
+            program.set_sandbox(self.sandbox.clone());
+            program.set_script_arguments(self.script_arguments.clone());
+            return program.run();
+        }
+        let mut program = match hook {
+            Some(hook) => Program::new_with_hook(program_ast, hook)?,
+            None => Program::new_with_context(program_ast)?,
+        };
+        program.set_gc_threshold(self.gc_threshold);
+        program.set_sandbox(self.sandbox.clone());
+        program.set_script_arguments(self.script_arguments.clone());
         program.run()?;
         Ok(())
     }
@@ -20,19 +134,29 @@ impl Command for RunCommand {
         CommandUtils::log_debug("Logs from your program will appear here!");
 
         let file_contents = CommandUtils::read_file(filename)?;
-        let tokens = CommandUtils::scan_tokens_checked(&file_contents)?;
-
-        let mut parse_stream = CommandUtils::create_parse_stream(tokens);
-
-        match parse_stream.parse::<ProgramAst>() {
-            Ok(program) => Self::run_program(program).map_err(|e| {
-                eprintln!("{}", e);
-                e.into()
-            }),
-            Err(e) => {
-                eprintln!("{}", e);
-                Err(e.into())
-            }
+        let tokens = CommandUtils::scan_tokens_checked(filename, &file_contents)?;
+
+        let program = CommandUtils::parse_program(tokens, filename, &file_contents)?;
+
+        let profiler = self.profile.as_ref().map(|_| Profiler::new());
+        let coverage = self.coverage.as_ref().map(|_| Coverage::new());
+        let hook: Option<Box<dyn RunHook>> = match (&profiler, &coverage) {
+            (Some(profiler), _) => Some(Box::new(profiler.clone())),
+            (_, Some(coverage)) => Some(Box::new(coverage.clone())),
+            _ => None,
+        };
+        let result = self.run_program(program, hook);
+        if let (Some(profiler), Some(path)) = (profiler, &self.profile) {
+            profiler.finish();
+            eprint!("{}", profiler.report());
+            Self::write_report(path, profiler.collapsed_stacks())?;
+        }
+        if let (Some(coverage), Some(path)) = (coverage, &self.coverage) {
+            Self::write_report(path, coverage.lcov())?;
         }
+        result.map_err(|e| {
+            CommandUtils::report_error(&e, e.span(), filename, &file_contents);
+            e.into()
+        })
     }
 }
@@ -0,0 +1,364 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Command, CommandOptions, CommandUtils};
+use crate::error::{InterpreterError, Result};
+use crate::evaluation::{BufferSink, BytecodeProgram, LinesSource, Session};
+use crate::syntax_analysis::{ParseStream, ProgramAst};
+use crate::tokenizer::scan_tokens;
+
+const EXPECT_OUTPUT: &str = "// expect: ";
+const EXPECT_RUNTIME_ERROR: &str = "// expect runtime error: ";
+const INPUT: &str = "// stdin:";
+
+/// Runs every `*.lox` file under a directory and checks what it prints, and
+/// how it exits, against the expectations written in its comments:
+/// - `// expect: <text>` for a line of output
+/// - `// expect runtime error: <message>` for a runtime error raised on that
+///   line, which exits with code 70
+/// - `// Error at '<lexeme>': <message>` for a compile error on that line,
+///   or `// [line <n>] Error ...` for one on another line, exiting with 65
+///
+/// `// stdin: <text>` gives the program a line of input. Programs only read
+/// the lines their file gives them, in order, never the runner's own stdin.
+///
+/// Supports `--filter=<text>` to only run the files whose path contains it,
+/// and `--vm` to run them on the bytecode VM.
+pub struct TestCommand {
+    filter: Option<String>,
+    use_vm: bool,
+}
+
+/// What a test file is expected to do
+#[derive(Debug, Default)]
+struct Expectations {
+    /// Lines of output, with the line each is expected on
+    output: Vec<(usize, String)>,
+    /// The first line of each compile error, as it is reported
+    compile_errors: Vec<String>,
+    /// The message of the runtime error and the line it is raised at
+    runtime_error: Option<(usize, String)>,
+    /// The lines of input the program reads
+    input: Vec<String>,
+}
+
+/// What running a test file did
+#[derive(Debug, Default)]
+struct Outcome {
+    output: Vec<String>,
+    /// The first line of each error reported
+    errors: Vec<String>,
+    /// The line the runtime error was raised at, when it points at one
+    error_line: Option<usize>,
+    exit_code: i32,
+}
+
+impl TestCommand {
+    pub fn new(options: &CommandOptions) -> Self {
+        Self {
+            filter: options.value("filter").map(str::to_string),
+            use_vm: options.has_flag("vm"),
+        }
+    }
+
+    /// The `*.lox` files at or under `path`, in path order.
+    fn discover(path: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+        if !path.is_dir() {
+            files.push(path.to_path_buf());
+            return Ok(());
+        }
+        let read_error =
+            |error| InterpreterError::io_error(path.to_string_lossy().to_string(), error);
+        let mut entries = fs::read_dir(path)
+            .map_err(read_error)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<Vec<_>>>()
+            .map_err(read_error)?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() {
+                Self::discover(&entry, files)?;
+            } else if entry
+                .extension()
+                .is_some_and(|extension| extension == "lox")
+            {
+                files.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs one file, returning why it failed if it did.
+    fn test_file(path: &Path, use_vm: bool) -> Result<Vec<String>> {
+        let filename = path.to_string_lossy();
+        let source = CommandUtils::read_file(&filename)?;
+        let expectations = Expectations::parse(&source);
+        let outcome = Outcome::run(&filename, &source, expectations.input.clone(), use_vm);
+        Ok(expectations.check(&outcome))
+    }
+}
+
+impl Command for TestCommand {
+    fn run(&self, path: &str) -> Result<()> {
+        let mut files = Vec::new();
+        Self::discover(Path::new(path), &mut files)?;
+        if let Some(filter) = &self.filter {
+            files.retain(|file| file.to_string_lossy().contains(filter.as_str()));
+        }
+
+        let mut failed = 0;
+        for file in &files {
+            let failures = match Self::test_file(file, self.use_vm) {
+                Ok(failures) => failures,
+                Err(error) => vec![error.to_string()],
+            };
+            if failures.is_empty() {
+                continue;
+            }
+            failed += 1;
+            println!("FAIL {}", file.display());
+            for failure in failures {
+                for line in failure.lines() {
+                    println!("     {}", line);
+                }
+            }
+        }
+
+        println!("{} passed, {} failed", files.len() - failed, failed);
+        match failed {
+            0 => Ok(()),
+            _ => Err(InterpreterError::TestsFailed(failed)),
+        }
+    }
+}
+
+impl Expectations {
+    fn parse(source: &str) -> Self {
+        let mut expectations = Self::default();
+        for (index, text) in source.lines().enumerate() {
+            let line = index + 1;
+            if let Some((_, output)) = text.split_once(EXPECT_OUTPUT) {
+                expectations.output.push((line, output.to_string()));
+            } else if let Some((_, message)) = text.split_once(EXPECT_RUNTIME_ERROR) {
+                expectations.runtime_error = Some((line, message.to_string()));
+            } else if let Some((_, input)) = text.split_once(INPUT) {
+                let input = input.strip_prefix(' ').unwrap_or(input);
+                expectations.input.push(input.to_string());
+            } else if let Some((_, error)) = text.split_once("// [line ") {
+                expectations.compile_errors.push(format!("[line {}", error));
+            } else if let Some((_, error)) = text.split_once("// Error") {
+                let error = format!("[line {}] Error{}", line, error);
+                expectations.compile_errors.push(error);
+            }
+        }
+        expectations
+    }
+
+    fn exit_code(&self) -> i32 {
+        match (&self.runtime_error, self.compile_errors.is_empty()) {
+            (Some(_), _) => 70,
+            (None, false) => 65,
+            (None, true) => 0,
+        }
+    }
+
+    /// Why `outcome` doesn't meet the expectations, if it doesn't.
+    fn check(&self, outcome: &Outcome) -> Vec<String> {
+        let mut failures = Vec::new();
+        let expected_output = self.output.iter().map(|(_, text)| text.as_str());
+        if !expected_output.eq(outcome.output.iter().map(String::as_str)) {
+            failures.push(self.output_diff(&outcome.output));
+        }
+
+        match &self.runtime_error {
+            Some((line, message)) => match outcome.errors.first() {
+                Some(error) if error != message => failures.push(format!(
+                    "Expected runtime error '{}' and got '{}'.",
+                    message, error
+                )),
+                Some(_) => match outcome.error_line {
+                    Some(error_line) if error_line != *line => failures.push(format!(
+                        "Expected runtime error on line {} and got it on line {}.",
+                        line, error_line
+                    )),
+                    _ => {}
+                },
+                None => failures.push(format!(
+                    "Expected runtime error '{}' and got none.",
+                    message
+                )),
+            },
+            None => {
+                for error in &self.compile_errors {
+                    if !outcome.errors.contains(error) {
+                        failures.push(format!("Missing expected error: {}", error));
+                    }
+                }
+                for error in &outcome.errors {
+                    if !self.compile_errors.contains(error) {
+                        failures.push(format!("Unexpected error: {}", error));
+                    }
+                }
+            }
+        }
+
+        if outcome.exit_code != self.exit_code() {
+            failures.push(format!(
+                "Expected exit code {} and got {}.",
+                self.exit_code(),
+                outcome.exit_code
+            ));
+        }
+        failures
+    }
+
+    /// The expected output against what was printed, line by line: `-` for
+    /// what was expected and `+` for what was printed instead.
+    fn output_diff(&self, output: &[String]) -> String {
+        let mut diff = String::from("Output differs:");
+        for index in 0..self.output.len().max(output.len()) {
+            let expected = self.output.get(index);
+            let actual = output.get(index);
+            match (expected, actual) {
+                (Some((_, expected)), Some(actual)) if expected == actual => {
+                    diff.push_str(&format!("\n    {}", actual));
+                }
+                _ => {
+                    if let Some((line, expected)) = expected {
+                        diff.push_str(&format!("\n  - {}  (line {})", expected, line));
+                    }
+                    if let Some(actual) = actual {
+                        diff.push_str(&format!("\n  + {}", actual));
+                    }
+                }
+            }
+        }
+        diff
+    }
+}
+
+impl Outcome {
+    /// Runs `source` the way the `run` command would, on a fresh session or
+    /// VM reading `input`.
+    fn run(filename: &str, source: &str, input: Vec<String>, use_vm: bool) -> Self {
+        let mut outcome = Self::default();
+        let (tokens, scanner_errors) = scan_tokens(filename, source);
+        if !scanner_errors.is_empty() {
+            outcome.fail(scanner_errors.into_iter().map(InterpreterError::from));
+            return outcome;
+        }
+        let program = match ProgramAst::parse_all(&mut ParseStream::new(tokens)) {
+            Ok(program) => program,
+            Err(errors) => {
+                outcome.fail(errors.into_iter().map(InterpreterError::from));
+                return outcome;
+            }
+        };
+
+        let output = BufferSink::new();
+        let input = Box::new(LinesSource::new(input));
+        let result = if use_vm {
+            BytecodeProgram::new(&program).and_then(|mut program| {
+                program.set_output(Box::new(output.clone()));
+                program.set_input(input);
+                program.run()
+            })
+        } else {
+            Session::new().and_then(|session| {
+                session.set_output(Box::new(output.clone()));
+                session.set_input(input);
+                let runnable = session.build_program(&program)?;
+                session.run(runnable.as_ref())
+            })
+        };
+        outcome.output = output.contents().lines().map(str::to_string).collect();
+        if let Err(error) = result {
+            outcome.error_line = error.span().map(|span| span.line);
+            outcome.fail(std::iter::once(error.into()));
+        }
+        outcome
+    }
+
+    fn fail(&mut self, errors: impl Iterator<Item = InterpreterError>) {
+        for error in errors {
+            self.exit_code = error.exit_code();
+            let message = error.to_string();
+            self.errors
+                .push(message.lines().next().unwrap_or_default().to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_what_differs() {
+        let source = "\
+print 1; // expect: 1
+print 2; // expect: 3
+var a = 1;
+{ var a = a; } // Error at 'a': Can't read local variable in its own initializer.
+";
+        let expectations = Expectations::parse(source);
+        let outcome = Outcome::run("test.lox", source, Vec::new(), false);
+        assert_eq!(
+            expectations.check(&outcome),
+            ["Output differs:\n  - 1  (line 1)\n  - 3  (line 2)".to_string()]
+        );
+
+        let source = "print 1;\nprint nope; // expect runtime error: Undefined variable 'nope'.\n";
+        let outcome = Outcome::run("test.lox", source, Vec::new(), false);
+        assert_eq!(outcome.exit_code, 70);
+        assert_eq!(
+            Expectations::parse(source).check(&outcome),
+            ["Output differs:\n  + 1".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_language_suite_passes() {
+        let suite = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/lox");
+        let mut files = Vec::new();
+        TestCommand::discover(&suite, &mut files).unwrap();
+        assert!(!files.is_empty());
+        for file in files {
+            let failures = TestCommand::test_file(&file, false).unwrap();
+            assert!(failures.is_empty(), "{}: {:?}", file.display(), failures);
+        }
+    }
+
+    #[test]
+    fn test_backends_agree_on_language_suite() {
+        // The VM doesn't support `import`, `throw` or `try`
+        let tree_walker_only = ["modules", "exceptions"];
+        let suite = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/lox");
+        let mut files = Vec::new();
+        TestCommand::discover(&suite, &mut files).unwrap();
+        files.retain(|file| {
+            let path = file.strip_prefix(&suite).unwrap();
+            !tree_walker_only
+                .iter()
+                .any(|skipped| path.starts_with(skipped))
+        });
+        assert!(!files.is_empty());
+        for file in files {
+            let filename = file.to_string_lossy();
+            let source = fs::read_to_string(&file).unwrap();
+            let input = Expectations::parse(&source).input;
+            let tree_walker = Outcome::run(&filename, &source, input.clone(), false);
+            let vm = Outcome::run(&filename, &source, input, true);
+            assert_eq!(
+                (
+                    tree_walker.output,
+                    tree_walker.errors,
+                    tree_walker.exit_code
+                ),
+                (vm.output, vm.errors, vm.exit_code),
+                "{}",
+                file.display()
+            );
+        }
+    }
+}
@@ -8,10 +8,10 @@ impl Command for TokenizeCommand {
         CommandUtils::log_debug("Logs from your program will appear here!");
 
         let file_contents = CommandUtils::read_file(filename)?;
-        let (tokens, errors) = CommandUtils::scan_tokens(&file_contents);
+        let (tokens, errors) = CommandUtils::scan_tokens(filename, &file_contents);
 
         for error in &errors {
-            eprintln!("{}", error);
+            CommandUtils::report_error(error, Some(error.span()), filename, &file_contents);
         }
         for token in &tokens {
             println!("{:?}", token);
@@ -2,8 +2,9 @@ use thiserror::Error;
 
 // Import the module-specific errors from their public exports
 use crate::evaluation::{ResolverError, RuntimeError};
+use crate::lsp::LspError;
 use crate::syntax_analysis::ParseError;
-use crate::tokenizer::ScannerError;
+use crate::tokenizer::{ScannerError, Span};
 
 /// Unified error type for the entire interpreter
 #[derive(Error, Debug)]
@@ -20,6 +21,10 @@ pub enum InterpreterError {
     #[error("{0}")]
     Runtime(#[from] RuntimeError),
 
+    /// Invalid command line usage
+    #[error("{0}")]
+    Usage(String),
+
     /// File I/O errors
     #[error("Failed to read file '{filename}': {source}")]
     Io {
@@ -27,6 +32,26 @@ pub enum InterpreterError {
         #[source]
         source: std::io::Error,
     },
+
+    /// Errors writing a file back, as `fmt` does
+    #[error("Failed to write file '{filename}': {source}")]
+    Write {
+        filename: String,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// A file `fmt --check` would change
+    #[error("{0} is not formatted")]
+    Unformatted(String),
+
+    /// Files the `test` command ran that didn't do what they expect
+    #[error("{0} tests failed")]
+    TestsFailed(usize),
+
+    /// The language server lost its connection to the client
+    #[error("{0}")]
+    Lsp(#[from] LspError),
 }
 
 impl InterpreterError {
@@ -36,8 +61,29 @@ impl InterpreterError {
             InterpreterError::Scanner(_) => 65,
             InterpreterError::Parse(_) => 65,
             InterpreterError::Runtime(RuntimeError::ResolverError(_)) => 65,
+            InterpreterError::Runtime(RuntimeError::ImportError(_)) => 65,
             InterpreterError::Runtime(_) => 70,
-            InterpreterError::Io { .. } => 1,
+            InterpreterError::Usage(_) => 64,
+            InterpreterError::Io { .. }
+            | InterpreterError::Write { .. }
+            | InterpreterError::Unformatted(_)
+            | InterpreterError::TestsFailed(_)
+            | InterpreterError::Lsp(_) => 1,
+        }
+    }
+
+    /// The source location the error points at, if any
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            InterpreterError::Scanner(error) => Some(error.span()),
+            InterpreterError::Parse(error) => error.span(),
+            InterpreterError::Runtime(error) => error.span(),
+            InterpreterError::Usage(_)
+            | InterpreterError::Io { .. }
+            | InterpreterError::Write { .. }
+            | InterpreterError::Unformatted(_)
+            | InterpreterError::TestsFailed(_)
+            | InterpreterError::Lsp(_) => None,
         }
     }
 
@@ -45,6 +91,11 @@ impl InterpreterError {
     pub fn io_error(filename: String, source: std::io::Error) -> Self {
         Self::Io { filename, source }
     }
+
+    /// Create a write error with context
+    pub fn write_error(filename: String, source: std::io::Error) -> Self {
+        Self::Write { filename, source }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, InterpreterError>;
@@ -56,7 +107,8 @@ mod tests {
     #[test]
     fn test_exit_codes() {
         // Test that error types map to correct exit codes
-        let scanner_err = InterpreterError::Scanner(ScannerError::UnexpectedCharacter('x', 1));
+        let scanner_err =
+            InterpreterError::Scanner(ScannerError::UnexpectedCharacter('x', Span::default()));
         assert_eq!(scanner_err.exit_code(), 65);
 
         let io_err = InterpreterError::io_error(
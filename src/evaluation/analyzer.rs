@@ -0,0 +1,910 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::common::{Visitable, VisitorWithContext};
+use crate::evaluation::run::get_native_functions;
+use crate::evaluation::runtime_value::{Result, RuntimeError};
+use crate::evaluation::symbols::{Definition, Reference, SymbolKind, Symbols};
+use crate::syntax_analysis::{
+    AccessorOrArgumentsType, Assignment, Block, BreakStatement, Call, ClassDeclaration, Comparison,
+    ContinueStatement, Declaration, DeclarationType, Equality, ExprStatement, Factor, ForStatement,
+    ForStatementType, Function, FunctionDeclaration, IfStatement, LambdaBody, LogicalAnd,
+    LogicalOr, Parameters, PrimaryExpression, PrimaryExpressionType, PrintStatement, ProgramAst,
+    ReturnStatement, Statement, StatementType, Term, ThrowStatement, TryStatement, UnaryExpression,
+    UnaryExpressionSelf, UnaryExpressionType, VarDeclaration, WhileStatement,
+};
+use crate::tokenizer::{Span, Token};
+
+/// Code that is valid but most likely not what was meant. Unlike a
+/// `ResolverError`, a warning never stops a program from running.
+#[derive(Error, Debug)]
+pub enum Warning {
+    #[error("[line {}] Warning at '{0}': Local variable is never used.", .1.line)]
+    UnusedVariable(String, Span),
+
+    #[error("[line {}] Warning at '{0}': Parameter is never used.", .1.line)]
+    UnusedParameter(String, Span),
+
+    #[error("[line {}] Warning at '{0}': Code after '{0}' is unreachable.", .1.line)]
+    UnreachableCode(&'static str, Span),
+
+    #[error("[line {}] Warning at '{0}': Shadows a local variable of an enclosing scope.", .1.line)]
+    ShadowedLocal(String, Span),
+
+    #[error("[line {}] Warning at '{0}': Assignment to an undeclared global variable.", .1.line)]
+    UndeclaredGlobal(String, Span),
+
+    #[error("[line {}] Warning at '{0}': Expected {1} arguments but got {2}.", .3.line)]
+    ArityMismatch(String, usize, usize, Span),
+}
+
+impl Warning {
+    pub fn span(&self) -> &Span {
+        match self {
+            Warning::UnusedVariable(_, span)
+            | Warning::UnusedParameter(_, span)
+            | Warning::UnreachableCode(_, span)
+            | Warning::ShadowedLocal(_, span)
+            | Warning::UndeclaredGlobal(_, span)
+            | Warning::ArityMismatch(_, _, _, span) => span,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum LocalKind {
+    Variable,
+    Parameter,
+    /// A local function and its arity, until something is assigned to it.
+    Function(Option<usize>),
+    Class,
+}
+
+struct Local {
+    name: String,
+    span: Span,
+    kind: LocalKind,
+    used: bool,
+    definition: usize,
+}
+
+/// Walks a program that already resolved, with the scopes the `Resolver`
+/// gives it, and collects `Warning`s and the program's `Symbols`. Only local
+/// scopes are tracked: globals can be redefined at any time, so nothing is
+/// assumed about them beyond which names the program declares.
+pub struct Analyzer;
+
+pub struct AnalyzerContext {
+    /// The first definition of each global name
+    globals: HashMap<String, usize>,
+    scopes: RefCell<Vec<Vec<Local>>>,
+    warnings: RefCell<Vec<Warning>>,
+    symbols: RefCell<Symbols>,
+    /// Where the last name or brace walked over ends
+    last_end: Cell<usize>,
+}
+
+impl Analyzer {
+    /// Returns the warnings for `program`, in source order.
+    pub fn analyze(program: &ProgramAst) -> Result<Vec<Warning>> {
+        let context = Self::walk(program)?;
+        let mut warnings = context.warnings.take();
+        warnings.sort_by_key(|warning| warning.span().start);
+        Ok(warnings)
+    }
+
+    /// Returns the declarations in `program` and the uses of their names.
+    pub fn symbols(program: &ProgramAst) -> Result<Symbols> {
+        Ok(Self::walk(program)?.symbols.take())
+    }
+
+    fn walk(program: &ProgramAst) -> Result<AnalyzerContext> {
+        let context = AnalyzerContext::new(program);
+        program.accept_with_context(&Self, &context)?;
+        Ok(context)
+    }
+
+    /// Analyzes a function's body in a scope of its own ending at `end`, the
+    /// closing brace of the body when it has one.
+    fn analyze_function(
+        parameters: &Parameters,
+        analyze_body: impl FnOnce(&AnalyzerContext) -> Result<()>,
+        end: Option<&Token>,
+        context: &AnalyzerContext,
+    ) -> Result<()> {
+        // Parameters share the body's scope, as the resolver has it.
+        context.enter_scope();
+        for parameter in &parameters.parameters {
+            let token = &parameter.token;
+            context.declare(token, LocalKind::Parameter, token.lexeme.clone());
+        }
+        analyze_body(context)?;
+        context.exit_scope(end);
+        Ok(())
+    }
+
+    /// How a function is shown, as in `add(a, b)`.
+    fn signature(function: &Function) -> String {
+        let name = function
+            .token_list
+            .first()
+            .map_or("", |token| token.lexeme.as_str());
+        let parameters = function
+            .parameters
+            .parameters
+            .iter()
+            .map(|parameter| parameter.token.lexeme.as_str())
+            .collect::<Vec<_>>();
+        format!("{}({})", name, parameters.join(", "))
+    }
+
+    fn class_detail(node: &ClassDeclaration, name: &str) -> String {
+        match &node.super_class.super_class {
+            Some(super_class) => format!("class {} < {}", name, super_class.token.lexeme),
+            None => format!("class {}", name),
+        }
+    }
+
+    /// Analyzes a block's declarations, warning once when a `return`, `break`
+    /// or `continue` is followed by more code.
+    fn analyze_declarations(
+        declarations: &[(impl Sized, Declaration)],
+        context: &AnalyzerContext,
+    ) -> Result<()> {
+        let mut reported = false;
+        for (position, (_, declaration)) in declarations.iter().enumerate() {
+            declaration.accept_with_context(&Self, context)?;
+            let is_last = position + 1 == declarations.len();
+            if reported || is_last {
+                continue;
+            }
+            if let Some((keyword, span)) = Self::jump_keyword(declaration) {
+                context.warn(Warning::UnreachableCode(keyword, span.clone()));
+                reported = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn jump_keyword(declaration: &Declaration) -> Option<(&'static str, &Span)> {
+        let DeclarationType::Statement(statement) = &declaration.token_type else {
+            return None;
+        };
+        let (keyword, token_list) = match &statement.token_type {
+            StatementType::ReturnStatement(node) => ("return", &node.token_list),
+            StatementType::BreakStatement(node) => ("break", &node.token_list),
+            StatementType::ContinueStatement(node) => ("continue", &node.token_list),
+            StatementType::ThrowStatement(node) => ("throw", &node.token_list),
+            _ => return None,
+        };
+        token_list.first().map(|token| (keyword, &token.span))
+    }
+}
+
+impl AnalyzerContext {
+    fn new(program: &ProgramAst) -> Self {
+        let mut definitions: Vec<Definition> = get_native_functions()
+            .into_iter()
+            .map(|(name, _)| Definition {
+                name: name.to_string(),
+                kind: SymbolKind::Native,
+                span: None,
+                detail: format!("fun {}", name),
+                scope: 0..usize::MAX,
+            })
+            .collect();
+        for (_, declaration) in &program.statements {
+            let (kind, token, detail) = match &declaration.token_type {
+                DeclarationType::VarDeclaration(var) => {
+                    let Some(token) = var.token_list.get(1) else {
+                        continue;
+                    };
+                    let detail = format!("var {}", token.lexeme);
+                    (SymbolKind::GlobalVariable, token, detail)
+                }
+                DeclarationType::FunctionDeclaration(function) => {
+                    let Some(token) = function.function.token_list.first() else {
+                        continue;
+                    };
+                    let detail = format!("fun {}", Analyzer::signature(&function.function));
+                    (SymbolKind::Function, token, detail)
+                }
+                DeclarationType::ClassDeclaration(class) => {
+                    let Some(token) = class.token_list.get(1) else {
+                        continue;
+                    };
+                    let detail = Analyzer::class_detail(class, &token.lexeme);
+                    (SymbolKind::Class, token, detail)
+                }
+                DeclarationType::ImportDeclaration(import) => {
+                    let (Some(alias), Some(path)) = (&import.alias.alias, import.token_list.get(1))
+                    else {
+                        continue;
+                    };
+                    let detail = format!("import {} as {}", path.lexeme, alias.token.lexeme);
+                    (SymbolKind::GlobalVariable, &alias.token, detail)
+                }
+                _ => continue,
+            };
+            definitions.push(Definition {
+                name: token.lexeme.clone(),
+                kind,
+                span: Some(token.span.clone()),
+                detail,
+                scope: 0..usize::MAX,
+            });
+        }
+
+        let mut globals = HashMap::new();
+        for (index, definition) in definitions.iter().enumerate() {
+            globals.entry(definition.name.clone()).or_insert(index);
+        }
+        Self {
+            globals,
+            scopes: RefCell::new(Vec::new()),
+            warnings: RefCell::new(Vec::new()),
+            symbols: RefCell::new(Symbols {
+                definitions,
+                references: Vec::new(),
+            }),
+            last_end: Cell::new(0),
+        }
+    }
+
+    fn touch(&self, span: &Span) {
+        self.last_end.set(self.last_end.get().max(span.end));
+    }
+
+    fn refer(&self, span: &Span, definition: usize) {
+        self.touch(span);
+        self.symbols.borrow_mut().references.push(Reference {
+            span: span.clone(),
+            definition,
+        });
+    }
+
+    /// Adds a definition whose scope starts after its name.
+    fn define(&self, token: &Token, kind: SymbolKind, detail: String) -> usize {
+        self.touch(&token.span);
+        let mut symbols = self.symbols.borrow_mut();
+        symbols.definitions.push(Definition {
+            name: token.lexeme.clone(),
+            kind,
+            span: Some(token.span.clone()),
+            detail,
+            scope: token.span.end..usize::MAX,
+        });
+        symbols.definitions.len() - 1
+    }
+
+    fn warn(&self, warning: Warning) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    fn enter_scope(&self) {
+        self.scopes.borrow_mut().push(Vec::new());
+    }
+
+    /// Leaves the innermost scope, which ends with `end` or else with the
+    /// last name walked over.
+    fn exit_scope(&self, end: Option<&Token>) {
+        let Some(scope) = self.scopes.borrow_mut().pop() else {
+            return;
+        };
+        if let Some(end) = end {
+            self.touch(&end.span);
+        }
+        let mut symbols = self.symbols.borrow_mut();
+        for local in &scope {
+            symbols.definitions[local.definition].scope.end = self.last_end.get();
+        }
+        drop(symbols);
+
+        for local in scope {
+            if local.used || local.name.starts_with('_') {
+                continue;
+            }
+            match local.kind {
+                LocalKind::Variable => self.warn(Warning::UnusedVariable(local.name, local.span)),
+                LocalKind::Parameter => self.warn(Warning::UnusedParameter(local.name, local.span)),
+                LocalKind::Function(_) | LocalKind::Class => {}
+            }
+        }
+    }
+
+    /// Declares a local in the innermost scope, shown as `detail`. At the top
+    /// level there is no scope and the name is a global, which the context
+    /// already knows.
+    fn declare(&self, token: &Token, kind: LocalKind, detail: String) {
+        let mut scopes = self.scopes.borrow_mut();
+        let Some((scope, enclosing)) = scopes.split_last_mut() else {
+            // A global declared again refers to its first declaration
+            if let Some(&definition) = self.globals.get(&token.lexeme) {
+                let first = self.symbols.borrow().definitions[definition]
+                    .span
+                    .as_ref()
+                    .map(|span| span.start);
+                if first != Some(token.span.start) {
+                    self.refer(&token.span, definition);
+                }
+            }
+            return;
+        };
+        let shadows = enclosing
+            .iter()
+            .any(|scope| scope.iter().any(|local| local.name == token.lexeme));
+        if shadows {
+            self.warn(Warning::ShadowedLocal(
+                token.lexeme.clone(),
+                token.span.clone(),
+            ));
+        }
+        let symbol_kind = match kind {
+            LocalKind::Variable => SymbolKind::LocalVariable,
+            LocalKind::Parameter => SymbolKind::Parameter,
+            LocalKind::Function(_) => SymbolKind::Function,
+            LocalKind::Class => SymbolKind::Class,
+        };
+        scope.push(Local {
+            name: token.lexeme.clone(),
+            span: token.span.clone(),
+            kind,
+            used: false,
+            definition: self.define(token, symbol_kind, detail),
+        });
+    }
+
+    /// Runs `f` on the innermost local named `name`, if there is one.
+    fn with_local<T>(&self, name: &str, f: impl FnOnce(&mut Local) -> T) -> Option<T> {
+        self.scopes
+            .borrow_mut()
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.iter_mut().rev().find(|local| local.name == name))
+            .map(f)
+    }
+
+    /// Marks a local as read, returning the arity when it is a known function.
+    fn read(&self, token: &Token) -> Option<usize> {
+        let local = self.with_local(&token.lexeme, |local| {
+            local.used = true;
+            let arity = match local.kind {
+                LocalKind::Function(arity) => arity,
+                _ => None,
+            };
+            (local.definition, arity)
+        });
+        match local {
+            Some((definition, arity)) => {
+                self.refer(&token.span, definition);
+                arity
+            }
+            None => {
+                if let Some(&definition) = self.globals.get(&token.lexeme) {
+                    self.refer(&token.span, definition);
+                }
+                None
+            }
+        }
+    }
+
+    fn assign(&self, token: &Token) {
+        let found = self.with_local(&token.lexeme, |local| {
+            if let LocalKind::Function(_) = local.kind {
+                local.kind = LocalKind::Function(None);
+            }
+            local.definition
+        });
+        let definition = found.or_else(|| self.globals.get(&token.lexeme).copied());
+        if let Some(definition) = definition {
+            self.refer(&token.span, definition);
+        } else {
+            self.warn(Warning::UndeclaredGlobal(
+                token.lexeme.clone(),
+                token.span.clone(),
+            ));
+        }
+    }
+}
+
+impl VisitorWithContext<&ProgramAst, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &ProgramAst, context: &AnalyzerContext) -> Result<()> {
+        for (_, declaration) in &node.statements {
+            declaration.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Declaration, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Declaration, context: &AnalyzerContext) -> Result<()> {
+        match &node.token_type {
+            DeclarationType::VarDeclaration(var) => var.accept_with_context(&Self, context),
+            DeclarationType::Statement(stmt) => stmt.accept_with_context(&Self, context),
+            DeclarationType::FunctionDeclaration(func) => func.accept_with_context(&Self, context),
+            DeclarationType::ClassDeclaration(class) => class.accept_with_context(&Self, context),
+            // What a module declares is only known once it is loaded
+            DeclarationType::ImportDeclaration(_) => Ok(()),
+            DeclarationType::None => Err(RuntimeError::ASTInvalidStructure),
+        }
+    }
+}
+
+impl VisitorWithContext<&VarDeclaration, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &VarDeclaration, context: &AnalyzerContext) -> Result<()> {
+        let ident_token = node
+            .token_list
+            .get(1)
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        if let Some(expr) = &node.expr {
+            expr.accept_with_context(&Self, context)?;
+        }
+        let detail = format!("var {}", ident_token.lexeme);
+        context.declare(ident_token, LocalKind::Variable, detail);
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&FunctionDeclaration, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(
+        &self,
+        node: &FunctionDeclaration,
+        context: &AnalyzerContext,
+    ) -> Result<()> {
+        let function = &node.function;
+        let function_ident = function
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        let arity = function.parameters.parameters.len();
+        let detail = format!("fun {}", Self::signature(function));
+        context.declare(function_ident, LocalKind::Function(Some(arity)), detail);
+        Self::analyze_function(
+            &function.parameters,
+            |context| Self::analyze_declarations(&function.block.declarations, context),
+            function.block.token_list.last(),
+            context,
+        )
+    }
+}
+
+impl VisitorWithContext<&ClassDeclaration, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &ClassDeclaration, context: &AnalyzerContext) -> Result<()> {
+        let class_ident = node
+            .token_list
+            .get(1)
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        if let Some(super_class) = &node.super_class.super_class {
+            context.read(&super_class.token);
+        }
+        let class_name = &class_ident.lexeme;
+        let detail = Self::class_detail(node, class_name);
+        context.declare(class_ident, LocalKind::Class, detail);
+        for (_, function) in &node.functions {
+            if let Some(method_ident) = function.token_list.first() {
+                // Methods are looked up on instances, so they can be named anywhere
+                let detail = format!("{}.{}", class_name, Self::signature(function));
+                let method = context.define(method_ident, SymbolKind::Method, detail);
+                context.symbols.borrow_mut().definitions[method].scope = 0..usize::MAX;
+            }
+            Self::analyze_function(
+                &function.parameters,
+                |context| Self::analyze_declarations(&function.block.declarations, context),
+                function.block.token_list.last(),
+                context,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Statement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Statement, context: &AnalyzerContext) -> Result<()> {
+        match &node.token_type {
+            StatementType::ExprStatement(expr) => expr.accept_with_context(&Self, context),
+            StatementType::PrintStatement(print) => print.accept_with_context(&Self, context),
+            StatementType::Block(block) => block.accept_with_context(&Self, context),
+            StatementType::IfStatement(if_stmt) => if_stmt.accept_with_context(&Self, context),
+            StatementType::WhileStatement(while_stmt) => {
+                while_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ForStatement(for_stmt) => for_stmt.accept_with_context(&Self, context),
+            StatementType::ReturnStatement(return_stmt) => {
+                return_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::BreakStatement(break_stmt) => {
+                break_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ContinueStatement(continue_stmt) => {
+                continue_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ThrowStatement(throw_stmt) => {
+                throw_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::TryStatement(try_stmt) => try_stmt.accept_with_context(&Self, context),
+            StatementType::None => Err(RuntimeError::ASTInvalidStructure),
+        }
+    }
+}
+
+impl VisitorWithContext<&PrintStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &PrintStatement, context: &AnalyzerContext) -> Result<()> {
+        node.expr.accept_with_context(&Self, context)
+    }
+}
+
+impl VisitorWithContext<&ExprStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &ExprStatement, context: &AnalyzerContext) -> Result<()> {
+        node.expr.accept_with_context(&Self, context)
+    }
+}
+
+impl VisitorWithContext<&Block, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Block, context: &AnalyzerContext) -> Result<()> {
+        context.enter_scope();
+        Self::analyze_declarations(&node.declarations, context)?;
+        context.exit_scope(node.token_list.last());
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&IfStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &IfStatement, context: &AnalyzerContext) -> Result<()> {
+        node.bool_expr.accept_with_context(&Self, context)?;
+        node.true_statement.accept_with_context(&Self, context)?;
+        if let Some(false_statement) = &node.false_statement {
+            false_statement.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&WhileStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &WhileStatement, context: &AnalyzerContext) -> Result<()> {
+        node.eval_expr.accept_with_context(&Self, context)?;
+        node.statement.accept_with_context(&Self, context)
+    }
+}
+
+impl VisitorWithContext<&ForStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &ForStatement, context: &AnalyzerContext) -> Result<()> {
+        context.enter_scope();
+        match &node.token_type {
+            ForStatementType::VarDeclaration(var) => var.accept_with_context(&Self, context)?,
+            ForStatementType::ExprStatement(expr) => expr.accept_with_context(&Self, context)?,
+            ForStatementType::Semicolon => {}
+            ForStatementType::None => return Err(RuntimeError::ASTInvalidStructure),
+        }
+        if let Some(condition) = &node.condition.expr {
+            condition.accept_with_context(&Self, context)?;
+        }
+        if let Some(increment) = &node.increment.expr {
+            increment.accept_with_context(&Self, context)?;
+        }
+        node.statement.accept_with_context(&Self, context)?;
+        context.exit_scope(None);
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&ReturnStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &ReturnStatement, context: &AnalyzerContext) -> Result<()> {
+        match &node.expr {
+            Some(expr) => expr.accept_with_context(&Self, context),
+            None => Ok(()),
+        }
+    }
+}
+
+impl VisitorWithContext<&BreakStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, _: &BreakStatement, _: &AnalyzerContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&ContinueStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, _: &ContinueStatement, _: &AnalyzerContext) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&ThrowStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &ThrowStatement, context: &AnalyzerContext) -> Result<()> {
+        node.expr.accept_with_context(&Self, context)
+    }
+}
+
+impl VisitorWithContext<&TryStatement, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &TryStatement, context: &AnalyzerContext) -> Result<()> {
+        node.body.accept_with_context(&Self, context)?;
+        if let Some(catch_clause) = &node.catch_clause {
+            let identifier = catch_clause
+                .token_list
+                .get(2)
+                .ok_or(RuntimeError::ASTInvalidStructure)?;
+            context.enter_scope();
+            let detail = format!("var {}", identifier.lexeme);
+            context.declare(identifier, LocalKind::Variable, detail);
+            catch_clause.body.accept_with_context(&Self, context)?;
+            context.exit_scope(None);
+        }
+        if let Some(finally_clause) = &node.finally_clause {
+            finally_clause.body.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Assignment, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Assignment, context: &AnalyzerContext) -> Result<()> {
+        match node {
+            Assignment::Assignment(value, identifier) => {
+                value.accept_with_context(&Self, context)?;
+                context.assign(&identifier.token);
+                Ok(())
+            }
+            Assignment::SetExpression(value, _, call) => {
+                call.accept_with_context(&Self, context)?;
+                value.accept_with_context(&Self, context)
+            }
+            Assignment::SetIndex(value, index, call) => {
+                call.accept_with_context(&Self, context)?;
+                index.expression.accept_with_context(&Self, context)?;
+                value.accept_with_context(&Self, context)
+            }
+            Assignment::Evaluable(evaluable) => evaluable.accept_with_context(&Self, context),
+        }
+    }
+}
+
+impl VisitorWithContext<&LogicalOr, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &LogicalOr, context: &AnalyzerContext) -> Result<()> {
+        node.main_and.accept_with_context(&Self, context)?;
+        for (_, and) in &node.ands {
+            and.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&LogicalAnd, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &LogicalAnd, context: &AnalyzerContext) -> Result<()> {
+        node.main_equality.accept_with_context(&Self, context)?;
+        for (_, equality) in &node.equalities {
+            equality.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Equality, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Equality, context: &AnalyzerContext) -> Result<()> {
+        node.main_comparison.accept_with_context(&Self, context)?;
+        for (_, comparison) in &node.comparisons {
+            comparison.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Comparison, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Comparison, context: &AnalyzerContext) -> Result<()> {
+        node.main_term.accept_with_context(&Self, context)?;
+        for (_, term) in &node.terms {
+            term.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Term, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Term, context: &AnalyzerContext) -> Result<()> {
+        node.main_factor.accept_with_context(&Self, context)?;
+        for (_, factor) in &node.factors {
+            factor.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Factor, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Factor, context: &AnalyzerContext) -> Result<()> {
+        node.main_unary.accept_with_context(&Self, context)?;
+        for (_, unary) in &node.unaries {
+            unary.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&UnaryExpression, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &UnaryExpression, context: &AnalyzerContext) -> Result<()> {
+        match &node.token_type {
+            UnaryExpressionType::Call(call) => call.accept_with_context(&Self, context),
+            UnaryExpressionType::UnaryExpressionSelf(unary) => {
+                unary.accept_with_context(&Self, context)
+            }
+            UnaryExpressionType::None => Err(RuntimeError::ASTInvalidStructure),
+        }
+    }
+}
+
+impl VisitorWithContext<&UnaryExpressionSelf, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(
+        &self,
+        node: &UnaryExpressionSelf,
+        context: &AnalyzerContext,
+    ) -> Result<()> {
+        node.expr.accept_with_context(&Self, context)
+    }
+}
+
+impl VisitorWithContext<&Call, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(&self, node: &Call, context: &AnalyzerContext) -> Result<()> {
+        // Only a local function called directly by name has a known arity.
+        let callee = match &node.primary {
+            PrimaryExpression::PrimaryWithoutSuperExpression(primary)
+                if matches!(primary.token_type, PrimaryExpressionType::Identifier) =>
+            {
+                primary.token_list.first()
+            }
+            _ => None,
+        };
+        match callee {
+            Some(token) => {
+                let arity = context.read(token);
+                let first_call =
+                    node.accessor_or_arguments
+                        .first()
+                        .and_then(|(_, first)| match &first.ty {
+                            AccessorOrArgumentsType::ArgumentsList(arguments_list) => {
+                                Some(arguments_list)
+                            }
+                            _ => None,
+                        });
+                if let (Some(arity), Some(arguments_list)) = (arity, first_call) {
+                    let argument_count = arguments_list
+                        .maybe_arguments
+                        .as_ref()
+                        .map_or(0, |arguments| arguments.rest.len() + 1);
+                    if argument_count != arity {
+                        context.warn(Warning::ArityMismatch(
+                            token.lexeme.clone(),
+                            arity,
+                            argument_count,
+                            token.span.clone(),
+                        ));
+                    }
+                }
+            }
+            None => node.primary.accept_with_context(&Self, context)?,
+        }
+
+        for (_, accessor_or_arguments) in &node.accessor_or_arguments {
+            match &accessor_or_arguments.ty {
+                AccessorOrArgumentsType::Accessor(_) => {}
+                AccessorOrArgumentsType::ArgumentsList(arguments_list) => {
+                    if let Some(arguments) = &arguments_list.maybe_arguments {
+                        arguments.first.accept_with_context(&Self, context)?;
+                        for (_, argument) in &arguments.rest {
+                            argument.accept_with_context(&Self, context)?;
+                        }
+                    }
+                }
+                AccessorOrArgumentsType::Index(index) => {
+                    index.expression.accept_with_context(&Self, context)?
+                }
+                AccessorOrArgumentsType::None => return Err(RuntimeError::ASTInvalidStructure),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&PrimaryExpression, Result<()>, AnalyzerContext> for Analyzer {
+    fn visit_with_context(
+        &self,
+        node: &PrimaryExpression,
+        context: &AnalyzerContext,
+    ) -> Result<()> {
+        match node {
+            PrimaryExpression::PrimaryWithoutSuperExpression(node) => match &node.token_type {
+                PrimaryExpressionType::Identifier => {
+                    let token = node
+                        .token_list
+                        .first()
+                        .ok_or(RuntimeError::ASTInvalidStructure)?;
+                    context.read(token);
+                    Ok(())
+                }
+                PrimaryExpressionType::Expression(expr) => expr.accept_with_context(&Self, context),
+                PrimaryExpressionType::ListElements(Some(elements)) => {
+                    elements.first.accept_with_context(&Self, context)?;
+                    for (_, element) in &elements.rest {
+                        element.accept_with_context(&Self, context)?;
+                    }
+                    Ok(())
+                }
+                PrimaryExpressionType::MapEntries(Some(entries)) => {
+                    let rest = entries.rest.iter().map(|(_, entry)| entry);
+                    for entry in std::iter::once(&entries.first).chain(rest) {
+                        entry.key.accept_with_context(&Self, context)?;
+                        entry.value.accept_with_context(&Self, context)?;
+                    }
+                    Ok(())
+                }
+                PrimaryExpressionType::None => Err(RuntimeError::ASTInvalidStructure),
+                _ => Ok(()),
+            },
+            PrimaryExpression::Lambda(lambda) => Self::analyze_function(
+                &lambda.parameters,
+                |context| match &lambda.body {
+                    LambdaBody::Block(block) => {
+                        Self::analyze_declarations(&block.declarations, context)
+                    }
+                    LambdaBody::Expression(expression) => {
+                        expression.accept_with_context(&Self, context)
+                    }
+                },
+                match &lambda.body {
+                    LambdaBody::Block(block) => block.token_list.last(),
+                    LambdaBody::Expression(_) => None,
+                },
+                context,
+            ),
+            PrimaryExpression::Super(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax_analysis::ParseStream;
+    use crate::tokenizer::scan_tokens;
+
+    fn analyze(source: &str) -> Vec<String> {
+        let (tokens, _) = scan_tokens("", source);
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        Analyzer::analyze(&program)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_reports_warnings_in_source_order() {
+        let warnings = analyze(
+            "fun f(a, _b) {\n\
+             var unused;\n\
+             fun g(x) { return x; }\n\
+             g();\n\
+             missing = 1;\n\
+             return;\n\
+             print a;\n\
+             }",
+        );
+        assert_eq!(
+            warnings,
+            [
+                "[line 2] Warning at 'unused': Local variable is never used.",
+                "[line 4] Warning at 'g': Expected 1 arguments but got 0.",
+                "[line 5] Warning at 'missing': Assignment to an undeclared global variable.",
+                "[line 6] Warning at 'return': Code after 'return' is unreachable.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shadowing_an_outer_local() {
+        let warnings =
+            analyze("var a; { var a = 1; fun f() { var a = 2; print a; } f(); print a; }");
+        assert_eq!(
+            warnings,
+            ["[line 1] Warning at 'a': Shadows a local variable of an enclosing scope."]
+        );
+    }
+}
@@ -16,11 +16,7 @@ impl AssignmentEvaluator {
 impl Evaluable for AssignmentEvaluator {
     fn eval(&self, run_state: &RunState) -> Result<RuntimeValue, RuntimeError> {
         let value = self.value.eval(run_state)?;
-        run_state.set_variable(
-            self.identifier.identifier().to_string(),
-            value.clone(),
-            self.identifier.depth(),
-        );
+        run_state.set_variable(&self.identifier, value.clone())?;
         Ok(value)
     }
 }
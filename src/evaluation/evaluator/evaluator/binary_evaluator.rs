@@ -1,5 +1,6 @@
 use super::core::Evaluable;
 use crate::evaluation::{run::RunState, RuntimeError, RuntimeValue};
+use crate::tokenizer::Span;
 
 #[derive(Debug)]
 pub enum BinaryOperation {
@@ -22,6 +23,7 @@ pub struct BinaryEvaluator {
     operation: BinaryOperation,
     left: Box<dyn Evaluable>,
     right: Box<dyn Evaluable>,
+    span: Span,
 }
 
 impl BinaryEvaluator {
@@ -29,11 +31,13 @@ impl BinaryEvaluator {
         operation: BinaryOperation,
         left: Box<dyn Evaluable>,
         right: Box<dyn Evaluable>,
+        span: Span,
     ) -> Self {
         Self {
             operation,
             left,
             right,
+            span,
         }
     }
 }
@@ -67,8 +71,11 @@ impl Evaluable for BinaryEvaluator {
             _ => {}
         }
         let left = self.left.eval(run_state)?;
-        let right = self.right.eval(run_state)?;
-        match self.operation {
+        let right = {
+            let _root = run_state.root(&left);
+            self.right.eval(run_state)?
+        };
+        let result = match self.operation {
             BinaryOperation::Addition => left + right,
             BinaryOperation::Subtraction => left - right,
             BinaryOperation::Multiplication => left * right,
@@ -85,6 +92,7 @@ impl Evaluable for BinaryEvaluator {
             BinaryOperation::LogicalOr => {
                 unreachable!()
             }
-        }
+        };
+        result.map_err(|error| error.at(&self.span))
     }
 }
@@ -0,0 +1,25 @@
+use super::core::Evaluable;
+use crate::evaluation::{run::RunState, RuntimeError, RuntimeValue};
+use crate::tokenizer::Span;
+
+/// The condition of an `if`, or the left operand of an `and` or `or`, built
+/// to tell a `RunHook` which way the branch at `span` goes.
+#[derive(Debug)]
+pub struct BranchEvaluator {
+    condition: Box<dyn Evaluable>,
+    span: Span,
+}
+
+impl BranchEvaluator {
+    pub fn new(condition: Box<dyn Evaluable>, span: Span) -> Self {
+        Self { condition, span }
+    }
+}
+
+impl Evaluable for BranchEvaluator {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        let value = self.condition.eval(state)?;
+        state.branch(&self.span, value.to_bool()?);
+        Ok(value)
+    }
+}
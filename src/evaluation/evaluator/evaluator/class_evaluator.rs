@@ -1,26 +1,37 @@
 use thiserror::Error;
 
-use crate::evaluation::{evaluator::Evaluable, RunState, RuntimeError, RuntimeValue};
+use crate::{
+    evaluation::{evaluator::Evaluable, RunState, RuntimeError, RuntimeValue},
+    tokenizer::Span,
+};
 
 #[derive(Debug, Error)]
 pub enum ClassAccessorError {
     #[error("Cannot access {0}")]
-    Unaccessible(RuntimeValue),
+    Unaccessible(RuntimeValue, Span),
+}
+
+impl ClassAccessorError {
+    pub fn span(&self) -> &Span {
+        match self {
+            ClassAccessorError::Unaccessible(_, span) => span,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ClassAccessorEvaluator {
     to_access: Box<dyn Evaluable>,
     to_get: String,
-    line: usize,
+    span: Span,
 }
 
 impl ClassAccessorEvaluator {
-    pub fn new(to_access: Box<dyn Evaluable>, to_get: String, line: usize) -> Self {
+    pub fn new(to_access: Box<dyn Evaluable>, to_get: String, span: Span) -> Self {
         Self {
             to_access,
             to_get,
-            line,
+            span,
         }
     }
 }
@@ -30,15 +41,14 @@ impl Evaluable for ClassAccessorEvaluator {
         let to_access = self.to_access.eval(state)?;
         let class_instance_pointer = match to_access {
             RuntimeValue::ClassInstance(pointer, _) => pointer,
-            _ => return Err(ClassAccessorError::Unaccessible(to_access).into()),
+            _ => return Err(ClassAccessorError::Unaccessible(to_access, self.span.clone()).into()),
         };
         state
             .get_instance_value(class_instance_pointer, &self.to_get, None, None)
             .and_then(|o| {
-                o.ok_or(RuntimeError::UndefinedProperty(
-                    self.to_get.clone(),
-                    self.line,
-                ))
+                o.ok_or_else(|| {
+                    RuntimeError::UndefinedProperty(self.to_get.clone(), self.span.clone())
+                })
             })
     }
 }
@@ -48,14 +58,21 @@ pub struct SetExpressionEvaluator {
     to_access: Box<dyn Evaluable>,
     to_set: String,
     value: Box<dyn Evaluable>,
+    span: Span,
 }
 
 impl SetExpressionEvaluator {
-    pub fn new(to_access: Box<dyn Evaluable>, to_set: String, value: Box<dyn Evaluable>) -> Self {
+    pub fn new(
+        to_access: Box<dyn Evaluable>,
+        to_set: String,
+        value: Box<dyn Evaluable>,
+        span: Span,
+    ) -> Self {
         Self {
             to_access,
             to_set,
             value,
+            span,
         }
     }
 }
@@ -65,8 +82,9 @@ impl Evaluable for SetExpressionEvaluator {
         let to_access = self.to_access.eval(state)?;
         let class_instance_pointer = match to_access {
             RuntimeValue::ClassInstance(pointer, _) => pointer,
-            _ => return Err(ClassAccessorError::Unaccessible(to_access).into()),
+            _ => return Err(ClassAccessorError::Unaccessible(to_access, self.span.clone()).into()),
         };
+        let _root = state.root(&to_access);
         let to_set_value = self.value.eval(state)?;
         state.set_instance_value(class_instance_pointer, &self.to_set, to_set_value.clone())?;
         Ok(to_set_value)
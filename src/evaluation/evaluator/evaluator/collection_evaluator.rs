@@ -0,0 +1,134 @@
+use crate::{
+    evaluation::{evaluator::Evaluable, List, Map, RunState, RuntimeError, RuntimeValue, Table},
+    tokenizer::Span,
+};
+
+/// A `[a, b, c]` literal, which creates a new list each time it runs.
+#[derive(Debug)]
+pub struct ListEvaluator {
+    elements: Vec<Box<dyn Evaluable>>,
+}
+
+impl ListEvaluator {
+    pub fn new(elements: Vec<Box<dyn Evaluable>>) -> Self {
+        Self { elements }
+    }
+}
+
+impl Evaluable for ListEvaluator {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        // The list is rooted while it is filled, so that elements already
+        // evaluated survive a collection run by the next one.
+        let list = List::new(Vec::with_capacity(self.elements.len()));
+        let value = RuntimeValue::List(list.clone());
+        let _root = state.root(&value);
+        for element in &self.elements {
+            let element = element.eval(state)?;
+            list.elements_mut().push(element);
+        }
+        Ok(value)
+    }
+}
+
+/// The key and value of a map literal entry, and the span of its colon.
+pub type EntryEvaluator = (Box<dyn Evaluable>, Box<dyn Evaluable>, Span);
+
+/// A `{key: value}` literal, which creates a new map each time it runs.
+/// Entries run in order, each key before its value, and a repeated key keeps
+/// the last value.
+#[derive(Debug)]
+pub struct MapEvaluator {
+    entries: Vec<EntryEvaluator>,
+}
+
+impl MapEvaluator {
+    pub fn new(entries: Vec<EntryEvaluator>) -> Self {
+        Self { entries }
+    }
+}
+
+impl Evaluable for MapEvaluator {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        let map = Map::new(Table::default());
+        let value = RuntimeValue::Map(map.clone());
+        let _root = state.root(&value);
+        for (key, entry, span) in &self.entries {
+            let key = key.eval(state)?;
+            let entry = entry.eval(state)?;
+            map.set(&key, entry, span)?;
+        }
+        Ok(value)
+    }
+}
+
+/// `xs[i]`, or `m[key]`. `span` is the opening bracket.
+#[derive(Debug)]
+pub struct IndexEvaluator {
+    to_index: Box<dyn Evaluable>,
+    index: Box<dyn Evaluable>,
+    span: Span,
+}
+
+impl IndexEvaluator {
+    pub fn new(to_index: Box<dyn Evaluable>, index: Box<dyn Evaluable>, span: Span) -> Self {
+        Self {
+            to_index,
+            index,
+            span,
+        }
+    }
+}
+
+impl Evaluable for IndexEvaluator {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        let to_index = self.to_index.eval(state)?;
+        let _root = state.root(&to_index);
+        let index = self.index.eval(state)?;
+        match to_index {
+            RuntimeValue::List(list) => list.get(&index, &self.span),
+            RuntimeValue::Map(map) => map.get(&index, &self.span),
+            _ => Err(RuntimeError::NotIndexable(self.span.clone())),
+        }
+    }
+}
+
+/// `xs[i] = value` or `m[key] = value`, which evaluates to the value
+/// assigned. A map gets a new entry when `key` isn't in it.
+#[derive(Debug)]
+pub struct SetIndexEvaluator {
+    to_index: Box<dyn Evaluable>,
+    index: Box<dyn Evaluable>,
+    value: Box<dyn Evaluable>,
+    span: Span,
+}
+
+impl SetIndexEvaluator {
+    pub fn new(
+        to_index: Box<dyn Evaluable>,
+        index: Box<dyn Evaluable>,
+        value: Box<dyn Evaluable>,
+        span: Span,
+    ) -> Self {
+        Self {
+            to_index,
+            index,
+            value,
+            span,
+        }
+    }
+}
+
+impl Evaluable for SetIndexEvaluator {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        let to_index = self.to_index.eval(state)?;
+        let _root = state.root(&to_index);
+        let index = self.index.eval(state)?;
+        let value = self.value.eval(state)?;
+        match to_index {
+            RuntimeValue::List(list) => list.set(&index, value.clone(), &self.span)?,
+            RuntimeValue::Map(map) => map.set(&index, value.clone(), &self.span)?,
+            _ => return Err(RuntimeError::NotIndexable(self.span.clone())),
+        }
+        Ok(value)
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     evaluation::{resolver::Resolver, run::RunState, RuntimeError, RuntimeValue},
-    tokenizer::{Token, TokenValue},
+    tokenizer::{Span, Token, TokenValue},
 };
 
 pub trait Evaluable: std::fmt::Debug {
@@ -10,7 +10,7 @@ pub trait Evaluable: std::fmt::Debug {
 #[derive(Debug)]
 pub struct EvaluableIdentifier {
     identifier: String,
-    line: usize,
+    span: Span,
     depth: Option<usize>,
 }
 
@@ -21,9 +21,9 @@ impl EvaluableIdentifier {
             _ => return Err(RuntimeError::ASTInvalidStructure),
         };
         Ok(Self {
-            depth: resolver.resolve(&identifier_string, token.line)?,
+            depth: resolver.resolve(&identifier_string, &token.span)?,
             identifier: identifier_string,
-            line: token.line,
+            span: token.span.clone(),
         })
     }
 
@@ -33,8 +33,8 @@ impl EvaluableIdentifier {
     }
 
     #[inline]
-    pub fn line(&self) -> usize {
-        self.line
+    pub fn span(&self) -> &Span {
+        &self.span
     }
 
     #[inline]
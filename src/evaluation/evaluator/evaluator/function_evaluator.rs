@@ -1,54 +1,156 @@
 use thiserror::Error;
 
-use crate::evaluation::{
-    evaluator::Evaluable,
-    run::{Callable, Runnable},
-    runtime_value::ThisInstance,
-    RunState, RuntimeError, RuntimeValue,
+use crate::{
+    evaluation::{
+        evaluator::Evaluable,
+        run::{CallFrame, Callable, ControlFlow, Runnable},
+        runtime_value::{Callable as CallableValue, CallableType, ThisInstance},
+        RunState, RuntimeError, RuntimeValue,
+    },
+    tokenizer::Span,
 };
 
 pub const INIT_FUNCTION_NAME: &str = "init";
 
+/// The name lambdas are called by in traces and when printed.
+pub const LAMBDA_NAME: &str = "anonymous";
+
 #[derive(Debug, Error)]
 pub enum FunctionEvaluationError {
-    #[error("Can only call functions and classes.\n[line {0}]")]
-    UnCallableFunction(usize),
+    #[error("Can only call functions and classes.\n[line {}]", .0.line)]
+    UnCallableFunction(Span),
+}
+
+impl FunctionEvaluationError {
+    pub fn span(&self) -> &Span {
+        match self {
+            FunctionEvaluationError::UnCallableFunction(span) => span,
+        }
+    }
+}
+
+/// A lambda expression, which closes over the scope it is evaluated in the
+/// way a function declaration does.
+#[derive(Debug)]
+pub struct LambdaEvaluator {
+    function_pointer: usize,
+}
+
+impl LambdaEvaluator {
+    pub fn new(function_pointer: usize) -> Self {
+        Self { function_pointer }
+    }
+}
+
+impl Evaluable for LambdaEvaluator {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        Ok(RuntimeValue::callable(
+            self.function_pointer,
+            LAMBDA_NAME.to_string(),
+            Some(state.get_current_scope()),
+            CallableType::Function,
+        ))
+    }
 }
 
 #[derive(Debug)]
 pub struct FunctionEvaluator {
     pub callable: Box<dyn Evaluable>,
     pub arguments: Vec<Box<dyn Evaluable>>,
-    line: usize,
+    span: Span,
 }
 
 impl FunctionEvaluator {
     pub fn new(
         callable: Box<dyn Evaluable>,
         arguments: Vec<Box<dyn Evaluable>>,
-        line: usize,
+        span: Span,
     ) -> Self {
         Self {
             callable,
             arguments,
-            line,
+            span,
         }
     }
 }
 
+impl FunctionEvaluator {
+    /// The frame a call to `callable` runs in. Natives run in their caller's
+    /// frame and calling a class runs its `init`.
+    fn call_frame(
+        &self,
+        callable: &CallableValue,
+        state: &RunState,
+    ) -> Result<Option<CallFrame>, RuntimeError> {
+        if callable.is_native() {
+            return Ok(None);
+        }
+        let (function, class) = if callable.is_class_constructor() {
+            (
+                INIT_FUNCTION_NAME.to_string(),
+                Some(callable.get_name().to_string()),
+            )
+        } else {
+            let class = callable
+                .get_this_pointer()
+                .map(|this| state.get_class_name(this.get_super_class()))
+                .transpose()?;
+            (callable.get_name().to_string(), class)
+        };
+        Ok(Some(CallFrame::new(function, class, self.span.clone())))
+    }
+}
+
 impl Evaluable for FunctionEvaluator {
     fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
         let callable = self.callable.eval(state)?;
-        let (index, scope, this_pointer) = match callable {
-            RuntimeValue::Callable(c) => (c.get_pointer(), c.get_scope(), c.get_this_pointer()),
-            _ => return Err(FunctionEvaluationError::UnCallableFunction(self.line).into()),
+        let _callable_root = state.root(&callable);
+        let (index, scope, this_pointer, frame, name, is_class) = match &callable {
+            RuntimeValue::Callable(c) => (
+                c.get_pointer(),
+                c.get_scope(),
+                c.get_this_pointer(),
+                self.call_frame(c, state)?,
+                c.get_name(),
+                c.is_class_constructor(),
+            ),
+            _ => return Err(FunctionEvaluationError::UnCallableFunction(self.span.clone()).into()),
         };
-        let arguments = self
-            .arguments
-            .iter()
-            .map(|arg| arg.eval(state))
-            .collect::<Result<Vec<RuntimeValue>, RuntimeError>>()?;
-        state.call_function(index, arguments, scope, this_pointer)
+        let mut argument_roots = Vec::with_capacity(self.arguments.len());
+        let mut arguments = Vec::with_capacity(self.arguments.len());
+        for argument in &self.arguments {
+            let value = argument.eval(state)?;
+            argument_roots.push(state.root(&value));
+            arguments.push(value);
+        }
+        // A class is named as called itself, as it may have no `init` to run
+        state.enter_call(index, || match &frame {
+            Some(frame) if !is_class => frame.to_string(),
+            _ => format!("{}()", name),
+        });
+        let has_frame = frame.is_some();
+        match frame {
+            Some(frame) => state.push_frame(frame),
+            // Natives get no frame to report a wrong number of arguments from
+            None => {
+                let arity = state.function_arity(index)?;
+                if arity != arguments.len() {
+                    return Err(RuntimeError::ArityMismatch(
+                        arity,
+                        arguments.len(),
+                        self.span.clone(),
+                    ));
+                }
+            }
+        }
+        let result = state.call_function(index, arguments, scope, this_pointer);
+        state.exit_call();
+        if !has_frame {
+            return result.map_err(|error| error.at(&self.span));
+        }
+        let result = result?;
+        state.pop_frame();
+        Ok(result)
     }
 }
 
@@ -71,8 +173,10 @@ impl FunctionCallable {
 
 impl Evaluable for FunctionCallable {
     fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
-        let ret = self.function_block.run(state)?;
-        Ok(ret.unwrap_or(RuntimeValue::Nil))
+        match self.function_block.run(state)? {
+            ControlFlow::Return(value) => Ok(value),
+            _ => Ok(RuntimeValue::Nil),
+        }
     }
 }
 
@@ -101,16 +205,21 @@ impl Callable for FunctionCallable {
         state: &RunState,
     ) -> Result<RuntimeValue, RuntimeError> {
         if arguments.len() != self.parameters.len() {
-            return Err(RuntimeError::ArityMismatch);
+            // The caller is at fault, so the error is reported from its frame.
+            let call_site = state.pop_frame().map(|frame| frame.span);
+            return Err(RuntimeError::ArityMismatch(
+                self.parameters.len(),
+                arguments.len(),
+                call_site.unwrap_or_default(),
+            ));
         }
 
-        state.enter_scope()?;
+        let _scope = state.enter_scope();
         if let Some(this_pointer) = &this_pointer {
             state.set_this(this_pointer.clone());
         }
         self.define_arguments(arguments, state)?;
         let result = self.eval(state)?;
-        state.exit_scope()?;
         if self.name == INIT_FUNCTION_NAME {
             if let Some(this_pointer) = &this_pointer {
                 let class = state.get_class_name(this_pointer.get_current())?;
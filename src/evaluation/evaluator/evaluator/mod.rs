@@ -1,6 +1,8 @@
 mod assignment_evaluator;
 mod binary_evaluator;
+mod branch_evaluator;
 mod class_evaluator;
+mod collection_evaluator;
 mod core;
 mod function_evaluator;
 mod primary_evaluator;
@@ -12,8 +14,12 @@ pub use core::{Evaluable, EvaluableIdentifier};
 // Re-export all evaluator implementations
 pub use assignment_evaluator::AssignmentEvaluator;
 pub use binary_evaluator::{BinaryEvaluator, BinaryOperation};
+pub use branch_evaluator::BranchEvaluator;
 pub use class_evaluator::{ClassAccessorError, ClassAccessorEvaluator, SetExpressionEvaluator};
-pub use function_evaluator::INIT_FUNCTION_NAME;
+pub use collection_evaluator::{
+    EntryEvaluator, IndexEvaluator, ListEvaluator, MapEvaluator, SetIndexEvaluator,
+};
 pub use function_evaluator::{FunctionCallable, FunctionEvaluationError, FunctionEvaluator};
+pub use function_evaluator::{LambdaEvaluator, INIT_FUNCTION_NAME, LAMBDA_NAME};
 pub use primary_evaluator::PrimaryEvaluator;
 pub use unary_evaluator::{UnaryEvaluator, UnaryOperation};
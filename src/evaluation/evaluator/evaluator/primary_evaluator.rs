@@ -49,10 +49,12 @@ impl Evaluable for PrimaryEvaluator {
                         Some(1),
                     )
                     .and_then(|o| {
-                        o.ok_or(RuntimeError::UndefinedProperty(
-                            identifier.lexeme.clone(),
-                            identifier.line,
-                        ))
+                        o.ok_or_else(|| {
+                            RuntimeError::UndefinedProperty(
+                                identifier.lexeme.clone(),
+                                identifier.span.clone(),
+                            )
+                        })
                     });
             }
         }
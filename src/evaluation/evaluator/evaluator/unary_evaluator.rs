@@ -1,5 +1,6 @@
 use super::core::Evaluable;
 use crate::evaluation::{run::RunState, RuntimeError, RuntimeValue};
+use crate::tokenizer::Span;
 
 #[derive(Debug)]
 pub enum UnaryOperation {
@@ -11,20 +12,26 @@ pub enum UnaryOperation {
 pub struct UnaryEvaluator {
     operation: UnaryOperation,
     operand: Box<dyn Evaluable>,
+    span: Span,
 }
 
 impl UnaryEvaluator {
-    pub fn new(operation: UnaryOperation, operand: Box<dyn Evaluable>) -> Self {
-        Self { operation, operand }
+    pub fn new(operation: UnaryOperation, operand: Box<dyn Evaluable>, span: Span) -> Self {
+        Self {
+            operation,
+            operand,
+            span,
+        }
     }
 }
 
 impl Evaluable for UnaryEvaluator {
     fn eval(&self, run_state: &RunState) -> Result<RuntimeValue, RuntimeError> {
         let operand = self.operand.eval(run_state)?;
-        match self.operation {
+        let result = match self.operation {
             UnaryOperation::Negation => -operand,
             UnaryOperation::Not => !operand,
-        }
+        };
+        result.map_err(|error| error.at(&self.span))
     }
 }
@@ -3,10 +3,12 @@ use crate::common::{Visitable, VisitorWithContext};
 use crate::evaluation::evaluator::evaluator::AssignmentEvaluator;
 use crate::evaluation::evaluator::evaluator_builders::binary_evaluator_builder::BinaryEvaluatorBuilder;
 use crate::evaluation::evaluator::evaluator_builders::function_call_evaluator_builder::FunctionCallEvaluatorBuilder;
-use crate::evaluation::evaluator::{EvaluableIdentifier, SetExpressionEvaluator};
+use crate::evaluation::evaluator::{
+    EvaluableIdentifier, SetExpressionEvaluator, SetIndexEvaluator,
+};
 use crate::evaluation::runtime_value::Result;
-use crate::evaluation::BuilderContext;
-use crate::syntax_analysis::{Assignment, Call};
+use crate::evaluation::{BuilderContext, RuntimeError};
+use crate::syntax_analysis::{Assignment, Call, Index};
 use crate::tokenizer::Token;
 
 pub struct AssignmentEvaluatorBuilder;
@@ -42,6 +44,32 @@ impl AssignmentEvaluatorBuilder {
             to_access_evaluator,
             ident_evaluator,
             value_evaluator,
+            ident_token.span.clone(),
+        )))
+    }
+
+    fn build_set_index_evaluator(
+        assignment: &Assignment,
+        call: &Call,
+        index: &Index,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Evaluable>> {
+        let to_index_evaluator =
+            call.accept_with_context(&FunctionCallEvaluatorBuilder, context)?;
+        let index_evaluator = index
+            .expression
+            .accept_with_context(&AssignmentEvaluatorBuilder, context)?;
+        let value_evaluator =
+            assignment.accept_with_context(&AssignmentEvaluatorBuilder, context)?;
+        Ok(Box::new(SetIndexEvaluator::new(
+            to_index_evaluator,
+            index_evaluator,
+            value_evaluator,
+            index
+                .bracket()
+                .ok_or(RuntimeError::ASTInvalidStructure)?
+                .span
+                .clone(),
         )))
     }
 }
@@ -61,6 +89,9 @@ impl VisitorWithContext<&Assignment, Result<Box<dyn Evaluable>>, BuilderContext>
             Assignment::SetExpression(assignment, identifier, call) => {
                 Self::build_set_expression_evaluator(assignment, &call, &identifier.token, context)
             }
+            Assignment::SetIndex(assignment, index, call) => {
+                Self::build_set_index_evaluator(assignment, call, index, context)
+            }
             Assignment::Evaluable(evaluable) => {
                 evaluable.accept_with_context(&BinaryEvaluatorBuilder, context)
             }
@@ -2,12 +2,14 @@ use super::super::Evaluable;
 use crate::common::{Visitable, VisitorWithContext};
 use crate::evaluation::evaluator::evaluator::{BinaryEvaluator, BinaryOperation};
 use crate::evaluation::evaluator::evaluator_builders::primary_evaluator_builder::UnaryEvaluatorBuilder;
+use crate::evaluation::evaluator::evaluator_builders::traced_branch;
 use crate::evaluation::runtime_value::Result;
 use crate::evaluation::{BuilderContext, RuntimeError};
 use crate::syntax_analysis::{
     Comparison, ComparisonType, Equality, EqualityType, Factor, FactorType, LogicalAnd, LogicalOr,
     Term, TermType,
 };
+use crate::tokenizer::{Span, Token};
 
 pub struct BinaryEvaluatorBuilder;
 
@@ -19,6 +21,14 @@ enum BinaryASTOperation<'a> {
 }
 
 impl BinaryEvaluatorBuilder {
+    /// Span of the `index`-th operator of a chain of binary operations.
+    fn operator_span(token_list: &[Token], index: usize) -> Result<Span> {
+        token_list
+            .get(index)
+            .map(|token| token.span.clone())
+            .ok_or(RuntimeError::ASTInvalidStructure)
+    }
+
     fn from_ast_type_to_evaluator_type(op_type: BinaryASTOperation) -> Result<BinaryOperation> {
         match op_type {
             BinaryASTOperation::Factor(FactorType::Star) => Ok(BinaryOperation::Multiplication),
@@ -53,10 +63,11 @@ impl VisitorWithContext<&Factor, Result<Box<dyn Evaluable>>, BuilderContext>
         let mut main_evaluator = node
             .main_unary
             .accept_with_context(&UnaryEvaluatorBuilder, context)?;
-        for (op_type, factor) in &node.unaries {
+        for (index, (op_type, factor)) in node.unaries.iter().enumerate() {
             let op = Self::from_ast_type_to_evaluator_type(BinaryASTOperation::Factor(op_type))?;
             let right = factor.accept_with_context(&UnaryEvaluatorBuilder, context)?;
-            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right));
+            let span = Self::operator_span(&node.token_list, index)?;
+            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right, span));
         }
         Ok(main_evaluator)
     }
@@ -73,10 +84,11 @@ impl VisitorWithContext<&Term, Result<Box<dyn Evaluable>>, BuilderContext>
         let mut main_evaluator = node
             .main_factor
             .accept_with_context(&BinaryEvaluatorBuilder, context)?;
-        for (op_type, term) in &node.factors {
+        for (index, (op_type, term)) in node.factors.iter().enumerate() {
             let op = Self::from_ast_type_to_evaluator_type(BinaryASTOperation::Term(op_type))?;
             let right = term.accept_with_context(&BinaryEvaluatorBuilder, context)?;
-            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right));
+            let span = Self::operator_span(&node.token_list, index)?;
+            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right, span));
         }
         Ok(main_evaluator)
     }
@@ -93,11 +105,12 @@ impl VisitorWithContext<&Comparison, Result<Box<dyn Evaluable>>, BuilderContext>
         let mut main_evaluator = node
             .main_term
             .accept_with_context(&BinaryEvaluatorBuilder, context)?;
-        for (op_type, comparison) in &node.terms {
+        for (index, (op_type, comparison)) in node.terms.iter().enumerate() {
             let op =
                 Self::from_ast_type_to_evaluator_type(BinaryASTOperation::Comparison(op_type))?;
             let right = comparison.accept_with_context(&BinaryEvaluatorBuilder, context)?;
-            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right));
+            let span = Self::operator_span(&node.token_list, index)?;
+            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right, span));
         }
         Ok(main_evaluator)
     }
@@ -114,10 +127,11 @@ impl VisitorWithContext<&Equality, Result<Box<dyn Evaluable>>, BuilderContext>
         let mut main_evaluator = node
             .main_comparison
             .accept_with_context(&BinaryEvaluatorBuilder, context)?;
-        for (op_type, equality) in &node.comparisons {
+        for (index, (op_type, equality)) in node.comparisons.iter().enumerate() {
             let op = Self::from_ast_type_to_evaluator_type(BinaryASTOperation::Equality(op_type))?;
             let right = equality.accept_with_context(&BinaryEvaluatorBuilder, context)?;
-            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right));
+            let span = Self::operator_span(&node.token_list, index)?;
+            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right, span));
         }
         Ok(main_evaluator)
     }
@@ -134,10 +148,12 @@ impl VisitorWithContext<&LogicalAnd, Result<Box<dyn Evaluable>>, BuilderContext>
         let mut main_evaluator = node
             .main_equality
             .accept_with_context(&BinaryEvaluatorBuilder, context)?;
-        for (_, equality) in &node.equalities {
+        for (index, (_, equality)) in node.equalities.iter().enumerate() {
             let op = BinaryOperation::LogicalAnd;
             let right = equality.accept_with_context(&BinaryEvaluatorBuilder, context)?;
-            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right));
+            let span = Self::operator_span(&node.token_list, index)?;
+            let left = traced_branch(main_evaluator, node.token_list.get(index), context);
+            main_evaluator = Box::new(BinaryEvaluator::new(op, left, right, span));
         }
         Ok(main_evaluator)
     }
@@ -154,10 +170,12 @@ impl VisitorWithContext<&LogicalOr, Result<Box<dyn Evaluable>>, BuilderContext>
         let mut main_evaluator = node
             .main_and
             .accept_with_context(&BinaryEvaluatorBuilder, context)?;
-        for (_, and) in &node.ands {
+        for (index, (_, and)) in node.ands.iter().enumerate() {
             let op = BinaryOperation::LogicalOr;
             let right = and.accept_with_context(&BinaryEvaluatorBuilder, context)?;
-            main_evaluator = Box::new(BinaryEvaluator::new(op, main_evaluator, right));
+            let span = Self::operator_span(&node.token_list, index)?;
+            let left = traced_branch(main_evaluator, node.token_list.get(index), context);
+            main_evaluator = Box::new(BinaryEvaluator::new(op, left, right, span));
         }
         Ok(main_evaluator)
     }
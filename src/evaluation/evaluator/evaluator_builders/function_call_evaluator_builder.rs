@@ -3,33 +3,29 @@ use super::super::super::BuilderContext;
 use super::super::evaluator::Evaluable;
 use super::primary_evaluator_builder::PrimaryEvaluatorBuilder;
 use crate::common::{Visitable, VisitorWithContext};
-use crate::evaluation::evaluator::{ClassAccessorEvaluator, FunctionEvaluator};
+use crate::evaluation::evaluator::{ClassAccessorEvaluator, FunctionEvaluator, IndexEvaluator};
 use crate::evaluation::{AssignmentEvaluatorBuilder, RuntimeError};
-use crate::syntax_analysis::{AccessorOrArgumentsType, ArgumentsList, Call};
+use crate::syntax_analysis::{
+    AccessorOrArguments, AccessorOrArgumentsType, Arguments, ArgumentsList, Call,
+};
 
 pub struct FunctionCallEvaluatorBuilder;
 
-impl VisitorWithContext<&ArgumentsList, Result<Vec<Box<dyn Evaluable>>>, BuilderContext>
+impl VisitorWithContext<&Arguments, Result<Vec<Box<dyn Evaluable>>>, BuilderContext>
     for FunctionCallEvaluatorBuilder
 {
     fn visit_with_context(
         &self,
-        node: &ArgumentsList,
+        node: &Arguments,
         context: &BuilderContext,
     ) -> Result<Vec<Box<dyn Evaluable>>> {
-        if node.maybe_arguments.is_none() {
-            return Ok(vec![]);
-        }
-
-        let arguments = node.maybe_arguments.as_ref().unwrap();
         let mut arguments_evaluator: Vec<_> = Vec::new();
-        arguments_evaluator.reserve_exact(arguments.rest.len() + 1);
+        arguments_evaluator.reserve_exact(node.rest.len() + 1);
         arguments_evaluator.push(
-            arguments
-                .first
+            node.first
                 .accept_with_context(&AssignmentEvaluatorBuilder, context)?,
         );
-        for (_, expression) in arguments.rest.iter() {
+        for (_, expression) in node.rest.iter() {
             arguments_evaluator
                 .push(expression.accept_with_context(&AssignmentEvaluatorBuilder, context)?);
         }
@@ -37,72 +33,83 @@ impl VisitorWithContext<&ArgumentsList, Result<Vec<Box<dyn Evaluable>>>, Builder
     }
 }
 
-impl VisitorWithContext<&Call, Result<Box<dyn Evaluable>>, BuilderContext>
+impl VisitorWithContext<&ArgumentsList, Result<Vec<Box<dyn Evaluable>>>, BuilderContext>
     for FunctionCallEvaluatorBuilder
 {
     fn visit_with_context(
         &self,
-        node: &Call,
+        node: &ArgumentsList,
         context: &BuilderContext,
-    ) -> Result<Box<dyn Evaluable>> {
-        let primary_evaluator = node
-            .primary
-            .accept_with_context(&PrimaryEvaluatorBuilder, context)?;
-
-        if node.accessor_or_arguments.len() == 0 {
-            return Ok(primary_evaluator);
+    ) -> Result<Vec<Box<dyn Evaluable>>> {
+        match &node.maybe_arguments {
+            Some(arguments) => arguments.accept_with_context(&Self, context),
+            None => Ok(vec![]),
         }
+    }
+}
 
-        let (_, first_argument) = node.accessor_or_arguments.first().unwrap();
-
-        let mut current_evaluator = match &first_argument.ty {
+impl FunctionCallEvaluatorBuilder {
+    /// Applies one call, property access or index to `current_evaluator`.
+    fn build_access(
+        current_evaluator: Box<dyn Evaluable>,
+        access: &AccessorOrArguments,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Evaluable>> {
+        match &access.ty {
             AccessorOrArgumentsType::ArgumentsList(arguments_list) => {
                 let function_evaluator = FunctionEvaluator::new(
-                    primary_evaluator,
+                    current_evaluator,
                     arguments_list.accept_with_context(&Self, context)?,
                     arguments_list
                         .token_list
                         .last()
                         .ok_or(RuntimeError::ASTInvalidStructure)?
-                        .line,
+                        .span
+                        .clone(),
                 );
-                Box::new(function_evaluator) as Box<dyn Evaluable>
+                Ok(Box::new(function_evaluator))
             }
             AccessorOrArgumentsType::Accessor(accessor) => {
                 let class_accessor_evaluator = ClassAccessorEvaluator::new(
-                    primary_evaluator,
+                    current_evaluator,
                     accessor.identifier.token.lexeme.clone(),
-                    accessor.identifier.token.line,
+                    accessor.identifier.token.span.clone(),
                 );
-                Box::new(class_accessor_evaluator) as Box<dyn Evaluable>
+                Ok(Box::new(class_accessor_evaluator))
             }
-            _ => unreachable!(),
-        };
+            AccessorOrArgumentsType::Index(index) => {
+                let index_evaluator = IndexEvaluator::new(
+                    current_evaluator,
+                    index
+                        .expression
+                        .accept_with_context(&AssignmentEvaluatorBuilder, context)?,
+                    index
+                        .bracket()
+                        .ok_or(RuntimeError::ASTInvalidStructure)?
+                        .span
+                        .clone(),
+                );
+                Ok(Box::new(index_evaluator))
+            }
+            AccessorOrArgumentsType::None => Err(RuntimeError::ASTInvalidStructure),
+        }
+    }
+}
+
+impl VisitorWithContext<&Call, Result<Box<dyn Evaluable>>, BuilderContext>
+    for FunctionCallEvaluatorBuilder
+{
+    fn visit_with_context(
+        &self,
+        node: &Call,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Evaluable>> {
+        let mut current_evaluator = node
+            .primary
+            .accept_with_context(&PrimaryEvaluatorBuilder, context)?;
 
-        for (_, arguments_list) in node.accessor_or_arguments.iter().skip(1) {
-            current_evaluator = match &arguments_list.ty {
-                AccessorOrArgumentsType::ArgumentsList(arguments_list) => {
-                    let function_evaluator = FunctionEvaluator::new(
-                        current_evaluator,
-                        arguments_list.accept_with_context(&Self, context)?,
-                        arguments_list
-                            .token_list
-                            .last()
-                            .ok_or(RuntimeError::ASTInvalidStructure)?
-                            .line,
-                    );
-                    Box::new(function_evaluator) as Box<dyn Evaluable>
-                }
-                AccessorOrArgumentsType::Accessor(accessor) => {
-                    let class_accessor_evaluator = ClassAccessorEvaluator::new(
-                        current_evaluator,
-                        accessor.identifier.token.lexeme.clone(),
-                        accessor.identifier.token.line,
-                    );
-                    Box::new(class_accessor_evaluator) as Box<dyn Evaluable>
-                }
-                _ => unreachable!(),
-            };
+        for (_, access) in node.accessor_or_arguments.iter() {
+            current_evaluator = Self::build_access(current_evaluator, access, context)?;
         }
 
         Ok(current_evaluator)
@@ -4,3 +4,23 @@ mod function_call_evaluator_builder;
 mod primary_evaluator_builder;
 
 pub use assignment_evaluator_builder::AssignmentEvaluatorBuilder;
+
+use super::{BranchEvaluator, Evaluable};
+use crate::evaluation::{BuilderContext, TracePoint};
+use crate::tokenizer::Token;
+
+/// Lets an installed `RunHook` see which way the branch at `token` goes as
+/// `condition` is evaluated.
+pub fn traced_branch(
+    condition: Box<dyn Evaluable>,
+    token: Option<&Token>,
+    context: &BuilderContext,
+) -> Box<dyn Evaluable> {
+    match token {
+        Some(token) if context.traced.get() => {
+            context.trace(TracePoint::Branch(token.span.clone()));
+            Box::new(BranchEvaluator::new(condition, token.span.clone()))
+        }
+        _ => condition,
+    }
+}
@@ -1,12 +1,15 @@
 use super::super::super::runtime_value::{Result, RuntimeError};
 use super::super::super::BuilderContext;
-use super::super::evaluator::{Evaluable, PrimaryEvaluator};
+use super::super::evaluator::{
+    EntryEvaluator, Evaluable, ListEvaluator, MapEvaluator, PrimaryEvaluator,
+};
 use super::super::evaluator::{UnaryEvaluator, UnaryOperation};
 use crate::common::{Visitable, VisitorWithContext};
 use crate::evaluation::evaluator::evaluator_builders::assignment_evaluator_builder::AssignmentEvaluatorBuilder;
 use crate::evaluation::evaluator::evaluator_builders::function_call_evaluator_builder::FunctionCallEvaluatorBuilder;
 use crate::evaluation::resolver::ResolverError;
-use crate::syntax_analysis::{PrimaryExpression, PrimaryExpressionType};
+use crate::evaluation::run::RunnableBuilder;
+use crate::syntax_analysis::{MapEntry, PrimaryExpression, PrimaryExpressionType};
 use crate::syntax_analysis::{
     UnaryExpression, UnaryExpressionSelf, UnaryExpressionSelfType, UnaryExpressionType,
 };
@@ -14,6 +17,24 @@ use crate::tokenizer::TokenValue;
 
 pub struct PrimaryEvaluatorBuilder;
 
+impl PrimaryEvaluatorBuilder {
+    fn build_entry(entry: &MapEntry, context: &BuilderContext) -> Result<EntryEvaluator> {
+        let colon = entry
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        Ok((
+            entry
+                .key
+                .accept_with_context(&AssignmentEvaluatorBuilder, context)?,
+            entry
+                .value
+                .accept_with_context(&AssignmentEvaluatorBuilder, context)?,
+            colon.span.clone(),
+        ))
+    }
+}
+
 impl VisitorWithContext<&PrimaryExpression, Result<Box<dyn Evaluable>>, BuilderContext>
     for PrimaryEvaluatorBuilder
 {
@@ -32,7 +53,7 @@ impl VisitorWithContext<&PrimaryExpression, Result<Box<dyn Evaluable>>, BuilderC
                     PrimaryExpressionType::True => Ok(Box::new(PrimaryEvaluator::Boolean(true))),
                     PrimaryExpressionType::This => {
                         if !context.resolver.borrow().is_in_class() {
-                            return Err(ResolverError::ThisOutsideClass(token.line).into());
+                            return Err(ResolverError::ThisOutsideClass(token.span.clone()).into());
                         }
                         Ok(Box::new(PrimaryEvaluator::This))
                     }
@@ -55,18 +76,43 @@ impl VisitorWithContext<&PrimaryExpression, Result<Box<dyn Evaluable>>, BuilderC
                     PrimaryExpressionType::Expression(expr) => {
                         expr.accept_with_context(&AssignmentEvaluatorBuilder, context)
                     }
+                    PrimaryExpressionType::ListElements(elements) => {
+                        let elements = match elements {
+                            Some(elements) => elements
+                                .accept_with_context(&FunctionCallEvaluatorBuilder, context)?,
+                            None => vec![],
+                        };
+                        Ok(Box::new(ListEvaluator::new(elements)))
+                    }
+                    PrimaryExpressionType::MapEntries(entries) => {
+                        let mut evaluators = Vec::new();
+                        if let Some(entries) = entries {
+                            evaluators.push(Self::build_entry(&entries.first, context)?);
+                            for (_, entry) in &entries.rest {
+                                evaluators.push(Self::build_entry(entry, context)?);
+                            }
+                        }
+                        Ok(Box::new(MapEvaluator::new(evaluators)))
+                    }
                     PrimaryExpressionType::None => Err(RuntimeError::ASTInvalidStructure),
                 }
             }
             PrimaryExpression::Super(identifier) => {
                 if !context.resolver.borrow().is_in_class() {
-                    return Err(ResolverError::ThisOutsideClass(identifier.token.line).into());
+                    return Err(
+                        ResolverError::ThisOutsideClass(identifier.token.span.clone()).into(),
+                    );
                 }
                 if !context.resolver.borrow().is_super_available() {
-                    return Err(ResolverError::SuperNotAvailable(identifier.token.line).into());
+                    return Err(
+                        ResolverError::SuperNotAvailable(identifier.token.span.clone()).into(),
+                    );
                 }
                 Ok(Box::new(PrimaryEvaluator::Super(identifier.token.clone())))
             }
+            PrimaryExpression::Lambda(lambda) => {
+                lambda.accept_with_context(&RunnableBuilder, context)
+            }
         }
     }
 }
@@ -109,6 +155,14 @@ impl VisitorWithContext<&UnaryExpressionSelf, Result<Box<dyn Evaluable>>, Builde
             UnaryExpressionSelfType::Minus => UnaryOperation::Negation,
             UnaryExpressionSelfType::None => return Err(RuntimeError::ASTInvalidStructure),
         };
-        Ok(Box::new(UnaryEvaluator::new(operation, evaluator)))
+        let operator = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        Ok(Box::new(UnaryEvaluator::new(
+            operation,
+            evaluator,
+            operator.span.clone(),
+        )))
     }
 }
@@ -3,4 +3,4 @@ mod evaluator_builders;
 
 // Re-export from the evaluator module
 pub use evaluator::*;
-pub use evaluator_builders::AssignmentEvaluatorBuilder;
+pub use evaluator_builders::{traced_branch, AssignmentEvaluatorBuilder};
@@ -1,15 +1,31 @@
+mod analyzer;
 mod evaluator;
 mod functions_resolver;
+mod modules;
 mod resolver;
 mod run;
 mod runtime_value;
+mod symbols;
+mod table;
+mod vm;
 
-use std::{cell::RefCell, collections::HashMap};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
 
+pub use analyzer::Analyzer;
 pub use evaluator::AssignmentEvaluatorBuilder;
 pub use resolver::ResolverError;
-pub use run::{Program, RunState};
-pub use runtime_value::{RuntimeError, RuntimeValue};
+pub use run::{
+    BufferSink, Coverage, Frame, LineCallbackSink, LinesSource, NativeFunctionError, OutputSink,
+    Profiler, Program, RunHook, RunState, SandboxPolicy, Session, StackTrace, StdoutSink,
+    TracePoint, DEFAULT_GC_THRESHOLD,
+};
+pub use runtime_value::{List, Map, RuntimeError, RuntimeValue};
+pub use symbols::{SymbolKind, Symbols};
+pub use table::{MapKey, Table};
+pub use vm::BytecodeProgram;
 
 // BuilderContext holds shared state for building evaluators and runnables
 #[derive(Debug, Default)]
@@ -17,6 +33,14 @@ pub struct BuilderContext {
     resolver: RefCell<resolver::Resolver>,
     functions_resolver: RefCell<functions_resolver::FunctionsResolver>,
     class_definitions: RefCell<HashMap<String, usize>>,
+    /// Whether statements are built to be seen by a `RunHook`
+    traced: Cell<bool>,
+    /// What was traced since the hook was last told
+    trace_points: RefCell<Vec<TracePoint>>,
+    /// The modules built so far, by canonical path
+    modules: RefCell<HashMap<PathBuf, Rc<dyn run::Runnable>>>,
+    /// The files whose imports are being built, the program's own first
+    importing: RefCell<Vec<modules::ModuleFile>>,
 }
 
 impl BuilderContext {
@@ -25,9 +49,17 @@ impl BuilderContext {
             resolver: RefCell::new(resolver::Resolver::new()?),
             functions_resolver: RefCell::new(functions_resolver::FunctionsResolver::new()?),
             class_definitions: RefCell::new(HashMap::new()),
+            traced: Cell::new(false),
+            trace_points: RefCell::new(Vec::new()),
+            modules: RefCell::new(HashMap::new()),
+            importing: RefCell::new(Vec::new()),
         })
     }
 
+    fn trace(&self, point: TracePoint) {
+        self.trace_points.borrow_mut().push(point);
+    }
+
     fn get_class_definition(&self, identifier: &str) -> Option<usize> {
         self.class_definitions.borrow().get(identifier).cloned()
     }
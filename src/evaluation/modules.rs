@@ -0,0 +1,131 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::syntax_analysis::{ParseStream, ProgramAst};
+use crate::tokenizer::{scan_tokens, Span, Token};
+
+/// Errors loading the module an `import` names. They point at the path in the
+/// `import`; what is wrong inside the module follows on the next lines.
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("[line {}] Error at '{0}': Can't read module: {1}", .2.line)]
+    Unreadable(String, std::io::Error, Span),
+
+    /// The import, the file it is in and the files in the cycle
+    #[error("[line {}] Error at '{0}': Import cycle in {1}: {2}.", .3.line)]
+    Cycle(String, String, String, Span),
+
+    #[error("[line {}] Error at '{0}': Module has errors.\n{1}", .2.line)]
+    InvalidModule(String, String, Span),
+}
+
+impl ImportError {
+    pub fn span(&self) -> &Span {
+        match self {
+            ImportError::Unreadable(_, _, span)
+            | ImportError::Cycle(_, _, _, span)
+            | ImportError::InvalidModule(_, _, span) => span,
+        }
+    }
+}
+
+/// A source file imported, or importing others
+#[derive(Debug, Clone)]
+pub struct ModuleFile {
+    /// The canonical path, which identifies the module
+    pub path: PathBuf,
+    /// The path as it is shown in errors and traces
+    pub name: String,
+}
+
+impl ModuleFile {
+    /// The file the string literal `token` names, relative to the directory of
+    /// the file it is written in.
+    pub fn resolve(token: &Token, target: &str) -> Result<Self, ImportError> {
+        let directory = Path::new(token.span.file.as_ref())
+            .parent()
+            .unwrap_or(Path::new(""));
+        let joined = directory.join(target);
+        let path = fs::canonicalize(&joined).map_err(|error| {
+            ImportError::Unreadable(token.lexeme.clone(), error, token.span.clone())
+        })?;
+
+        // `..` is folded into the name shown, but left for the file system to follow
+        let mut name = PathBuf::new();
+        for component in joined.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir
+                    if matches!(name.components().next_back(), Some(Component::Normal(_))) =>
+                {
+                    name.pop();
+                }
+                component => name.push(component),
+            }
+        }
+        let name = name.to_string_lossy().to_string();
+        Ok(Self { path, name })
+    }
+
+    /// The file a program was read from, if it was read from one.
+    pub fn of_program(file: &str) -> Option<Self> {
+        let path = fs::canonicalize(file).ok()?;
+        Some(Self {
+            path,
+            name: file.to_string(),
+        })
+    }
+
+    /// Reads and parses the module, reporting every error in it against the
+    /// `import` of it at `token`.
+    pub fn parse(&self, token: &Token) -> Result<ProgramAst, ImportError> {
+        let invalid = |errors: Vec<String>| {
+            ImportError::InvalidModule(token.lexeme.clone(), errors.join("\n"), token.span.clone())
+        };
+        let source = fs::read_to_string(&self.path).map_err(|error| {
+            ImportError::Unreadable(token.lexeme.clone(), error, token.span.clone())
+        })?;
+        let (tokens, errors) = scan_tokens(&self.name, &source);
+        if !errors.is_empty() {
+            return Err(invalid(errors.iter().map(ToString::to_string).collect()));
+        }
+        ProgramAst::parse_all(&mut ParseStream::new(tokens))
+            .map_err(|errors| invalid(errors.iter().map(ToString::to_string).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::Session;
+
+    #[test]
+    fn test_cycle_names_the_file_closing_it() {
+        let directory = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/lox/modules");
+        let filename = format!("{}/ring.lox", directory);
+        let source = fs::read_to_string(&filename).unwrap();
+        let (tokens, _) = scan_tokens(&filename, &source);
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        let error = Session::new()
+            .unwrap()
+            .build_program(&program)
+            .err()
+            .unwrap();
+
+        let ring_a = format!("{}/lib/ring_a.lox", directory);
+        let ring_b = format!("{}/lib/ring_b.lox", directory);
+        assert_eq!(
+            error.to_string().lines().collect::<Vec<_>>(),
+            [
+                "[line 2] Error at '\"lib/ring_a.lox\"': Module has errors.".to_string(),
+                "[line 1] Error at '\"ring_b.lox\"': Module has errors.".to_string(),
+                format!(
+                    "[line 1] Error at '\"../ring.lox\"': Import cycle in {}: {} -> {} -> {} -> {}.",
+                    ring_b, filename, ring_a, ring_b, filename
+                ),
+            ]
+        );
+    }
+}
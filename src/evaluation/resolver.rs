@@ -2,19 +2,21 @@ use std::collections::{HashMap, TryReserveError};
 
 use thiserror::Error;
 
+use crate::tokenizer::Span;
+
 #[derive(Error, Debug)]
 pub enum ResolverError {
-    #[error("[line {1}] Error at '{0}': Can't read local variable in its own initializer.")]
-    LocalVariableInInitializer(String, usize),
+    #[error("[line {}] Error at '{0}': Can't read local variable in its own initializer.", .1.line)]
+    LocalVariableInInitializer(String, Span),
 
-    #[error("[line {1}] Error at '{0}': Already a variable with this name in this scope.")]
-    AlreadyDeclaredIdentifier(String, usize),
+    #[error("[line {}] Error at '{0}': Already a variable with this name in this scope.", .1.line)]
+    AlreadyDeclaredIdentifier(String, Span),
 
-    #[error("[line {0}] Error at 'return': Can't return from top-level code.")]
-    ReturnOutsideFunction(usize),
+    #[error("[line {}] Error at 'return': Can't return from top-level code.", .0.line)]
+    ReturnOutsideFunction(Span),
 
-    #[error("[line {0}] Error at 'this': Can't use 'this' outside of a class.")]
-    ThisOutsideClass(usize),
+    #[error("[line {}] Error at 'this': Can't use 'this' outside of a class.", .0.line)]
+    ThisOutsideClass(Span),
 
     #[error("Identifier not found")]
     UndeclaredIdentifier,
@@ -25,14 +27,43 @@ pub enum ResolverError {
     #[error("Not enough space to allocate new scope")]
     NotEnoughSpace(#[from] TryReserveError),
 
-    #[error("[line {0}] Error at 'return': Can't return a value from an initializer.")]
-    ReturnFromInitializer(usize),
+    #[error("[line {}] Error at 'return': Can't return a value from an initializer.", .0.line)]
+    ReturnFromInitializer(Span),
+
+    #[error("[line {}] Error at '{0}': A class can't inherit from itself.", .1.line)]
+    InheritFromItself(String, Span),
+
+    #[error("[line {}] Error at 'super': Can't use 'super' in a class with no superclass.", .0.line)]
+    SuperNotAvailable(Span),
+
+    #[error("[line {}] Error at '{0}': Can't use '{0}' outside of a loop.", .1.line)]
+    OutsideLoop(&'static str, Span),
+
+    #[error("[line {}] Error at 'import': Can't import outside of top-level code.", .0.line)]
+    ImportOutsideTopLevel(Span),
 
-    #[error("[line {1}] Error at '{0}': A class can't inherit from itself.")]
-    InheritFromItself(String, usize),
+    #[error("[line {}] Error at 'try': Expect 'catch' or 'finally' after try block.", .0.line)]
+    TryWithoutHandler(Span),
+}
 
-    #[error("[line {0}] Error at 'super': Can't use 'super' in a class with no superclass.")]
-    SuperNotAvailable(usize),
+impl ResolverError {
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            ResolverError::LocalVariableInInitializer(_, span)
+            | ResolverError::AlreadyDeclaredIdentifier(_, span)
+            | ResolverError::InheritFromItself(_, span)
+            | ResolverError::OutsideLoop(_, span)
+            | ResolverError::ReturnOutsideFunction(span)
+            | ResolverError::ThisOutsideClass(span)
+            | ResolverError::ReturnFromInitializer(span)
+            | ResolverError::SuperNotAvailable(span)
+            | ResolverError::ImportOutsideTopLevel(span)
+            | ResolverError::TryWithoutHandler(span) => Some(span),
+            ResolverError::UndeclaredIdentifier
+            | ResolverError::NoAvailableScopes
+            | ResolverError::NotEnoughSpace(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Default)]
@@ -41,6 +72,9 @@ pub struct Resolver {
     function_depth: usize,
     class_depth: usize,
     method_stack: std::collections::LinkedList<(String, bool)>,
+    /// Loops enclosing the current statement, one count per function since a
+    /// loop doesn't reach into the functions declared in its body.
+    loop_depths: Vec<usize>,
 }
 
 const INITIAL_SCOPE_CAPACITY: usize = 50;
@@ -57,6 +91,7 @@ impl Resolver {
             function_depth: 0,
             class_depth: 0,
             method_stack,
+            loop_depths: vec![0],
         })
     }
 
@@ -74,7 +109,17 @@ impl Resolver {
         Ok(())
     }
 
-    pub fn declare(&mut self, identifier: &str, line: usize) -> Result<(), ResolverError> {
+    /// Drops every local scope and function/class marker, keeping the globals.
+    /// Used to recover after a failed build so the next one starts from the top level.
+    pub fn reset(&mut self) {
+        self.scopes.truncate(1);
+        self.function_depth = 0;
+        self.class_depth = 0;
+        self.method_stack.clear();
+        self.loop_depths = vec![0];
+    }
+
+    pub fn declare(&mut self, identifier: &str, span: &Span) -> Result<(), ResolverError> {
         let in_global_scope = self.scopes.len() == 1;
 
         let last_scope = self
@@ -85,7 +130,7 @@ impl Resolver {
         if last_scope.contains_key(identifier) && !in_global_scope {
             return Err(ResolverError::AlreadyDeclaredIdentifier(
                 identifier.to_string(),
-                line,
+                span.clone(),
             ));
         }
 
@@ -102,7 +147,7 @@ impl Resolver {
         {
             return Err(ResolverError::AlreadyDeclaredIdentifier(
                 identifier.to_string(),
-                line,
+                span.clone(),
             ));
         }
 
@@ -124,7 +169,7 @@ impl Resolver {
         Ok(())
     }
 
-    pub fn resolve(&self, identifier: &str, line: usize) -> Result<Option<usize>, ResolverError> {
+    pub fn resolve(&self, identifier: &str, span: &Span) -> Result<Option<usize>, ResolverError> {
         for (index, scope) in self.scopes.iter().rev().enumerate() {
             if let Some(value) = scope.get(identifier) {
                 let is_global_scope = index == self.scopes.len() - 1;
@@ -133,7 +178,7 @@ impl Resolver {
                 }
                 return Err(ResolverError::LocalVariableInInitializer(
                     identifier.to_string(),
-                    line,
+                    span.clone(),
                 ));
             }
         }
@@ -143,17 +188,25 @@ impl Resolver {
     pub fn enter_function(&mut self, name: String, super_available: bool) {
         self.method_stack.push_back((name, super_available));
         self.function_depth += 1;
+        self.loop_depths.push(0);
     }
 
     pub fn exit_function_or_method(&mut self) {
         self.method_stack.pop_back();
         self.function_depth -= 1;
+        self.loop_depths.pop();
     }
 
     pub fn function_depth(&self) -> usize {
         self.function_depth
     }
 
+    /// Whether code here runs at the top level of its file, outside any block
+    /// or function.
+    pub fn is_at_top_level(&self) -> bool {
+        self.scopes.len() == 1 && self.function_depth == 0
+    }
+
     pub fn enter_class(&mut self) {
         self.class_depth += 1;
     }
@@ -169,6 +222,23 @@ impl Resolver {
     pub fn enter_method(&mut self, name: String, super_available: bool) {
         self.method_stack.push_back((name, super_available));
         self.function_depth += 1;
+        self.loop_depths.push(0);
+    }
+
+    pub fn enter_loop(&mut self) {
+        if let Some(depth) = self.loop_depths.last_mut() {
+            *depth += 1;
+        }
+    }
+
+    pub fn exit_loop(&mut self) {
+        if let Some(depth) = self.loop_depths.last_mut() {
+            *depth -= 1;
+        }
+    }
+
+    pub fn is_in_loop(&self) -> bool {
+        self.loop_depths.last().is_some_and(|depth| *depth > 0)
     }
 
     pub fn is_in_method(&self) -> Option<String> {
@@ -0,0 +1,164 @@
+use std::cell::{RefCell, RefMut};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::rc::Rc;
+
+use crate::evaluation::run::{Frame, RunHook, TracePoint};
+use crate::evaluation::RuntimeError;
+use crate::tokenizer::Span;
+
+/// Records which statements ran and which way each branch went, for an LCOV
+/// report. Clones share what was recorded, so a clone kept by the host can
+/// report on a run once the coverage has been handed to the session as its
+/// hook.
+#[derive(Clone, Default)]
+pub struct Coverage {
+    files: Rc<RefCell<BTreeMap<Rc<str>, FileCoverage>>>,
+}
+
+#[derive(Default)]
+struct FileCoverage {
+    /// How many statements ran on each line with any.
+    lines: BTreeMap<usize, usize>,
+    /// How many times each branch, by line and byte offset, went each way:
+    /// the condition truthy first.
+    branches: BTreeMap<(usize, usize), [usize; 2]>,
+}
+
+impl Coverage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn file(&self, span: &Span) -> RefMut<'_, FileCoverage> {
+        RefMut::map(self.files.borrow_mut(), |files| {
+            files.entry(span.file.clone()).or_default()
+        })
+    }
+
+    /// The report in LCOV's tracefile format, a record per source file.
+    pub fn lcov(&self) -> String {
+        let mut lcov = String::new();
+        for (file, coverage) in self.files.borrow().iter() {
+            let _ = writeln!(lcov, "TN:\nSF:{}", file);
+
+            let mut block = 0;
+            let mut previous_line = 0;
+            for (&(line, _), taken) in &coverage.branches {
+                block = if line == previous_line { block + 1 } else { 0 };
+                previous_line = line;
+                // A branch never reached isn't counted as never taken
+                let reached = taken[0] + taken[1] > 0;
+                for (branch, count) in taken.iter().enumerate() {
+                    let count = match reached {
+                        true => count.to_string(),
+                        false => "-".to_string(),
+                    };
+                    let _ = writeln!(lcov, "BRDA:{},{},{},{}", line, block, branch, count);
+                }
+            }
+            let branches_hit = coverage
+                .branches
+                .values()
+                .flatten()
+                .filter(|&&count| count > 0)
+                .count();
+            let _ = writeln!(lcov, "BRF:{}", coverage.branches.len() * 2);
+            let _ = writeln!(lcov, "BRH:{}", branches_hit);
+
+            for (line, hits) in &coverage.lines {
+                let _ = writeln!(lcov, "DA:{},{}", line, hits);
+            }
+            let lines_hit = coverage.lines.values().filter(|&&hits| hits > 0).count();
+            let _ = writeln!(lcov, "LF:{}", coverage.lines.len());
+            let _ = writeln!(lcov, "LH:{}", lines_hit);
+            lcov.push_str("end_of_record\n");
+        }
+        lcov
+    }
+}
+
+impl RunHook for Coverage {
+    fn traced(&mut self, points: &[TracePoint]) {
+        for point in points {
+            match point {
+                TracePoint::Statement(span) => {
+                    self.file(span).lines.entry(span.line).or_default();
+                }
+                TracePoint::Branch(span) => {
+                    let key = (span.line, span.start);
+                    self.file(span).branches.entry(key).or_default();
+                }
+            }
+        }
+    }
+
+    fn before_statement(&mut self, frame: &Frame) -> Result<(), RuntimeError> {
+        let span = frame.span();
+        *self.file(span).lines.entry(span.line).or_default() += 1;
+        Ok(())
+    }
+
+    fn branch(&mut self, span: &Span, taken: bool) {
+        let key = (span.line, span.start);
+        let mut file = self.file(span);
+        file.branches.entry(key).or_default()[usize::from(!taken)] += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{BufferSink, Session};
+    use crate::syntax_analysis::{ParseStream, ProgramAst};
+    use crate::tokenizer::scan_tokens;
+
+    #[test]
+    fn test_reports_lines_and_branches_run() {
+        let source = "\
+fun sign(n) {
+  if (n < 0) return -1;
+  return 1;
+}
+print sign(2);
+if (false or true) print \"yes\";
+fun unused() {
+  print 1;
+}
+";
+        let (tokens, _) = scan_tokens("test.lox", source);
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        let session = Session::new().unwrap();
+        session.set_output(Box::new(BufferSink::new()));
+        let coverage = Coverage::new();
+        session.set_hook(Box::new(coverage.clone()));
+        let runnable = session.build_program(&program).unwrap();
+        session.run(runnable.as_ref()).unwrap();
+
+        assert_eq!(
+            coverage.lcov(),
+            "\
+TN:
+SF:test.lox
+BRDA:2,0,0,0
+BRDA:2,0,1,1
+BRDA:6,0,0,1
+BRDA:6,0,1,0
+BRDA:6,1,0,0
+BRDA:6,1,1,1
+BRF:6
+BRH:3
+DA:1,1
+DA:2,1
+DA:3,1
+DA:5,1
+DA:6,2
+DA:7,1
+DA:8,0
+LF:7
+LH:6
+end_of_record
+"
+        );
+    }
+}
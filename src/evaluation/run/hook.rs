@@ -0,0 +1,112 @@
+use crate::common::Visitable;
+use crate::evaluation::evaluator::AssignmentEvaluatorBuilder;
+use crate::evaluation::run::{CallFrame, RunState};
+use crate::evaluation::{BuilderContext, RuntimeError, RuntimeValue};
+use crate::syntax_analysis::Expression;
+use crate::tokenizer::Span;
+
+/// Where a traced program can be seen to run from
+#[derive(Debug, Clone, PartialEq)]
+pub enum TracePoint {
+    /// A statement, at its first token
+    Statement(Span),
+    /// An `if`, `and` or `or`, at its keyword
+    Branch(Span),
+}
+
+/// Observes a tree-walker run statement by statement and call by call, as
+/// the `debug` command's debugger and the `run --profile` profiler do.
+/// Installed with `Session::set_hook`.
+pub trait RunHook {
+    /// Called before each statement runs. An error stops the program.
+    fn before_statement(&mut self, frame: &Frame) -> Result<(), RuntimeError>;
+
+    /// Called once code is built, with every point it can be seen to run from.
+    fn traced(&mut self, _points: &[TracePoint]) {}
+
+    /// Called as the program goes one way at a branch: `taken` is whether the
+    /// condition, or the left operand of `and` or `or`, is truthy.
+    fn branch(&mut self, _span: &Span, _taken: bool) {}
+
+    /// Called as a function, method or class is called, with its index in the
+    /// functions resolver and a way to name it, such as `fib()`, `A.get()` or
+    /// `A()` for a class.
+    fn enter_call(&mut self, _index: usize, _name: &dyn Fn() -> String) {}
+
+    /// Called as the innermost call entered returns or fails.
+    fn exit_call(&mut self) {}
+}
+
+/// The state of a paused program: where it is, the calls in progress and the
+/// scopes of the code about to run.
+pub struct Frame<'a> {
+    state: &'a RunState,
+    context: &'a BuilderContext,
+    span: &'a Span,
+}
+
+impl<'a> Frame<'a> {
+    pub fn new(state: &'a RunState, context: &'a BuilderContext, span: &'a Span) -> Self {
+        Self {
+            state,
+            context,
+            span,
+        }
+    }
+
+    /// The first token of the statement about to run.
+    pub fn span(&self) -> &Span {
+        self.span
+    }
+
+    /// The calls in progress, outermost first.
+    pub fn call_stack(&self) -> Vec<CallFrame> {
+        self.state.call_stack()
+    }
+
+    /// The variables of each local scope, innermost first and sorted by name.
+    /// The global scope is left out.
+    pub fn locals(&self) -> Vec<Vec<(String, RuntimeValue)>> {
+        let mut locals = Vec::new();
+        let mut scope = self.state.get_current_scope();
+        loop {
+            let Some(enclosing) = scope.borrow().get_enclosing() else {
+                return locals;
+            };
+            let mut variables = scope
+                .borrow()
+                .variables()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect::<Vec<_>>();
+            variables.sort_by(|(a, _), (b, _)| a.cmp(b));
+            locals.push(variables);
+            scope = enclosing;
+        }
+    }
+
+    /// Evaluates `expression` as if it were written in the statement about to
+    /// run. Names are looked up from the innermost scope outwards.
+    pub fn evaluate(&self, expression: &Expression) -> Result<RuntimeValue, RuntimeError> {
+        let in_method = self.state.get_this().is_some();
+        if in_method {
+            self.context.resolver.borrow_mut().enter_class();
+        }
+        let evaluator = expression.accept_with_context(&AssignmentEvaluatorBuilder, self.context);
+        if evaluator.is_err() {
+            self.context.resolver.borrow_mut().reset();
+        } else if in_method {
+            self.context.resolver.borrow_mut().exit_class();
+        }
+
+        // A call that fails leaves its frame behind, which isn't the program's
+        let depth = self.state.call_stack().len();
+        let value = evaluator?.eval(self.state);
+        self.state.unwind_call_stack(depth);
+        value
+    }
+
+    /// Writes a line where the program prints.
+    pub fn write_line(&self, line: &str) {
+        self.state.write_line(line);
+    }
+}
@@ -1,57 +1,61 @@
+mod coverage;
+mod hook;
+mod profiler;
 mod run_state;
 mod runnable;
 mod runnable_builders;
+mod session;
 
-pub use run_state::{RunScopeRef, RunScopes, RunState};
-pub use runnable::Runnable;
-pub use runnable::{Callable, NativeFunctionError};
+pub use coverage::Coverage;
+pub use hook::{Frame, RunHook, TracePoint};
+pub use profiler::Profiler;
+pub use run_state::{
+    BufferSink, CallFrame, InputSource, LineCallbackSink, LinesSource, OutputSink, RunScopeRef,
+    RunScopes, RunState, SandboxPolicy, StackTrace, StdoutSink, DEFAULT_GC_THRESHOLD,
+};
+pub use runnable::{get_native_functions, Callable, NativeFunctionError};
+pub use runnable::{ControlFlow, RunResult, Runnable};
+pub use runnable_builders::RunnableBuilder;
+pub use session::Session;
 
-use crate::common::Visitable;
-use crate::evaluation::run::runnable::get_native_functions;
-use crate::evaluation::runtime_value::CallableType;
-use crate::evaluation::{BuilderContext, RuntimeValue};
 use crate::{evaluation::RuntimeError, syntax_analysis::ProgramAst};
-use runnable_builders::RunnableBuilder;
 
 pub struct Program {
     program: Box<dyn Runnable>,
-    state: RunState,
+    session: Session,
 }
 
 impl Program {
     pub fn new_with_context(program_ast: ProgramAst) -> Result<Self, RuntimeError> {
-        let context = BuilderContext::new()?;
-        let mut scopes = RunScopes::new(None);
-        Self::initialize_context(&context, &mut scopes)?;
-        let runner = program_ast.accept_with_context(&RunnableBuilder, &context)?;
-        let state = RunState::new(context.functions_resolver.take(), scopes);
-        Ok(Self {
-            program: runner,
-            state,
-        })
+        let session = Session::new()?;
+        let program = session.build_program(&program_ast)?;
+        Ok(Self { program, session })
     }
 
-    fn initialize_context(
-        context: &BuilderContext,
-        scopes: &mut RunScopes,
-    ) -> Result<(), RuntimeError> {
-        let functions = get_native_functions();
-        for (name, function) in functions.into_iter() {
-            let pointer = context
-                .functions_resolver
-                .borrow_mut()
-                .add_function(function)?;
-            context.resolver.borrow_mut().declare(name, 0)?;
-            context.resolver.borrow_mut().define(name)?;
-            let callable_value =
-                RuntimeValue::callable(pointer, name.to_string(), None, CallableType::Function);
-            scopes.declare_variable(name.to_string(), Some(callable_value), None);
-        }
-
-        Ok(())
+    /// Builds the program to be observed by `hook` as it runs.
+    pub fn new_with_hook(
+        program_ast: ProgramAst,
+        hook: Box<dyn RunHook>,
+    ) -> Result<Self, RuntimeError> {
+        let session = Session::new()?;
+        session.set_hook(hook);
+        let program = session.build_program(&program_ast)?;
+        Ok(Self { program, session })
+    }
+
+    pub fn set_gc_threshold(&self, threshold: Option<usize>) {
+        self.session.set_gc_threshold(threshold);
+    }
+
+    pub fn set_sandbox(&self, sandbox: SandboxPolicy) {
+        self.session.set_sandbox(sandbox);
+    }
+
+    pub fn set_script_arguments(&self, arguments: Vec<String>) {
+        self.session.set_script_arguments(arguments);
     }
 
     pub fn run(&mut self) -> Result<(), RuntimeError> {
-        self.program.run(&mut self.state).map(|_| ())
+        self.session.run(self.program.as_ref())
     }
 }
@@ -0,0 +1,272 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::evaluation::run::{Frame, RunHook};
+use crate::evaluation::RuntimeError;
+
+/// What the top level of a program is called in reports.
+const SCRIPT_NAME: &str = "script";
+
+/// Counts calls and the statements run on each line, and times every call.
+/// Clones share what was recorded, so a clone kept by the host can report on
+/// a run once the profiler has been handed to the session as its hook.
+#[derive(Clone)]
+pub struct Profiler {
+    profile: Rc<RefCell<Profile>>,
+}
+
+#[derive(Default)]
+struct Profile {
+    functions: Vec<FunctionProfile>,
+    /// Where each function is in `functions`, by functions resolver index
+    /// (none for the script).
+    keys: HashMap<Option<usize>, usize>,
+    /// Calls in progress, the script outermost.
+    calls: Vec<OpenCall>,
+    /// Time spent in each call stack itself, by the functions on it.
+    stacks: BTreeMap<Vec<usize>, Duration>,
+    /// How many statements ran on each line.
+    lines: BTreeMap<usize, usize>,
+}
+
+struct FunctionProfile {
+    /// The name of the first call, as every call to a function is named alike.
+    name: String,
+    calls: usize,
+    inclusive: Duration,
+    exclusive: Duration,
+}
+
+struct OpenCall {
+    function: usize,
+    start: Instant,
+    /// Time spent in the calls made from this one.
+    callees: Duration,
+    /// The functions called to get here, this one last.
+    stack: Vec<usize>,
+}
+
+impl Profile {
+    fn enter(&mut self, index: Option<usize>, name: &dyn Fn() -> String) {
+        let next = self.functions.len();
+        let function = *self.keys.entry(index).or_insert(next);
+        if function == next {
+            self.functions.push(FunctionProfile {
+                name: name(),
+                calls: 0,
+                inclusive: Duration::ZERO,
+                exclusive: Duration::ZERO,
+            });
+        }
+        self.functions[function].calls += 1;
+
+        let mut stack = self
+            .calls
+            .last()
+            .map_or_else(Vec::new, |caller| caller.stack.clone());
+        stack.push(function);
+        self.calls.push(OpenCall {
+            function,
+            start: Instant::now(),
+            callees: Duration::ZERO,
+            stack,
+        });
+    }
+
+    fn exit(&mut self) {
+        let Some(call) = self.calls.pop() else {
+            return;
+        };
+        let elapsed = call.start.elapsed();
+        let exclusive = elapsed.saturating_sub(call.callees);
+        let function = &mut self.functions[call.function];
+        function.exclusive += exclusive;
+        // A recursive call's time is already in the outermost one
+        if !self.calls.iter().any(|open| open.function == call.function) {
+            function.inclusive += elapsed;
+        }
+        *self.stacks.entry(call.stack).or_default() += exclusive;
+        if let Some(caller) = self.calls.last_mut() {
+            caller.callees += elapsed;
+        }
+    }
+}
+
+impl Profiler {
+    /// Starts timing the script.
+    pub fn new() -> Self {
+        let mut profile = Profile::default();
+        profile.enter(None, &|| SCRIPT_NAME.to_string());
+        Self {
+            profile: Rc::new(RefCell::new(profile)),
+        }
+    }
+
+    /// Stops timing the script, and any calls an error left unfinished.
+    pub fn finish(&self) {
+        let mut profile = self.profile.borrow_mut();
+        while !profile.calls.is_empty() {
+            profile.exit();
+        }
+    }
+
+    /// The functions by the time spent in them, most first, then the lines by
+    /// how many statements ran on them, most first.
+    pub fn report(&self) -> String {
+        let profile = self.profile.borrow();
+        let mut functions = profile.functions.iter().collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.exclusive.cmp(&a.exclusive).then(b.calls.cmp(&a.calls)));
+        let mut lines = profile.lines.iter().collect::<Vec<_>>();
+        lines.sort_by(|(a_line, a_statements), (b_line, b_statements)| {
+            b_statements.cmp(a_statements).then(a_line.cmp(b_line))
+        });
+
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "{:>10}  {:>14}  {:>14}  function",
+            "calls", "inclusive ms", "exclusive ms"
+        );
+        for function in functions {
+            let _ = writeln!(
+                report,
+                "{:>10}  {:>14.3}  {:>14.3}  {}",
+                function.calls,
+                function.inclusive.as_secs_f64() * 1000.0,
+                function.exclusive.as_secs_f64() * 1000.0,
+                function.name
+            );
+        }
+        let _ = writeln!(report, "\n{:>10}  line", "statements");
+        for (line, statements) in lines {
+            let _ = writeln!(report, "{:>10}  {}", statements, line);
+        }
+        report
+    }
+
+    /// Each call stack with the microseconds spent in it, in the collapsed
+    /// format flame graph tools read.
+    pub fn collapsed_stacks(&self) -> String {
+        let profile = self.profile.borrow();
+        let mut stacks = String::new();
+        for (stack, time) in &profile.stacks {
+            let names = stack
+                .iter()
+                .map(|&function| profile.functions[function].name.as_str())
+                .collect::<Vec<_>>();
+            let _ = writeln!(stacks, "{} {}", names.join(";"), time.as_micros());
+        }
+        stacks
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunHook for Profiler {
+    fn before_statement(&mut self, frame: &Frame) -> Result<(), RuntimeError> {
+        *self
+            .profile
+            .borrow_mut()
+            .lines
+            .entry(frame.span().line)
+            .or_default() += 1;
+        Ok(())
+    }
+
+    fn enter_call(&mut self, index: usize, name: &dyn Fn() -> String) {
+        self.profile.borrow_mut().enter(Some(index), name);
+    }
+
+    fn exit_call(&mut self) {
+        self.profile.borrow_mut().exit();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::{BufferSink, Session};
+    use crate::syntax_analysis::{ParseStream, ProgramAst};
+    use crate::tokenizer::scan_tokens;
+
+    fn profile(source: &str) -> Profiler {
+        let (tokens, _) = scan_tokens("test.lox", source);
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        let session = Session::new().unwrap();
+        session.set_output(Box::new(BufferSink::new()));
+        let profiler = Profiler::new();
+        session.set_hook(Box::new(profiler.clone()));
+        let runnable = session.build_program(&program).unwrap();
+        session.run(runnable.as_ref()).unwrap();
+        profiler.finish();
+        profiler
+    }
+
+    fn calls(profile: &Profile) -> Vec<(&str, usize)> {
+        profile
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.calls))
+            .collect()
+    }
+
+    #[test]
+    fn test_counts_calls_and_lines() {
+        let source = "\
+fun fib(n) {
+  if (n < 2) return n;
+  return fib(n - 1) + fib(n - 2);
+}
+print fib(5);
+print clock() > 0;
+";
+        let profiler = profile(source);
+        let profile = profiler.profile.borrow();
+        assert_eq!(
+            calls(&profile),
+            [("script", 1), ("fib()", 15), ("clock()", 1)]
+        );
+        let lines = profile.lines.iter().map(|(&line, &hits)| (line, hits));
+        assert_eq!(
+            lines.collect::<Vec<_>>(),
+            // The `if` on line 2 runs 15 times and its `return` 8 of them
+            [(1, 1), (2, 23), (3, 7), (5, 1), (6, 1)]
+        );
+        let stacks = profiler.collapsed_stacks();
+        assert!(stacks.contains("script;fib();fib();fib();fib();fib() "));
+        assert!(stacks.contains("script;clock() "));
+    }
+
+    #[test]
+    fn test_counts_each_declaration_once() {
+        let source = "\
+class A { get() {} }
+class B < A {}
+A().get();
+B().get();
+fun f() {}
+f();
+{ fun f() {} f(); }
+";
+        let profiler = profile(source);
+        assert_eq!(
+            calls(&profiler.profile.borrow()),
+            [
+                ("script", 1),
+                ("A()", 1),
+                ("A.get()", 2),
+                ("B()", 1),
+                ("f()", 1),
+                ("f()", 1)
+            ]
+        );
+        assert!(profiler.collapsed_stacks().contains("script;B() "));
+    }
+}
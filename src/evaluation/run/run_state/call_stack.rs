@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+use crate::tokenizer::Span;
+
+/// A call in progress: the function called, the class it is a method of and
+/// the call expression it was made from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallFrame {
+    pub function: String,
+    pub class: Option<String>,
+    pub span: Span,
+}
+
+impl CallFrame {
+    pub fn new(function: String, class: Option<String>, span: Span) -> Self {
+        Self {
+            function,
+            class,
+            span,
+        }
+    }
+}
+
+impl Display for CallFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.class {
+            Some(class) => write!(f, "{}.{}()", class, self.function),
+            None => write!(f, "{}()", self.function),
+        }
+    }
+}
+
+/// The calls an uncaught error escaped from, outermost first, and the line
+/// the error was raised at when it is known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackTrace {
+    frames: Vec<CallFrame>,
+    line: Option<usize>,
+}
+
+impl StackTrace {
+    pub fn new(frames: Vec<CallFrame>, line: Option<usize>) -> Self {
+        Self { frames, line }
+    }
+}
+
+impl Display for StackTrace {
+    /// Prints the innermost call first. Each call is shown with the line it
+    /// was executing: where the error was raised for the innermost one and
+    /// where the next call was made from for the others.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut line = self.line;
+        for frame in self.frames.iter().rev() {
+            match line {
+                Some(line) => writeln!(f, "[line {}] in {}", line, frame)?,
+                None => writeln!(f, "in {}", frame)?,
+            }
+            line = Some(frame.span.line);
+        }
+        match line {
+            Some(line) => write!(f, "[line {}] in script", line),
+            None => write!(f, "in script"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(line: usize) -> Span {
+        Span {
+            line,
+            ..Span::default()
+        }
+    }
+
+    #[test]
+    fn test_trace_prints_innermost_call_first() {
+        let trace = StackTrace::new(
+            vec![
+                CallFrame::new("fib".to_string(), None, line(20)),
+                CallFrame::new("add".to_string(), Some("Math".to_string()), line(12)),
+            ],
+            Some(3),
+        );
+        assert_eq!(
+            trace.to_string(),
+            "[line 3] in Math.add()\n[line 12] in fib()\n[line 20] in script"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use crate::evaluation::{
+    run::{run_state::InstanceManager, RunScopeRef, RunScopes},
+    runtime_value::ThisInstance,
+    List, Map, RuntimeValue, Table,
+};
+
+pub const DEFAULT_GC_THRESHOLD: usize = 1024;
+
+/// Decides when a collection is due. A collection runs once `threshold`
+/// instances have been allocated since the previous one; the threshold grows
+/// with the number of survivors so large live heaps are not traced on every
+/// few allocations.
+pub struct GarbageCollector {
+    threshold: Option<usize>,
+    next_collection: usize,
+    allocations: usize,
+}
+
+impl GarbageCollector {
+    pub fn new(threshold: Option<usize>) -> Self {
+        Self {
+            threshold,
+            next_collection: threshold.unwrap_or(0),
+            allocations: 0,
+        }
+    }
+
+    pub fn set_threshold(&mut self, threshold: Option<usize>) {
+        self.threshold = threshold;
+        self.next_collection = threshold.unwrap_or(0);
+    }
+
+    pub fn record_allocation(&mut self) {
+        self.allocations += 1;
+    }
+
+    pub fn should_collect(&self) -> bool {
+        self.threshold.is_some() && self.allocations >= self.next_collection
+    }
+
+    /// Marks every instance reachable from `scopes` and `values`, frees the
+    /// rest back into the instance manager and returns how many were freed.
+    pub fn collect<'a>(
+        &mut self,
+        instances: &mut InstanceManager,
+        scopes: impl IntoIterator<Item = RunScopeRef>,
+        values: impl IntoIterator<Item = &'a RuntimeValue>,
+        instance_roots: impl IntoIterator<Item = usize>,
+    ) -> usize {
+        let mut tracer = Tracer::default();
+        tracer.pending_scopes.extend(scopes);
+        for value in values {
+            tracer.trace_value(value);
+        }
+        tracer.pending_instances.extend(instance_roots);
+        tracer.trace(instances);
+
+        let freed = instances.free_unmarked(&tracer.marked_instances);
+        self.allocations = 0;
+        self.next_collection = self.threshold.unwrap_or(0).max(instances.live_instances());
+        freed
+    }
+}
+
+#[derive(Default)]
+struct Tracer {
+    marked_instances: HashSet<usize>,
+    visited_scopes: HashSet<*const RefCell<RunScopes>>,
+    visited_lists: HashSet<*const RefCell<Vec<RuntimeValue>>>,
+    visited_maps: HashSet<*const RefCell<Table<RuntimeValue>>>,
+    pending_instances: Vec<usize>,
+    pending_scopes: Vec<RunScopeRef>,
+    pending_lists: Vec<List>,
+    pending_maps: Vec<Map>,
+}
+
+impl Tracer {
+    fn trace_value(&mut self, value: &RuntimeValue) {
+        match value {
+            RuntimeValue::ClassInstance(pointer, _) => self.pending_instances.push(*pointer),
+            RuntimeValue::List(list) => self.pending_lists.push(list.clone()),
+            RuntimeValue::Map(map) => self.pending_maps.push(map.clone()),
+            RuntimeValue::Callable(callable) => {
+                if let Some(scope) = callable.get_scope() {
+                    self.pending_scopes.push(scope);
+                }
+                if let Some(this) = callable.get_this_pointer() {
+                    self.trace_this(&this);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn trace_this(&mut self, this: &ThisInstance) {
+        self.pending_instances.push(this.get_current());
+        self.pending_instances.push(this.get_super_class());
+    }
+
+    fn trace_scope(&mut self, scope: RunScopeRef) {
+        if !self.visited_scopes.insert(Rc::as_ptr(&scope)) {
+            return;
+        }
+        let scope = scope.borrow();
+        for value in scope.values() {
+            self.trace_value(value);
+        }
+        if let Some(this) = scope.own_this() {
+            self.trace_this(this);
+        }
+        if let Some(enclosing) = scope.get_enclosing() {
+            self.pending_scopes.push(enclosing);
+        }
+    }
+
+    fn trace_list(&mut self, list: List) {
+        if !self.visited_lists.insert(list.as_ptr()) {
+            return;
+        }
+        for value in list.elements().iter() {
+            self.trace_value(value);
+        }
+    }
+
+    /// Keys are never instances, so only values are traced.
+    fn trace_map(&mut self, map: Map) {
+        if !self.visited_maps.insert(map.as_ptr()) {
+            return;
+        }
+        for value in map.entries().values() {
+            self.trace_value(value);
+        }
+    }
+
+    fn trace_instance(&mut self, instances: &InstanceManager, index: usize) {
+        if !self.marked_instances.insert(index) {
+            return;
+        }
+        if let Some((values, super_class)) = instances.instance_references(index) {
+            for value in values {
+                self.trace_value(value);
+            }
+            if let Some(super_class) = super_class {
+                self.pending_instances.push(super_class);
+            }
+        }
+    }
+
+    fn trace(&mut self, instances: &InstanceManager) {
+        loop {
+            if let Some(scope) = self.pending_scopes.pop() {
+                self.trace_scope(scope);
+            } else if let Some(index) = self.pending_instances.pop() {
+                self.trace_instance(instances, index);
+            } else if let Some(list) = self.pending_lists.pop() {
+                self.trace_list(list);
+            } else if let Some(map) = self.pending_maps.pop() {
+                self.trace_map(map);
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::evaluation::{
+        functions_resolver::FunctionsResolver,
+        run::{RunScopes, RunState},
+        List, RuntimeValue,
+    };
+
+    #[test]
+    fn test_collects_only_unreachable_instances() {
+        let state = RunState::new(FunctionsResolver::default(), RunScopes::new(None));
+        let parent = state
+            .initialize_instance("Parent".to_string(), None)
+            .unwrap();
+        let child = state
+            .initialize_instance("Child".to_string(), Some(parent))
+            .unwrap();
+        let field = state
+            .initialize_instance("Field".to_string(), None)
+            .unwrap();
+        state
+            .set_instance_value(
+                child,
+                "field",
+                RuntimeValue::ClassInstance(field, "Field".to_string()),
+            )
+            .unwrap();
+        let temporary = state.initialize_instance("Temp".to_string(), None).unwrap();
+        state
+            .initialize_instance("Garbage".to_string(), None)
+            .unwrap();
+
+        state.declare_variable(
+            "child".to_string(),
+            Some(RuntimeValue::ClassInstance(child, "Child".to_string())),
+            None,
+        );
+        let temporary_value = RuntimeValue::ClassInstance(temporary, "Temp".to_string());
+        let root = state.root(&temporary_value);
+
+        assert_eq!(state.collect_garbage(None), 1);
+        assert_eq!(state.live_instances(), 4);
+
+        drop(root);
+        assert_eq!(state.collect_garbage(None), 1);
+        assert_eq!(state.live_instances(), 3);
+    }
+
+    #[test]
+    fn test_lists_keep_their_elements_alive() {
+        let state = RunState::new(FunctionsResolver::default(), RunScopes::new(None));
+        let element = state
+            .initialize_instance("Element".to_string(), None)
+            .unwrap();
+        let list = List::new(vec![RuntimeValue::ClassInstance(
+            element,
+            "Element".to_string(),
+        )]);
+        // A list holding itself is traced once.
+        list.elements_mut().push(RuntimeValue::List(list.clone()));
+        state.declare_variable(
+            "xs".to_string(),
+            Some(RuntimeValue::List(list.clone())),
+            None,
+        );
+
+        assert_eq!(state.collect_garbage(None), 0);
+
+        list.elements_mut().clear();
+        assert_eq!(state.collect_garbage(None), 1);
+    }
+}
@@ -0,0 +1,45 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+/// Where `readLine` reads from: one line per call, without its line break, or
+/// `None` once the input is exhausted.
+pub trait InputSource {
+    fn read_line(&mut self) -> io::Result<Option<String>>;
+}
+
+/// Reads the process's standard input.
+#[derive(Debug, Default)]
+pub struct StdinSource;
+
+impl InputSource for StdinSource {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let trimmed = line.trim_end_matches(['\n', '\r']).len();
+        line.truncate(trimmed);
+        Ok(Some(line))
+    }
+}
+
+/// Hands out the lines it was given, in order, then reports the input
+/// exhausted. Without any lines it is an empty input.
+#[derive(Debug, Default)]
+pub struct LinesSource {
+    lines: VecDeque<String>,
+}
+
+impl LinesSource {
+    pub fn new(lines: Vec<String>) -> Self {
+        Self {
+            lines: lines.into(),
+        }
+    }
+}
+
+impl InputSource for LinesSource {
+    fn read_line(&mut self) -> io::Result<Option<String>> {
+        Ok(self.lines.pop_front())
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, LinkedList};
+use std::collections::{HashMap, HashSet, LinkedList};
 
 use crate::evaluation::{RuntimeError, RuntimeValue};
 
@@ -122,6 +122,34 @@ impl InstanceManager {
         Ok(())
     }
 
+    /// The values stored in an instance and its super class pointer, for tracing.
+    pub fn instance_references(
+        &self,
+        index: usize,
+    ) -> Option<(impl Iterator<Item = &RuntimeValue>, SuperClassPointer)> {
+        self.instances
+            .get(index)
+            .and_then(|o| o.as_ref())
+            .map(|(_, instance, super_class)| (instance.values(), *super_class))
+    }
+
+    /// Frees every live slot not in `marked`, returning how many were freed.
+    pub fn free_unmarked(&mut self, marked: &HashSet<usize>) -> usize {
+        let mut freed = 0;
+        for (index, slot) in self.instances.iter_mut().enumerate() {
+            if slot.is_some() && !marked.contains(&index) {
+                *slot = None;
+                self.available.push_back(index);
+                freed += 1;
+            }
+        }
+        freed
+    }
+
+    pub fn live_instances(&self) -> usize {
+        self.instances.len() - self.available.len()
+    }
+
     pub fn get_class_name(&self, index: usize) -> Result<&str, RuntimeError> {
         self.instances
             .get(index)
@@ -1,7 +1,17 @@
+mod call_stack;
+mod garbage_collector;
+mod input;
 mod instance_manager;
+mod output;
 mod run_scopes;
 mod run_state;
+mod sandbox;
 
+pub use call_stack::{CallFrame, StackTrace};
+pub use garbage_collector::{GarbageCollector, DEFAULT_GC_THRESHOLD};
+pub use input::{InputSource, LinesSource, StdinSource};
 pub use instance_manager::InstanceManager;
+pub use output::{BufferSink, LineCallbackSink, OutputSink, StdoutSink};
 pub use run_scopes::{RunScopeRef, RunScopes};
 pub use run_state::RunState;
+pub use sandbox::SandboxPolicy;
@@ -0,0 +1,61 @@
+use std::{cell::RefCell, rc::Rc};
+
+/// Where `print` writes: one call per printed line, without its line break.
+pub trait OutputSink {
+    fn write_line(&mut self, line: &str);
+}
+
+/// Writes to the process's standard output.
+#[derive(Debug, Default)]
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn write_line(&mut self, line: &str) {
+        println!("{}", line);
+    }
+}
+
+/// Collects every line in memory. Clones share the same buffer, so a clone
+/// kept by the host sees what the program printed.
+#[derive(Debug, Default, Clone)]
+pub struct BufferSink {
+    buffer: Rc<RefCell<String>>,
+}
+
+impl BufferSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything printed so far, each line followed by a line break.
+    pub fn contents(&self) -> String {
+        self.buffer.borrow().clone()
+    }
+}
+
+impl OutputSink for BufferSink {
+    fn write_line(&mut self, line: &str) {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.push_str(line);
+        buffer.push('\n');
+    }
+}
+
+/// Hands each printed line to a callback.
+pub struct LineCallbackSink {
+    callback: Box<dyn FnMut(&str)>,
+}
+
+impl LineCallbackSink {
+    pub fn new(callback: impl FnMut(&str) + 'static) -> Self {
+        Self {
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl OutputSink for LineCallbackSink {
+    fn write_line(&mut self, line: &str) {
+        (self.callback)(line);
+    }
+}
@@ -42,8 +42,13 @@ impl RunScopes {
     }
 
     #[inline]
-    pub fn set_variable(&mut self, identifier: String, value: RuntimeValue, depth: Option<usize>) {
-        let depth = depth.unwrap_or(0);
+    pub fn set_variable(
+        &mut self,
+        identifier: &EvaluableIdentifier,
+        value: RuntimeValue,
+        overwrite_depth: Option<usize>,
+    ) -> Result<(), RuntimeError> {
+        let depth = overwrite_depth.unwrap_or(identifier.depth().unwrap_or(0));
         if depth > 0 {
             return self.enclosing.as_ref().unwrap().borrow_mut().set_variable(
                 identifier,
@@ -51,14 +56,16 @@ impl RunScopes {
                 Some(depth - 1),
             );
         }
-        if self.values.contains_key(&identifier) {
-            self.values.insert(identifier, value);
-        } else {
-            self.enclosing
-                .as_ref()
-                .unwrap()
-                .borrow_mut()
-                .set_variable(identifier, value, None);
+        if let Some(variable) = self.values.get_mut(identifier.identifier()) {
+            *variable = value;
+            return Ok(());
+        }
+        match &self.enclosing {
+            Some(scope) => scope.borrow_mut().set_variable(identifier, value, Some(0)),
+            None => Err(RuntimeError::UndefinedVariable(
+                identifier.identifier().to_string(),
+                identifier.span().clone(),
+            )),
         }
     }
 
@@ -90,10 +97,12 @@ impl RunScopes {
             .as_ref()
             .map(|scope| scope.borrow().evaluate_variable(identifier, Some(0)))
             .transpose()?
-            .ok_or(RuntimeError::UndefinedVariable(
-                identifier.identifier().to_string(),
-                identifier.line(),
-            ))
+            .ok_or_else(|| {
+                RuntimeError::UndefinedVariable(
+                    identifier.identifier().to_string(),
+                    identifier.span().clone(),
+                )
+            })
     }
 
     fn print_with_depth(&self, depth: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -124,6 +133,20 @@ impl RunScopes {
         this
     }
 
+    /// The `this` bound directly on this scope, ignoring enclosing scopes.
+    pub fn own_this(&self) -> Option<&ThisInstance> {
+        self.this.as_ref()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &RuntimeValue> {
+        self.values.values()
+    }
+
+    /// The variables declared directly in this scope, by name.
+    pub fn variables(&self) -> impl Iterator<Item = (&String, &RuntimeValue)> {
+        self.values.iter()
+    }
+
     pub fn unset_this(&mut self) {
         self.this = None;
     }
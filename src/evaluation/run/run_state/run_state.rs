@@ -1,34 +1,112 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 use crate::evaluation::{
     evaluator::EvaluableIdentifier,
     functions_resolver::FunctionsResolver,
-    run::{run_state::InstanceManager, RunScopeRef, RunScopes},
+    run::{
+        run_state::{
+            CallFrame, GarbageCollector, InputSource, InstanceManager, OutputSink, SandboxPolicy,
+            StackTrace, StdinSource, StdoutSink, DEFAULT_GC_THRESHOLD,
+        },
+        Frame, RunHook, RunScopeRef, RunScopes, TracePoint,
+    },
     runtime_value::ThisInstance,
-    RuntimeError, RuntimeValue,
+    BuilderContext, RuntimeError, RuntimeValue,
 };
+use crate::tokenizer::Span;
 
 pub struct RunState {
     scopes: RefCell<RunScopeRef>,
+    /// Scopes left for the ones entered by `enter_scope` and `replace_scopes`,
+    /// innermost last.
+    saved_scopes: RefCell<Vec<RunScopeRef>>,
+    /// Values only held by Rust locals mid-evaluation, kept alive across collections.
+    temporary_roots: RefCell<Vec<RuntimeValue>>,
+    /// Calls in progress, innermost last. A call that fails leaves its frame
+    /// behind so the trace can be taken once the error reaches the top.
+    call_stack: RefCell<Vec<CallFrame>>,
     functions_resolver: RefCell<FunctionsResolver>,
     instance_manager: RefCell<InstanceManager>,
+    garbage_collector: RefCell<GarbageCollector>,
+    /// What the file natives may access.
+    sandbox: RefCell<SandboxPolicy>,
+    /// What the `args` native returns.
+    script_arguments: RefCell<Vec<String>>,
+    output: RefCell<Box<dyn OutputSink>>,
+    /// What the `readLine` native reads.
+    input: RefCell<Box<dyn InputSource>>,
+    hook: RefCell<Option<InstalledHook>>,
+    /// The top-level scope of each module run, by canonical path.
+    modules: RefCell<HashMap<PathBuf, RunScopeRef>>,
+}
+
+/// A `RunHook` and the context the expressions it evaluates are built in.
+struct InstalledHook {
+    hook: Box<dyn RunHook>,
+    context: Rc<BuilderContext>,
+}
+
+/// Puts back the scope that was current before `RunState::enter_scope` or
+/// `RunState::replace_scopes` when dropped, however the code holding it is
+/// left: at its end, on a `return`, `break` or `continue`, or with an error.
+#[must_use]
+pub struct ScopeGuard<'a> {
+    state: &'a RunState,
+    restore_len: usize,
+}
+
+impl Drop for ScopeGuard<'_> {
+    fn drop(&mut self) {
+        let mut saved_scopes = self.state.saved_scopes.borrow_mut();
+        saved_scopes.truncate(self.restore_len + 1);
+        if let Some(scope) = saved_scopes.pop() {
+            self.state.scopes.replace(scope);
+        }
+    }
+}
+
+/// Keeps the values pushed by `RunState::root` alive until dropped.
+#[must_use]
+pub struct RootGuard<'a> {
+    state: &'a RunState,
+    restore_len: usize,
+}
+
+impl Drop for RootGuard<'_> {
+    fn drop(&mut self) {
+        self.state
+            .temporary_roots
+            .borrow_mut()
+            .truncate(self.restore_len);
+    }
 }
 
 impl RunState {
     pub fn new(functions_resolver: FunctionsResolver, scopes: RunScopes) -> Self {
         Self {
             scopes: RefCell::new(Rc::new(RefCell::new(scopes))),
+            saved_scopes: RefCell::new(Vec::new()),
+            temporary_roots: RefCell::new(Vec::new()),
+            call_stack: RefCell::new(Vec::new()),
             functions_resolver: RefCell::new(functions_resolver),
             instance_manager: RefCell::new(InstanceManager::new().unwrap()),
+            garbage_collector: RefCell::new(GarbageCollector::new(Some(DEFAULT_GC_THRESHOLD))),
+            sandbox: RefCell::new(SandboxPolicy::default()),
+            script_arguments: RefCell::new(Vec::new()),
+            output: RefCell::new(Box::new(StdoutSink)),
+            input: RefCell::new(Box::new(StdinSource)),
+            hook: RefCell::new(None),
+            modules: RefCell::new(HashMap::new()),
         }
     }
 
     pub fn void() -> Self {
-        Self {
-            scopes: RefCell::new(Rc::new(RefCell::new(RunScopes::new(None)))),
-            functions_resolver: RefCell::new(FunctionsResolver::new().unwrap()),
-            instance_manager: RefCell::new(InstanceManager::new().unwrap()),
-        }
+        Self::new(FunctionsResolver::new().unwrap(), RunScopes::new(None))
     }
 
     #[inline]
@@ -45,31 +123,24 @@ impl RunState {
     }
 
     #[inline]
-    pub fn set_variable(&self, identifier: String, value: RuntimeValue, depth: Option<usize>) {
+    pub fn set_variable(
+        &self,
+        identifier: &EvaluableIdentifier,
+        value: RuntimeValue,
+    ) -> Result<(), RuntimeError> {
         self.scopes
             .borrow()
             .borrow_mut()
-            .set_variable(identifier, value, depth);
+            .set_variable(identifier, value, None)
     }
 
+    /// Runs in a new scope enclosed by the current one until the guard returned
+    /// is dropped.
     #[inline]
-    pub fn enter_scope(&self) -> Result<(), RuntimeError> {
+    pub fn enter_scope(&self) -> ScopeGuard<'_> {
         let enclosing = self.scopes.borrow().clone();
         let new_scope = RunScopes::new(Some(enclosing));
-        self.scopes.replace(Rc::new(RefCell::new(new_scope)));
-        Ok(())
-    }
-
-    #[inline]
-    pub fn exit_scope(&self) -> Result<(), RuntimeError> {
-        let enclosing = self
-            .scopes
-            .borrow()
-            .borrow()
-            .get_enclosing()
-            .ok_or(RuntimeError::OutOfScope)?;
-        self.scopes.replace(enclosing);
-        Ok(())
+        self.replace_scopes(Rc::new(RefCell::new(new_scope)))
     }
 
     pub fn evaluate_variable(
@@ -94,11 +165,48 @@ impl RunState {
             .resolve(index)
             .ok_or(RuntimeError::FunctionNotFound)?;
 
-        let restore = self.replace_scopes(function_scope.unwrap_or(self.get_current_scope()));
-        let result: Result<RuntimeValue, RuntimeError> =
-            pointer.call(arguments, this_pointer, self);
-        restore();
-        result
+        let _scopes = self.replace_scopes(function_scope.unwrap_or(self.get_current_scope()));
+        pointer.call(arguments, this_pointer, self)
+    }
+
+    pub fn push_frame(&self, frame: CallFrame) {
+        self.call_stack.borrow_mut().push(frame);
+    }
+
+    pub fn pop_frame(&self) -> Option<CallFrame> {
+        self.call_stack.borrow_mut().pop()
+    }
+
+    /// A class call runs in one frame, which runs the `init` of each class
+    /// constructed along the superclass chain in turn.
+    pub fn set_frame_class(&self, class: String) {
+        if let Some(frame) = self.call_stack.borrow_mut().last_mut() {
+            frame.class = Some(class);
+        }
+    }
+
+    /// The calls in progress, outermost first.
+    pub fn call_stack(&self) -> Vec<CallFrame> {
+        self.call_stack.borrow().clone()
+    }
+
+    /// Drops the frames failed calls left above the first `depth` ones.
+    pub fn unwind_call_stack(&self, depth: usize) {
+        self.call_stack.borrow_mut().truncate(depth);
+    }
+
+    pub fn call_depth(&self) -> usize {
+        self.call_stack.borrow().len()
+    }
+
+    /// Empties the call stack, returning the calls an error escaped from, if
+    /// it was raised inside any.
+    pub fn take_stack_trace(&self, error: &RuntimeError) -> Option<StackTrace> {
+        let frames = self.call_stack.take();
+        if frames.is_empty() {
+            return None;
+        }
+        Some(StackTrace::new(frames, error.span().map(|span| span.line)))
     }
 
     pub fn function_arity(&self, index: usize) -> Result<usize, RuntimeError> {
@@ -110,28 +218,187 @@ impl RunState {
         pointer.arity(self)
     }
 
-    pub fn replace_scopes(&self, scopes: RunScopeRef) -> impl FnOnce() + use<'_> {
+    /// Runs in `scopes` until the guard returned is dropped.
+    pub fn replace_scopes(&self, scopes: RunScopeRef) -> ScopeGuard<'_> {
         let current = self.scopes.replace(scopes);
-        let restore = || {
-            self.scopes.replace(current);
-        };
-        return restore;
+        let mut saved_scopes = self.saved_scopes.borrow_mut();
+        let restore_len = saved_scopes.len();
+        saved_scopes.push(current);
+        ScopeGuard {
+            state: self,
+            restore_len,
+        }
     }
 
     pub fn get_current_scope(&self) -> RunScopeRef {
         self.scopes.borrow().clone()
     }
 
+    /// The outermost scope, where the natives are declared.
+    pub fn get_global_scope(&self) -> RunScopeRef {
+        let mut scope = self.get_current_scope();
+        loop {
+            let enclosing = scope.borrow().get_enclosing();
+            match enclosing {
+                Some(enclosing) => scope = enclosing,
+                None => return scope,
+            }
+        }
+    }
+
+    /// The top-level scope of the module at `path`, if it has been run.
+    pub fn get_module_scope(&self, path: &Path) -> Option<RunScopeRef> {
+        self.modules.borrow().get(path).cloned()
+    }
+
+    pub fn set_module_scope(&self, path: PathBuf, scope: RunScopeRef) {
+        self.modules.borrow_mut().insert(path, scope);
+    }
+
+    pub fn take_functions_resolver(&self) -> FunctionsResolver {
+        self.functions_resolver.take()
+    }
+
+    pub fn replace_functions_resolver(&self, functions_resolver: FunctionsResolver) {
+        self.functions_resolver.replace(functions_resolver);
+    }
+
     pub fn initialize_instance(
         &self,
         class_name: String,
         super_class: Option<usize>,
     ) -> Result<usize, RuntimeError> {
+        if self.garbage_collector.borrow().should_collect() {
+            self.collect_garbage(super_class);
+        }
+        self.garbage_collector.borrow_mut().record_allocation();
         self.instance_manager
             .borrow_mut()
             .initialize_instance(class_name, super_class)
     }
 
+    /// Keeps `value` reachable for the collector while the guard is alive. Use it
+    /// for values held in Rust locals while evaluating code that may allocate.
+    pub fn root(&self, value: &RuntimeValue) -> RootGuard<'_> {
+        let mut temporary_roots = self.temporary_roots.borrow_mut();
+        let restore_len = temporary_roots.len();
+        if matches!(
+            value,
+            RuntimeValue::ClassInstance(..)
+                | RuntimeValue::Callable(_)
+                | RuntimeValue::List(_)
+                | RuntimeValue::Map(_)
+        ) {
+            temporary_roots.push(value.clone());
+        }
+        RootGuard {
+            state: self,
+            restore_len,
+        }
+    }
+
+    /// Frees every class instance unreachable from the current scopes, the scopes
+    /// of calls in progress and of modules, and the rooted temporaries. Returns
+    /// how many were freed.
+    pub fn collect_garbage(&self, extra_root: Option<usize>) -> usize {
+        let scopes = std::iter::once(self.get_current_scope())
+            .chain(self.saved_scopes.borrow().iter().cloned())
+            .chain(self.modules.borrow().values().cloned())
+            .collect::<Vec<_>>();
+        self.garbage_collector.borrow_mut().collect(
+            &mut self.instance_manager.borrow_mut(),
+            scopes,
+            self.temporary_roots.borrow().iter(),
+            extra_root,
+        )
+    }
+
+    pub fn set_gc_threshold(&self, threshold: Option<usize>) {
+        self.garbage_collector.borrow_mut().set_threshold(threshold);
+    }
+
+    pub fn set_sandbox(&self, sandbox: SandboxPolicy) {
+        self.sandbox.replace(sandbox);
+    }
+
+    pub fn sandbox(&self) -> SandboxPolicy {
+        self.sandbox.borrow().clone()
+    }
+
+    pub fn set_script_arguments(&self, arguments: Vec<String>) {
+        self.script_arguments.replace(arguments);
+    }
+
+    pub fn script_arguments(&self) -> Vec<String> {
+        self.script_arguments.borrow().clone()
+    }
+
+    pub fn set_output(&self, output: Box<dyn OutputSink>) {
+        self.output.replace(output);
+    }
+
+    pub fn set_input(&self, input: Box<dyn InputSource>) {
+        self.input.replace(input);
+    }
+
+    pub fn set_hook(&self, hook: Box<dyn RunHook>, context: Rc<BuilderContext>) {
+        self.hook.replace(Some(InstalledHook { hook, context }));
+    }
+
+    /// Shows the statement at `span` to the installed hook before it runs.
+    pub fn before_statement(&self, span: &Span) -> Result<(), RuntimeError> {
+        // Taken out while it runs, so the code it evaluates isn't shown to it
+        let Some(mut installed) = self.hook.take() else {
+            return Ok(());
+        };
+        let frame = Frame::new(self, &installed.context, span);
+        let result = installed.hook.before_statement(&frame);
+        self.hook.replace(Some(installed));
+        result
+    }
+
+    /// Tells the installed hook what a build traced.
+    pub fn traced(&self, points: &[TracePoint]) {
+        if let Some(installed) = self.hook.borrow_mut().as_mut() {
+            installed.hook.traced(points);
+        }
+    }
+
+    pub fn branch(&self, span: &Span, taken: bool) {
+        if let Some(installed) = self.hook.borrow_mut().as_mut() {
+            installed.hook.branch(span, taken);
+        }
+    }
+
+    /// Tells the installed hook a call to the function at `index` starts.
+    /// `name` is only worked out if the hook asks for it.
+    pub fn enter_call(&self, index: usize, name: impl Fn() -> String) {
+        if let Some(installed) = self.hook.borrow_mut().as_mut() {
+            installed.hook.enter_call(index, &name);
+        }
+    }
+
+    pub fn exit_call(&self) {
+        if let Some(installed) = self.hook.borrow_mut().as_mut() {
+            installed.hook.exit_call();
+        }
+    }
+
+    /// Reads a line of program input, as `readLine` does.
+    pub fn read_line(&self) -> std::io::Result<Option<String>> {
+        self.input.borrow_mut().read_line()
+    }
+
+    /// Writes a line of program output, as `print` does.
+    pub fn write_line(&self, line: &str) {
+        self.output.borrow_mut().write_line(line);
+    }
+
+    #[cfg(test)]
+    pub fn live_instances(&self) -> usize {
+        self.instance_manager.borrow().live_instances()
+    }
+
     pub fn get_instance_value(
         &self,
         index: usize,
@@ -0,0 +1,109 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::evaluation::run::NativeFunctionError;
+
+/// The directories natives may read files from and write files to. Nothing
+/// is allowed by default.
+#[derive(Debug, Clone, Default)]
+pub struct SandboxPolicy {
+    read: Vec<PathBuf>,
+    write: Vec<PathBuf>,
+}
+
+impl SandboxPolicy {
+    /// Allows reading anything under `directory`, which must exist.
+    pub fn allow_read(&mut self, directory: impl AsRef<Path>) -> io::Result<()> {
+        self.read.push(directory.as_ref().canonicalize()?);
+        Ok(())
+    }
+
+    /// Allows creating and writing files under `directory`, which must exist.
+    pub fn allow_write(&mut self, directory: impl AsRef<Path>) -> io::Result<()> {
+        self.write.push(directory.as_ref().canonicalize()?);
+        Ok(())
+    }
+
+    /// `path`, if it may be read.
+    pub fn check_read(&self, path: &str) -> Result<PathBuf, NativeFunctionError> {
+        Self::check(&self.read, path)
+            .ok_or_else(|| NativeFunctionError::PermissionDenied("read", path.to_string()))
+    }
+
+    /// `path`, if it may be written.
+    pub fn check_write(&self, path: &str) -> Result<PathBuf, NativeFunctionError> {
+        Self::check(&self.write, path)
+            .ok_or_else(|| NativeFunctionError::PermissionDenied("write", path.to_string()))
+    }
+
+    fn check(allowed: &[PathBuf], path: &str) -> Option<PathBuf> {
+        let resolved = Self::resolve(Path::new(path))?;
+        allowed
+            .iter()
+            .any(|directory| resolved.starts_with(directory))
+            .then_some(resolved)
+    }
+
+    /// The absolute path `path` refers to, with links and `..` resolved. A
+    /// file that doesn't exist yet is resolved through its directory; a link
+    /// that points nowhere is refused, as writing through it would create its
+    /// target wherever that is.
+    fn resolve(path: &Path) -> Option<PathBuf> {
+        if let Ok(resolved) = path.canonicalize() {
+            return Some(resolved);
+        }
+        if path.symlink_metadata().is_ok() {
+            return None;
+        }
+        let name = path.file_name()?;
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        Some(parent.canonicalize().ok()?.join(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sandbox_denies_paths_outside_allowed_directories() {
+        let allowed = std::env::temp_dir().join("lox-sandbox-test");
+        std::fs::create_dir_all(&allowed).unwrap();
+        let allowed_path = allowed.to_str().unwrap();
+
+        let mut sandbox = SandboxPolicy::default();
+        assert!(sandbox.check_read(allowed_path).is_err());
+
+        sandbox.allow_read(&allowed).unwrap();
+        assert!(sandbox
+            .check_read(&format!("{}/new.txt", allowed_path))
+            .is_ok());
+        assert!(sandbox
+            .check_read(&format!("{}/../x", allowed_path))
+            .is_err());
+        assert!(sandbox
+            .check_write(&format!("{}/new.txt", allowed_path))
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_sandbox_denies_dangling_links_out_of_allowed_directories() {
+        let allowed = std::env::temp_dir().join("lox-sandbox-link-test");
+        std::fs::create_dir_all(&allowed).unwrap();
+        let outside = std::env::temp_dir().join("lox-sandbox-link-target.txt");
+        let _ = std::fs::remove_file(&outside);
+        let link = allowed.join("link.txt");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&outside, &link).unwrap();
+
+        let mut sandbox = SandboxPolicy::default();
+        sandbox.allow_write(&allowed).unwrap();
+        assert!(sandbox.check_write(link.to_str().unwrap()).is_err());
+    }
+}
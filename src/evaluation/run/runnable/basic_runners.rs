@@ -2,8 +2,25 @@ use super::super::run_state::RunState;
 use crate::evaluation::evaluator::{Evaluable, PrimaryEvaluator};
 use crate::evaluation::runtime_value::{CallableType, Result as RuntimeResult};
 use crate::evaluation::{RuntimeError, RuntimeValue};
+use crate::tokenizer::Span;
 
-pub type RunResult = RuntimeResult<Option<RuntimeValue>>;
+/// How a statement finished. Anything but `Normal` unwinds the enclosing
+/// statements until the function or loop it belongs to handles it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlFlow {
+    Normal,
+    Return(RuntimeValue),
+    Break,
+    Continue,
+}
+
+impl ControlFlow {
+    pub fn is_normal(&self) -> bool {
+        matches!(self, ControlFlow::Normal)
+    }
+}
+
+pub type RunResult = RuntimeResult<ControlFlow>;
 
 pub trait Runnable: std::fmt::Debug {
     fn run(&self, state: &RunState) -> RunResult;
@@ -22,8 +39,9 @@ impl PrintRunnable {
 
 impl Runnable for PrintRunnable {
     fn run(&self, run_state: &RunState) -> RunResult {
-        println!("{}", self.value.eval(run_state)?);
-        Ok(None)
+        let value = self.value.eval(run_state)?;
+        run_state.write_line(&value.to_string());
+        Ok(ControlFlow::Normal)
     }
 }
 
@@ -41,7 +59,7 @@ impl ExpressionRunnable {
 impl Runnable for ExpressionRunnable {
     fn run(&self, run_state: &RunState) -> RunResult {
         self.value.eval(run_state)?;
-        Ok(None)
+        Ok(ControlFlow::Normal)
     }
 }
 
@@ -59,12 +77,12 @@ impl ProgramRunnable {
 impl Runnable for ProgramRunnable {
     fn run(&self, state: &RunState) -> RunResult {
         for statement in &self.statements {
-            let ret = statement.run(state)?;
-            if ret.is_some() {
-                return Ok(ret);
+            let flow = statement.run(state)?;
+            if !flow.is_normal() {
+                return Ok(flow);
             }
         }
-        Ok(None)
+        Ok(ControlFlow::Normal)
     }
 }
 
@@ -96,7 +114,7 @@ impl Runnable for FunctionDeclarationRunnable {
             )),
             Some(0),
         );
-        Ok(None)
+        Ok(ControlFlow::Normal)
     }
 }
 
@@ -114,19 +132,38 @@ impl ReturnRunnable {
 impl Runnable for ReturnRunnable {
     fn run(&self, state: &RunState) -> RunResult {
         let ret = self.expr.as_ref().map(|e| e.eval(state)).transpose()?;
-        return Ok(Some(ret.unwrap_or(RuntimeValue::Nil)));
+        return Ok(ControlFlow::Return(ret.unwrap_or(RuntimeValue::Nil)));
+    }
+}
+
+#[derive(Debug)]
+pub struct ThrowRunnable {
+    expr: Box<dyn Evaluable>,
+    span: Span,
+}
+
+impl ThrowRunnable {
+    pub fn new(expr: Box<dyn Evaluable>, span: Span) -> Self {
+        Self { expr, span }
+    }
+}
+
+impl Runnable for ThrowRunnable {
+    fn run(&self, state: &RunState) -> RunResult {
+        let value = self.expr.eval(state)?;
+        Err(RuntimeError::Thrown(value, self.span.clone()))
     }
 }
 
 #[derive(Debug)]
 pub struct IfNoClassErrorRunnable {
     evaluatable: PrimaryEvaluator,
-    line: usize,
+    span: Span,
 }
 
 impl IfNoClassErrorRunnable {
-    pub fn new(evaluatable: PrimaryEvaluator, line: usize) -> Self {
-        Self { evaluatable, line }
+    pub fn new(evaluatable: PrimaryEvaluator, span: Span) -> Self {
+        Self { evaluatable, span }
     }
 }
 
@@ -134,8 +171,46 @@ impl Runnable for IfNoClassErrorRunnable {
     fn run(&self, state: &RunState) -> RunResult {
         let ret = self.evaluatable.eval(state)?;
         if let RuntimeValue::ClassInstance(_, _) = ret {
-            return Ok(None);
+            return Ok(ControlFlow::Normal);
         }
-        return Err(RuntimeError::SuperClassMustBeAClass(self.line));
+        return Err(RuntimeError::SuperClassMustBeAClass(self.span.clone()));
+    }
+}
+
+/// A statement built to be shown to a `RunHook` before it runs.
+#[derive(Debug)]
+pub struct TracedRunnable {
+    span: Span,
+    runnable: Box<dyn Runnable>,
+}
+
+impl TracedRunnable {
+    pub fn new(span: Span, runnable: Box<dyn Runnable>) -> Self {
+        Self { span, runnable }
+    }
+}
+
+impl Runnable for TracedRunnable {
+    fn run(&self, state: &RunState) -> RunResult {
+        state.before_statement(&self.span)?;
+        self.runnable.run(state)
+    }
+}
+
+#[derive(Debug)]
+pub struct BreakRunnable;
+
+impl Runnable for BreakRunnable {
+    fn run(&self, _state: &RunState) -> RunResult {
+        Ok(ControlFlow::Break)
+    }
+}
+
+#[derive(Debug)]
+pub struct ContinueRunnable;
+
+impl Runnable for ContinueRunnable {
+    fn run(&self, _state: &RunState) -> RunResult {
+        Ok(ControlFlow::Continue)
     }
 }
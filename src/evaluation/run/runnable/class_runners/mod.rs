@@ -1,8 +1,11 @@
-use crate::evaluation::{
-    evaluator::{Evaluable, PrimaryEvaluator, INIT_FUNCTION_NAME},
-    run::{Callable, Runnable},
-    runtime_value::{CallableType, ThisInstance},
-    RunState, RuntimeError, RuntimeValue,
+use crate::{
+    evaluation::{
+        evaluator::{Evaluable, PrimaryEvaluator, INIT_FUNCTION_NAME},
+        run::{Callable, ControlFlow, RunResult, Runnable},
+        runtime_value::{CallableType, ThisInstance},
+        RunState, RuntimeError, RuntimeValue,
+    },
+    tokenizer::Span,
 };
 
 #[derive(Debug)]
@@ -33,13 +36,18 @@ impl Evaluable for ClassInitializationCallable {
 }
 
 impl Callable for ClassInitializationCallable {
+    /// The arity of the `init` that runs: the class's own, or else the one it
+    /// inherits.
     fn arity(&self, state: &RunState) -> Result<usize, RuntimeError> {
-        self.methods
+        let init = self
+            .methods
             .iter()
             .find(|(_, method_name)| method_name == INIT_FUNCTION_NAME)
-            .map(|(init_pointer, _)| state.function_arity(*init_pointer))
-            .transpose()
-            .map(|a| a.unwrap_or(0))
+            .map(|(init_pointer, _)| *init_pointer);
+        match init.or(self.super_class) {
+            Some(pointer) => state.function_arity(pointer),
+            None => Ok(0),
+        }
     }
 
     fn call(
@@ -48,13 +56,33 @@ impl Callable for ClassInitializationCallable {
         _: Option<ThisInstance>,
         state: &RunState,
     ) -> Result<RuntimeValue, RuntimeError> {
+        let arity = self.arity(state)?;
+        if arity != arguments.len() {
+            // The caller is at fault, so the error is reported from its frame.
+            let call_site = state.pop_frame().map(|frame| frame.span);
+            return Err(RuntimeError::ArityMismatch(
+                arity,
+                arguments.len(),
+                call_site.unwrap_or_default(),
+            ));
+        }
+        let _argument_roots = arguments
+            .iter()
+            .map(|argument| state.root(argument))
+            .collect::<Vec<_>>();
         let super_class = self
             .super_class
             .as_ref()
             .map(|super_class| {
                 let arity = state.function_arity(*super_class)?;
                 if arity > arguments.len() {
-                    return Err(RuntimeError::ArityMismatch);
+                    // The caller is at fault, so the error is reported from its frame.
+                    let call_site = state.pop_frame().map(|frame| frame.span);
+                    return Err(RuntimeError::ArityMismatch(
+                        arity,
+                        arguments.len(),
+                        call_site.unwrap_or_default(),
+                    ));
                 }
                 let super_class_arguments = arguments.iter().take(arity).cloned().collect();
                 state.call_function(*super_class, super_class_arguments, None, None)
@@ -89,6 +117,9 @@ impl Callable for ClassInitializationCallable {
                     state.get_instance_value(this_pointer, INIT_FUNCTION_NAME, None, None)?;
                 match init_callable {
                     Some(RuntimeValue::Callable(callable)) => {
+                        if let Some(this) = callable.get_this_pointer() {
+                            state.set_frame_class(state.get_class_name(this.get_super_class())?);
+                        }
                         state.call_function(
                             callable.get_pointer(),
                             arguments,
@@ -110,14 +141,14 @@ impl Callable for ClassInitializationCallable {
 pub struct ClassDeclarationRunnable {
     class_constructor_pointer: usize,
     identifier: String,
-    super_class_evaluable: Option<(PrimaryEvaluator, usize)>,
+    super_class_evaluable: Option<(PrimaryEvaluator, Span)>,
 }
 
 impl ClassDeclarationRunnable {
     pub fn new(
         class_constructor_pointer: usize,
         identifier: String,
-        super_class_evaluable: Option<(PrimaryEvaluator, usize)>,
+        super_class_evaluable: Option<(PrimaryEvaluator, Span)>,
     ) -> Self {
         Self {
             class_constructor_pointer,
@@ -128,16 +159,16 @@ impl ClassDeclarationRunnable {
 }
 
 impl Runnable for ClassDeclarationRunnable {
-    fn run(&self, state: &RunState) -> Result<Option<RuntimeValue>, RuntimeError> {
-        if let Some((evaluator, line)) = &self.super_class_evaluable {
+    fn run(&self, state: &RunState) -> RunResult {
+        if let Some((evaluator, span)) = &self.super_class_evaluable {
             let super_class = evaluator.eval(state)?;
             match super_class {
                 RuntimeValue::Callable(callable) => {
                     if !callable.is_class_constructor() {
-                        return Err(RuntimeError::SuperClassMustBeAClass(*line));
+                        return Err(RuntimeError::SuperClassMustBeAClass(span.clone()));
                     }
                 }
-                _ => return Err(RuntimeError::SuperClassMustBeAClass(*line)),
+                _ => return Err(RuntimeError::SuperClassMustBeAClass(span.clone())),
             }
         }
 
@@ -152,6 +183,6 @@ impl Runnable for ClassDeclarationRunnable {
             Some(0),
         );
 
-        Ok(None)
+        Ok(ControlFlow::Normal)
     }
 }
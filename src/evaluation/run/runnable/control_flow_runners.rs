@@ -1,8 +1,12 @@
 use super::super::run_state::RunState;
 use super::basic_runners::Runnable;
 use crate::evaluation::evaluator::Evaluable;
-use crate::evaluation::run::runnable::basic_runners::RunResult;
+use crate::evaluation::run::runnable::basic_runners::{ControlFlow, RunResult};
 use crate::evaluation::runtime_value::Result as RuntimeResult;
+use crate::evaluation::{RuntimeError, RuntimeValue};
+
+/// The class name of the values runtime errors are caught as.
+const ERROR_CLASS_NAME: &str = "Error";
 
 #[derive(Debug)]
 pub struct IsStatementRunnable {
@@ -27,7 +31,7 @@ impl IsStatementRunnable {
 
 impl Runnable for IsStatementRunnable {
     fn run(&self, state: &RunState) -> RunResult {
-        let mut ret = None;
+        let mut ret = ControlFlow::Normal;
         let is_true = self.if_expr.eval(state)?.to_bool()?;
         if is_true {
             ret = self.true_block.run(state)?;
@@ -56,12 +60,13 @@ impl WhileStatementRunnable {
 impl Runnable for WhileStatementRunnable {
     fn run(&self, state: &RunState) -> RunResult {
         while self.eval_expr.eval(state)?.to_bool()? {
-            let ret = self.statement.run(state)?;
-            if ret.is_some() {
-                return Ok(ret);
+            match self.statement.run(state)? {
+                ControlFlow::Break => break,
+                ControlFlow::Normal | ControlFlow::Continue => {}
+                flow @ ControlFlow::Return(_) => return Ok(flow),
             }
         }
-        Ok(None)
+        Ok(ControlFlow::Normal)
     }
 }
 
@@ -102,20 +107,101 @@ impl ForStatementRunnable {
 
 impl Runnable for ForStatementRunnable {
     fn run(&self, state: &RunState) -> RunResult {
-        state.enter_scope()?;
+        let _scope = state.enter_scope();
         if let Some(var_declaration) = &self.var_declaration {
             var_declaration.run(state)?;
         }
         while self.eval_condition(state)? {
-            let ret = self.statement.run(state)?;
-            if ret.is_some() {
-                return Ok(ret);
+            match self.statement.run(state)? {
+                ControlFlow::Break => break,
+                ControlFlow::Normal | ControlFlow::Continue => {}
+                flow @ ControlFlow::Return(_) => return Ok(flow),
             }
             if let Some(increment) = &self.increment {
                 increment.eval(state)?;
             }
         }
-        state.exit_scope()?;
-        Ok(None)
+        Ok(ControlFlow::Normal)
+    }
+}
+
+#[derive(Debug)]
+pub struct TryStatementRunnable {
+    body: Box<dyn Runnable>,
+    catch: Option<(String, Box<dyn Runnable>)>,
+    finally: Option<Box<dyn Runnable>>,
+}
+
+impl TryStatementRunnable {
+    pub fn new(
+        body: Box<dyn Runnable>,
+        catch: Option<(String, Box<dyn Runnable>)>,
+        finally: Option<Box<dyn Runnable>>,
+    ) -> Self {
+        Self {
+            body,
+            catch,
+            finally,
+        }
+    }
+
+    /// What the catch block sees `error` as: the value thrown, or an `Error`
+    /// instance with the `message` and `line` of an error raised by the
+    /// interpreter.
+    fn caught_value(error: RuntimeError, state: &RunState) -> RuntimeResult<RuntimeValue> {
+        if let RuntimeError::Thrown(value, _) = error {
+            return Ok(value);
+        }
+        let line = error.span().map_or(0, |span| span.line);
+        let message = error.to_string();
+        let message = message.lines().next().unwrap_or_default().to_string();
+
+        let instance = state.initialize_instance(ERROR_CLASS_NAME.to_string(), None)?;
+        state.set_instance_value(instance, "message", RuntimeValue::String(message))?;
+        state.set_instance_value(instance, "line", RuntimeValue::Number(line as f64))?;
+        Ok(RuntimeValue::ClassInstance(
+            instance,
+            ERROR_CLASS_NAME.to_string(),
+        ))
+    }
+
+    fn catch(
+        error: RuntimeError,
+        identifier: &str,
+        handler: &dyn Runnable,
+        state: &RunState,
+    ) -> RunResult {
+        let value = Self::caught_value(error, state)?;
+        let _scope = state.enter_scope();
+        state.declare_variable(identifier.to_string(), Some(value), Some(0));
+        handler.run(state)
+    }
+}
+
+impl Runnable for TryStatementRunnable {
+    fn run(&self, state: &RunState) -> RunResult {
+        let calls = state.call_depth();
+        let result = match (self.body.run(state), &self.catch) {
+            // Errors that don't point into the program, such as the debugger
+            // stopping it, can't be caught
+            (Err(error), Some((identifier, handler))) if error.span().is_some() => {
+                state.unwind_call_stack(calls);
+                Self::catch(error, identifier, handler.as_ref(), state)
+            }
+            (result, _) => result,
+        };
+
+        let Some(finally) = &self.finally else {
+            return result;
+        };
+        // A value thrown on past the finally block is only held here meanwhile
+        let _root = match &result {
+            Err(RuntimeError::Thrown(value, _)) => Some(state.root(value)),
+            _ => None,
+        };
+        match finally.run(state)? {
+            ControlFlow::Normal => result,
+            flow => Ok(flow),
+        }
     }
 }
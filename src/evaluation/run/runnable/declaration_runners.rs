@@ -1,7 +1,12 @@
-use super::super::run_state::RunState;
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use super::super::run_state::{RunScopes, RunState};
 use super::basic_runners::Runnable;
 use crate::evaluation::evaluator::Evaluable;
-use crate::evaluation::run::runnable::basic_runners::RunResult;
+use crate::evaluation::run::runnable::basic_runners::{ControlFlow, RunResult};
+use crate::evaluation::RuntimeValue;
 
 #[derive(Debug)]
 pub struct VarDeclarationRunnable {
@@ -22,7 +27,7 @@ impl Runnable for VarDeclarationRunnable {
             value = Some(expr.eval(state)?);
         }
         state.declare_variable(self.identifier.clone(), value, Some(0));
-        Ok(None)
+        Ok(ControlFlow::Normal)
     }
 }
 
@@ -39,15 +44,72 @@ impl BlockRunnable {
 
 impl Runnable for BlockRunnable {
     fn run(&self, state: &RunState) -> RunResult {
-        state.enter_scope()?;
-        let mut ret = None;
+        let _scope = state.enter_scope();
         for declaration in &self.declarations {
-            ret = declaration.run(state)?;
-            if ret.is_some() {
-                break;
+            let flow = declaration.run(state)?;
+            if !flow.is_normal() {
+                return Ok(flow);
+            }
+        }
+        Ok(ControlFlow::Normal)
+    }
+}
+
+/// Runs a module the first time it is imported, in a scope of its own enclosed
+/// by the globals, then declares what its top level declared: each name in the
+/// importing scope, or all of them as the properties of one namespace value.
+#[derive(Debug)]
+pub struct ImportRunnable {
+    path: PathBuf,
+    module: Rc<dyn Runnable>,
+    alias: Option<String>,
+}
+
+impl ImportRunnable {
+    pub fn new(path: PathBuf, module: Rc<dyn Runnable>, alias: Option<String>) -> Self {
+        Self {
+            path,
+            module,
+            alias,
+        }
+    }
+}
+
+impl Runnable for ImportRunnable {
+    fn run(&self, state: &RunState) -> RunResult {
+        let scope = match state.get_module_scope(&self.path) {
+            Some(scope) => scope,
+            None => {
+                let scope = RunScopes::new(Some(state.get_global_scope()));
+                let scope = Rc::new(RefCell::new(scope));
+                let scopes = state.replace_scopes(scope.clone());
+                self.module.run(state)?;
+                drop(scopes);
+                state.set_module_scope(self.path.clone(), scope.clone());
+                scope
+            }
+        };
+
+        let exports = scope
+            .borrow()
+            .variables()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect::<Vec<_>>();
+        match &self.alias {
+            None => {
+                for (name, value) in exports {
+                    state.declare_variable(name, Some(value), Some(0));
+                }
+            }
+            Some(alias) => {
+                let namespace = state.initialize_instance(alias.clone(), None)?;
+                for (name, value) in exports {
+                    state.set_instance_value(namespace, &name, value)?;
+                }
+                let namespace = RuntimeValue::ClassInstance(namespace, alias.clone());
+                state.declare_variable(alias.clone(), Some(namespace), Some(0));
             }
         }
-        state.exit_scope()?;
-        Ok(ret)
+        Ok(ControlFlow::Normal)
     }
 }
@@ -2,5 +2,5 @@ mod native_functions;
 mod traits;
 
 pub use native_functions::get_native_functions;
-pub use native_functions::NativeFunctionError;
+pub use native_functions::{BuiltinFunction, HostFunction, NativeFunctionError};
 pub use traits::Callable;
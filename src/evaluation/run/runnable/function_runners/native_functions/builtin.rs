@@ -0,0 +1,144 @@
+use super::NativeFunctionError;
+
+use crate::evaluation::{
+    evaluator::Evaluable, run::Callable, runtime_value::ThisInstance, List, Map, MapKey, RunState,
+    RuntimeError, RuntimeValue,
+};
+
+pub type BuiltinResult = Result<RuntimeValue, NativeFunctionError>;
+
+/// A native function supplied by a program embedding the interpreter.
+pub type HostFunction = Box<dyn Fn(&[RuntimeValue]) -> Result<RuntimeValue, RuntimeError>>;
+
+enum Builtin {
+    /// Computed from the arguments alone.
+    Pure(fn(&[RuntimeValue]) -> BuiltinResult),
+    /// Needs the run state, for the sandbox policy for instance.
+    WithState(fn(&[RuntimeValue], &RunState) -> BuiltinResult),
+    Host(HostFunction),
+}
+
+impl std::fmt::Debug for Builtin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Builtin::Pure(_) => write!(f, "Pure"),
+            Builtin::WithState(_) => write!(f, "WithState"),
+            Builtin::Host(_) => write!(f, "Host"),
+        }
+    }
+}
+
+/// A native function with a fixed number of arguments.
+#[derive(Debug)]
+pub struct BuiltinFunction {
+    arity: usize,
+    function: Builtin,
+}
+
+impl BuiltinFunction {
+    pub fn new(arity: usize, function: fn(&[RuntimeValue]) -> BuiltinResult) -> Self {
+        Self {
+            arity,
+            function: Builtin::Pure(function),
+        }
+    }
+
+    pub fn with_state(
+        arity: usize,
+        function: fn(&[RuntimeValue], &RunState) -> BuiltinResult,
+    ) -> Self {
+        Self {
+            arity,
+            function: Builtin::WithState(function),
+        }
+    }
+
+    pub fn host(arity: usize, function: HostFunction) -> Self {
+        Self {
+            arity,
+            function: Builtin::Host(function),
+        }
+    }
+}
+
+impl Callable for BuiltinFunction {
+    fn arity(&self, _: &RunState) -> Result<usize, RuntimeError> {
+        Ok(self.arity)
+    }
+
+    fn call(
+        &self,
+        arguments: Vec<RuntimeValue>,
+        _this_pointer: Option<ThisInstance>,
+        state: &RunState,
+    ) -> Result<RuntimeValue, RuntimeError> {
+        if arguments.len() != self.arity {
+            return Err(NativeFunctionError::ArityMismatch(self.arity, arguments.len()).into());
+        }
+        let result = match &self.function {
+            Builtin::Pure(function) => function(&arguments),
+            Builtin::WithState(function) => function(&arguments, state),
+            Builtin::Host(function) => return function(&arguments),
+        };
+        Ok(result?)
+    }
+}
+
+impl Evaluable for BuiltinFunction {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        self.call(Vec::new(), None, state)
+    }
+}
+
+/// The argument at `index` as a number.
+pub fn number(arguments: &[RuntimeValue], index: usize) -> Result<f64, NativeFunctionError> {
+    match &arguments[index] {
+        RuntimeValue::Number(number) => Ok(*number),
+        _ => Err(NativeFunctionError::InvalidArgument(index + 1, "a number")),
+    }
+}
+
+/// The argument at `index` as a whole number that can be used as a position.
+pub fn position(arguments: &[RuntimeValue], index: usize) -> Result<usize, NativeFunctionError> {
+    match &arguments[index] {
+        RuntimeValue::Number(number) if *number >= 0.0 && number.fract() == 0.0 => {
+            Ok(*number as usize)
+        }
+        _ => Err(NativeFunctionError::InvalidArgument(
+            index + 1,
+            "a non-negative integer",
+        )),
+    }
+}
+
+/// The argument at `index` as a string.
+pub fn string(arguments: &[RuntimeValue], index: usize) -> Result<&str, NativeFunctionError> {
+    match &arguments[index] {
+        RuntimeValue::String(string) => Ok(string),
+        _ => Err(NativeFunctionError::InvalidArgument(index + 1, "a string")),
+    }
+}
+
+/// The argument at `index` as a list.
+pub fn list(arguments: &[RuntimeValue], index: usize) -> Result<&List, NativeFunctionError> {
+    match &arguments[index] {
+        RuntimeValue::List(list) => Ok(list),
+        _ => Err(NativeFunctionError::InvalidArgument(index + 1, "a list")),
+    }
+}
+
+/// The argument at `index` as a map.
+pub fn map(arguments: &[RuntimeValue], index: usize) -> Result<&Map, NativeFunctionError> {
+    match &arguments[index] {
+        RuntimeValue::Map(map) => Ok(map),
+        _ => Err(NativeFunctionError::InvalidArgument(index + 1, "a map")),
+    }
+}
+
+/// The argument at `index` as a map key.
+pub fn key(arguments: &[RuntimeValue], index: usize) -> Result<MapKey, NativeFunctionError> {
+    MapKey::from_runtime_value(&arguments[index]).ok_or(NativeFunctionError::InvalidArgument(
+        index + 1,
+        "a number, string, boolean or nil",
+    ))
+}
@@ -0,0 +1,29 @@
+use crate::evaluation::{
+    evaluator::Evaluable, run::Callable, runtime_value::ThisInstance, RunState, RuntimeError,
+    RuntimeValue,
+};
+
+/// Forces a garbage collection and returns the number of instances freed.
+#[derive(Debug)]
+pub struct GcNativeFunction;
+
+impl Callable for GcNativeFunction {
+    fn arity(&self, _: &RunState) -> Result<usize, RuntimeError> {
+        Ok(0)
+    }
+
+    fn call(
+        &self,
+        _arguments: Vec<RuntimeValue>,
+        _this_pointer: Option<ThisInstance>,
+        state: &RunState,
+    ) -> Result<RuntimeValue, RuntimeError> {
+        self.eval(state)
+    }
+}
+
+impl Evaluable for GcNativeFunction {
+    fn eval(&self, state: &RunState) -> Result<RuntimeValue, RuntimeError> {
+        Ok(RuntimeValue::Number(state.collect_garbage(None) as f64))
+    }
+}
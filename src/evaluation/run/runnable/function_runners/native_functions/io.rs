@@ -0,0 +1,59 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+use super::builtin::{string, BuiltinResult};
+use super::NativeFunctionError;
+
+use crate::evaluation::{RunState, RuntimeValue};
+
+/// `readLine()`: the next line of input, standard input unless the host
+/// supplies another, without its line break, or `nil` once the input is
+/// exhausted.
+pub fn read_line(_: &[RuntimeValue], state: &RunState) -> BuiltinResult {
+    let line = state
+        .read_line()
+        .map_err(|error| NativeFunctionError::Io("<stdin>".to_string(), error))?;
+    Ok(line.map_or(RuntimeValue::Nil, RuntimeValue::String))
+}
+
+/// `readFile(path)`: the contents of the file at `path`.
+pub fn read_file(arguments: &[RuntimeValue], state: &RunState) -> BuiltinResult {
+    let path = string(arguments, 0)?;
+    let resolved = state.sandbox().check_read(path)?;
+    let contents = fs::read_to_string(resolved)
+        .map_err(|error| NativeFunctionError::Io(path.to_string(), error))?;
+    Ok(RuntimeValue::String(contents))
+}
+
+/// `writeFile(path, s)`: replaces the contents of the file at `path` with `s`,
+/// creating it if needed.
+pub fn write_file(arguments: &[RuntimeValue], state: &RunState) -> BuiltinResult {
+    let path = string(arguments, 0)?;
+    let contents = string(arguments, 1)?;
+    let resolved = state.sandbox().check_write(path)?;
+    fs::write(resolved, contents)
+        .map_err(|error| NativeFunctionError::Io(path.to_string(), error))?;
+    Ok(RuntimeValue::Nil)
+}
+
+/// `appendFile(path, s)`: adds `s` to the end of the file at `path`, creating
+/// it if needed.
+pub fn append_file(arguments: &[RuntimeValue], state: &RunState) -> BuiltinResult {
+    let path = string(arguments, 0)?;
+    let contents = string(arguments, 1)?;
+    let resolved = state.sandbox().check_write(path)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(resolved)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|error| NativeFunctionError::Io(path.to_string(), error))?;
+    Ok(RuntimeValue::Nil)
+}
+
+/// `exists(path)`: whether there is a file or directory at `path`.
+pub fn exists(arguments: &[RuntimeValue], state: &RunState) -> BuiltinResult {
+    let path = string(arguments, 0)?;
+    let resolved = state.sandbox().check_read(path)?;
+    Ok(RuntimeValue::Boolean(resolved.exists()))
+}
@@ -0,0 +1,54 @@
+use super::builtin::{list, position, BuiltinResult};
+use super::NativeFunctionError;
+
+use crate::evaluation::{List, RunState, RuntimeValue};
+
+/// `push(xs, value)`: appends `value` to `xs`.
+pub fn push(arguments: &[RuntimeValue]) -> BuiltinResult {
+    list(arguments, 0)?
+        .elements_mut()
+        .push(arguments[1].clone());
+    Ok(RuntimeValue::Nil)
+}
+
+/// `pop(xs)`: removes the last element of `xs` and returns it.
+pub fn pop(arguments: &[RuntimeValue]) -> BuiltinResult {
+    list(arguments, 0)?
+        .elements_mut()
+        .pop()
+        .ok_or(NativeFunctionError::EmptyList)
+}
+
+/// `insert(xs, index, value)`: inserts `value` at `index`, shifting the
+/// elements after it. `index` may be the length of `xs`, to append.
+pub fn insert(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let list = list(arguments, 0)?;
+    let index = position(arguments, 1)?;
+    let mut elements = list.elements_mut();
+    if index > elements.len() {
+        return Err(NativeFunctionError::IndexOutOfRange(index, elements.len()));
+    }
+    elements.insert(index, arguments[2].clone());
+    Ok(RuntimeValue::Nil)
+}
+
+/// `remove(xs, index)`: removes the element at `index` and returns it.
+pub fn remove(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let list = list(arguments, 0)?;
+    let index = position(arguments, 1)?;
+    let mut elements = list.elements_mut();
+    if index >= elements.len() {
+        return Err(NativeFunctionError::IndexOutOfRange(index, elements.len()));
+    }
+    Ok(elements.remove(index))
+}
+
+/// `args()`: the arguments given to the script on the command line.
+pub fn args(_: &[RuntimeValue], state: &RunState) -> BuiltinResult {
+    let arguments = state
+        .script_arguments()
+        .into_iter()
+        .map(RuntimeValue::String)
+        .collect();
+    Ok(RuntimeValue::List(List::new(arguments)))
+}
@@ -0,0 +1,35 @@
+use super::builtin::{key, map, BuiltinResult};
+
+use crate::evaluation::{List, RuntimeValue};
+
+/// `keys(m)`: a list of the keys of `m`, in the order they were added.
+pub fn keys(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let keys = map(arguments, 0)?
+        .entries()
+        .keys()
+        .map(|key| key.to_runtime_value())
+        .collect();
+    Ok(RuntimeValue::List(List::new(keys)))
+}
+
+/// `values(m)`: a list of the values of `m`, in the order of their keys.
+pub fn values(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let values = map(arguments, 0)?.entries().values().cloned().collect();
+    Ok(RuntimeValue::List(List::new(values)))
+}
+
+/// `has(m, key)`: whether `m` has an entry for `key`.
+pub fn has(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let map = map(arguments, 0)?;
+    let key = key(arguments, 1)?;
+    Ok(RuntimeValue::Boolean(map.entries().contains(&key)))
+}
+
+/// `delete(m, key)`: removes the entry for `key` and returns its value, or
+/// `nil` when there is none.
+pub fn delete(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let map = map(arguments, 0)?;
+    let key = key(arguments, 1)?;
+    let removed = map.entries_mut().remove(&key);
+    Ok(removed.unwrap_or(RuntimeValue::Nil))
+}
@@ -0,0 +1,33 @@
+use super::builtin::{number, BuiltinResult};
+
+use crate::evaluation::RuntimeValue;
+
+pub fn floor(arguments: &[RuntimeValue]) -> BuiltinResult {
+    Ok(RuntimeValue::Number(number(arguments, 0)?.floor()))
+}
+
+pub fn sqrt(arguments: &[RuntimeValue]) -> BuiltinResult {
+    Ok(RuntimeValue::Number(number(arguments, 0)?.sqrt()))
+}
+
+pub fn abs(arguments: &[RuntimeValue]) -> BuiltinResult {
+    Ok(RuntimeValue::Number(number(arguments, 0)?.abs()))
+}
+
+pub fn pow(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let base = number(arguments, 0)?;
+    let exponent = number(arguments, 1)?;
+    Ok(RuntimeValue::Number(base.powf(exponent)))
+}
+
+pub fn min(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let left = number(arguments, 0)?;
+    let right = number(arguments, 1)?;
+    Ok(RuntimeValue::Number(left.min(right)))
+}
+
+pub fn max(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let left = number(arguments, 0)?;
+    let right = number(arguments, 1)?;
+    Ok(RuntimeValue::Number(left.max(right)))
+}
@@ -1,17 +1,98 @@
 use thiserror::Error;
 
+mod builtin;
 mod clock;
+mod gc;
+mod io;
+mod lists;
+mod maps;
+mod math;
+mod strings;
+mod types;
 
 #[derive(Debug, Error)]
 pub enum NativeFunctionError {
     #[error("System clock before UNIX EPOCH")]
     SystemClockBeforeUnixEpoch,
+    #[error("Expected {0} arguments but got {1}.")]
+    ArityMismatch(usize, usize),
+    #[error("Argument {0} must be {1}.")]
+    InvalidArgument(usize, &'static str),
+    #[error("Index {0} is out of range for length {1}.")]
+    IndexOutOfRange(usize, usize),
+    #[error("Length {0} from index {1} is out of range for length {2}.")]
+    LengthOutOfRange(usize, usize, usize),
+    #[error("Cannot pop from an empty list.")]
+    EmptyList,
+    #[error("Permission denied: cannot {0} '{1}'.")]
+    PermissionDenied(&'static str, String),
+    #[error("Could not access '{0}': {1}")]
+    Io(String, std::io::Error),
+    #[error("Expected {0} but got {1}.")]
+    TypeMismatch(&'static str, &'static str),
+    #[error("{0}")]
+    Host(String),
 }
 
+pub use builtin::{BuiltinFunction, HostFunction};
 use clock::ClockNativeFunction;
+use gc::GcNativeFunction;
 
 use crate::evaluation::run::Callable;
 
 pub fn get_native_functions() -> Vec<(&'static str, Box<dyn Callable>)> {
-    vec![("clock", Box::new(ClockNativeFunction))]
+    vec![
+        ("clock", Box::new(ClockNativeFunction)),
+        ("gc", Box::new(GcNativeFunction)),
+        ("len", Box::new(BuiltinFunction::new(1, strings::len))),
+        ("substr", Box::new(BuiltinFunction::new(3, strings::substr))),
+        (
+            "indexOf",
+            Box::new(BuiltinFunction::new(2, strings::index_of)),
+        ),
+        ("split", Box::new(BuiltinFunction::new(2, strings::split))),
+        ("upper", Box::new(BuiltinFunction::new(1, strings::upper))),
+        ("lower", Box::new(BuiltinFunction::new(1, strings::lower))),
+        ("push", Box::new(BuiltinFunction::new(2, lists::push))),
+        ("pop", Box::new(BuiltinFunction::new(1, lists::pop))),
+        ("insert", Box::new(BuiltinFunction::new(3, lists::insert))),
+        ("remove", Box::new(BuiltinFunction::new(2, lists::remove))),
+        (
+            "args",
+            Box::new(BuiltinFunction::with_state(0, lists::args)),
+        ),
+        ("keys", Box::new(BuiltinFunction::new(1, maps::keys))),
+        ("values", Box::new(BuiltinFunction::new(1, maps::values))),
+        ("has", Box::new(BuiltinFunction::new(2, maps::has))),
+        ("delete", Box::new(BuiltinFunction::new(2, maps::delete))),
+        ("str", Box::new(BuiltinFunction::new(1, types::str))),
+        ("num", Box::new(BuiltinFunction::new(1, types::num))),
+        ("type", Box::new(BuiltinFunction::new(1, types::type_of))),
+        ("floor", Box::new(BuiltinFunction::new(1, math::floor))),
+        ("sqrt", Box::new(BuiltinFunction::new(1, math::sqrt))),
+        ("abs", Box::new(BuiltinFunction::new(1, math::abs))),
+        ("pow", Box::new(BuiltinFunction::new(2, math::pow))),
+        ("min", Box::new(BuiltinFunction::new(2, math::min))),
+        ("max", Box::new(BuiltinFunction::new(2, math::max))),
+        (
+            "readLine",
+            Box::new(BuiltinFunction::with_state(0, io::read_line)),
+        ),
+        (
+            "readFile",
+            Box::new(BuiltinFunction::with_state(1, io::read_file)),
+        ),
+        (
+            "writeFile",
+            Box::new(BuiltinFunction::with_state(2, io::write_file)),
+        ),
+        (
+            "appendFile",
+            Box::new(BuiltinFunction::with_state(2, io::append_file)),
+        ),
+        (
+            "exists",
+            Box::new(BuiltinFunction::with_state(1, io::exists)),
+        ),
+    ]
 }
@@ -0,0 +1,74 @@
+use super::builtin::{position, string, BuiltinResult};
+use super::NativeFunctionError;
+
+use crate::evaluation::{List, RuntimeValue};
+
+/// `len(s)`: the number of characters in the string `s`, of elements in the
+/// list `s` or of entries in the map `s`.
+pub fn len(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let len = match &arguments[0] {
+        RuntimeValue::String(string) => string.chars().count(),
+        RuntimeValue::List(list) => list.len(),
+        RuntimeValue::Map(map) => map.len(),
+        _ => {
+            return Err(NativeFunctionError::InvalidArgument(
+                1,
+                "a string, a list or a map",
+            ))
+        }
+    };
+    Ok(RuntimeValue::Number(len as f64))
+}
+
+/// `split(s, separator)`: the parts of `s` between each `separator`, or its
+/// characters when `separator` is empty.
+pub fn split(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let text = string(arguments, 0)?;
+    let separator = string(arguments, 1)?;
+    let parts = if separator.is_empty() {
+        text.chars()
+            .map(|c| RuntimeValue::String(c.to_string()))
+            .collect()
+    } else {
+        text.split(separator)
+            .map(|part| RuntimeValue::String(part.to_string()))
+            .collect()
+    };
+    Ok(RuntimeValue::List(List::new(parts)))
+}
+
+/// `substr(s, start, length)`: the `length` characters of `s` starting at
+/// character `start`.
+pub fn substr(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let string = string(arguments, 0)?;
+    let start = position(arguments, 1)?;
+    let length = position(arguments, 2)?;
+    let count = string.chars().count();
+    if start > count {
+        return Err(NativeFunctionError::IndexOutOfRange(start, count));
+    }
+    if start + length > count {
+        return Err(NativeFunctionError::LengthOutOfRange(length, start, count));
+    }
+    let substring = string.chars().skip(start).take(length).collect();
+    Ok(RuntimeValue::String(substring))
+}
+
+/// `indexOf(s, part)`: the character position of the first `part` in `s`, or
+/// `-1` when there is none.
+pub fn index_of(arguments: &[RuntimeValue]) -> BuiltinResult {
+    let text = string(arguments, 0)?;
+    let part = string(arguments, 1)?;
+    let index = text
+        .find(part)
+        .map_or(-1.0, |byte| text[..byte].chars().count() as f64);
+    Ok(RuntimeValue::Number(index))
+}
+
+pub fn upper(arguments: &[RuntimeValue]) -> BuiltinResult {
+    Ok(RuntimeValue::String(string(arguments, 0)?.to_uppercase()))
+}
+
+pub fn lower(arguments: &[RuntimeValue]) -> BuiltinResult {
+    Ok(RuntimeValue::String(string(arguments, 0)?.to_lowercase()))
+}
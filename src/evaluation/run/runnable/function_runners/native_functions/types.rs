@@ -0,0 +1,30 @@
+use super::builtin::BuiltinResult;
+use super::NativeFunctionError;
+
+use crate::evaluation::RuntimeValue;
+
+/// `str(x)`: `x` as `print` would show it.
+pub fn str(arguments: &[RuntimeValue]) -> BuiltinResult {
+    Ok(RuntimeValue::String(arguments[0].to_string()))
+}
+
+/// `num(x)`: the number a string spells, or `nil` when it isn't one. Numbers
+/// are returned unchanged.
+pub fn num(arguments: &[RuntimeValue]) -> BuiltinResult {
+    match &arguments[0] {
+        RuntimeValue::Number(number) => Ok(RuntimeValue::Number(*number)),
+        RuntimeValue::String(string) => Ok(string
+            .trim()
+            .parse::<f64>()
+            .map_or(RuntimeValue::Nil, RuntimeValue::Number)),
+        _ => Err(NativeFunctionError::InvalidArgument(
+            1,
+            "a number or a string",
+        )),
+    }
+}
+
+/// `type(x)`: the name of the kind of value `x` is.
+pub fn type_of(arguments: &[RuntimeValue]) -> BuiltinResult {
+    Ok(RuntimeValue::String(arguments[0].type_name().to_string()))
+}
@@ -5,14 +5,19 @@ mod declaration_runners;
 mod function_runners;
 
 // Re-export the Runnable trait
-pub use basic_runners::Runnable;
+pub use basic_runners::{ControlFlow, RunResult, Runnable};
 
 // Re-export all runner implementations
 pub use basic_runners::{
-    ExpressionRunnable, FunctionDeclarationRunnable, IfNoClassErrorRunnable, PrintRunnable,
-    ProgramRunnable, ReturnRunnable,
+    BreakRunnable, ContinueRunnable, ExpressionRunnable, FunctionDeclarationRunnable,
+    IfNoClassErrorRunnable, PrintRunnable, ProgramRunnable, ReturnRunnable, ThrowRunnable,
+    TracedRunnable,
 };
 pub use class_runners::{ClassDeclarationRunnable, ClassInitializationCallable};
-pub use control_flow_runners::{ForStatementRunnable, IsStatementRunnable, WhileStatementRunnable};
-pub use declaration_runners::{BlockRunnable, VarDeclarationRunnable};
-pub use function_runners::{get_native_functions, Callable, NativeFunctionError};
+pub use control_flow_runners::{
+    ForStatementRunnable, IsStatementRunnable, TryStatementRunnable, WhileStatementRunnable,
+};
+pub use declaration_runners::{BlockRunnable, ImportRunnable, VarDeclarationRunnable};
+pub use function_runners::{
+    get_native_functions, BuiltinFunction, Callable, HostFunction, NativeFunctionError,
+};
@@ -1,25 +1,115 @@
+use std::rc::Rc;
+
 use crate::common::{Visitable, VisitorWithContext};
 use crate::evaluation::evaluator::{
-    AssignmentEvaluatorBuilder, FunctionCallable, PrimaryEvaluator,
+    AssignmentEvaluatorBuilder, Evaluable, FunctionCallable, LambdaEvaluator, PrimaryEvaluator,
+    LAMBDA_NAME,
 };
+use crate::evaluation::modules::{ImportError, ModuleFile};
 use crate::evaluation::resolver::ResolverError;
 use crate::evaluation::run::runnable::{
     ClassDeclarationRunnable, ClassInitializationCallable, IfNoClassErrorRunnable,
 };
 use crate::evaluation::run::runnable::{
-    FunctionDeclarationRunnable, Runnable, VarDeclarationRunnable,
+    FunctionDeclarationRunnable, ImportRunnable, ReturnRunnable, Runnable, TracedRunnable,
+    VarDeclarationRunnable,
 };
 use crate::evaluation::runtime_value::Result;
-use crate::evaluation::BuilderContext;
 use crate::evaluation::RuntimeError;
+use crate::evaluation::{BuilderContext, TracePoint};
 use crate::syntax_analysis::{
-    ClassDeclaration, Declaration, DeclarationType, Function, FunctionDeclaration, VarDeclaration,
+    ClassDeclaration, Declaration, DeclarationType, Function, FunctionDeclaration,
+    ImportDeclaration, Lambda, LambdaBody, Parameters, VarDeclaration,
 };
-use crate::tokenizer::TokenValue;
+use crate::tokenizer::{Span, Token, TokenValue};
 
 pub struct RunnableBuilder;
 
 impl RunnableBuilder {
+    /// Lets an installed `RunHook` see the statement starting with `token`
+    /// before it runs.
+    pub(super) fn traced(
+        runnable: Box<dyn Runnable>,
+        token: Option<&Token>,
+        context: &BuilderContext,
+    ) -> Box<dyn Runnable> {
+        match token {
+            Some(token) if context.traced.get() => {
+                context.trace(TracePoint::Statement(token.span.clone()));
+                Box::new(TracedRunnable::new(token.span.clone(), runnable))
+            }
+            _ => runnable,
+        }
+    }
+
+    /// Builds the module `import` names once, in the same resolvers as the
+    /// program importing it, so that function indices and global names are
+    /// shared by every file. `import` is the path string in the import.
+    fn build_module(
+        module: &ModuleFile,
+        import: &Token,
+        context: &BuilderContext,
+    ) -> Result<Rc<dyn Runnable>> {
+        if let Some(runnable) = context.modules.borrow().get(&module.path) {
+            return Ok(runnable.clone());
+        }
+
+        let is_root = context.importing.borrow().is_empty();
+        if is_root {
+            let program = ModuleFile::of_program(&import.span.file);
+            context.importing.borrow_mut().extend(program);
+        }
+        let cycle = context
+            .importing
+            .borrow()
+            .iter()
+            .any(|file| file.path == module.path);
+        let result = match cycle {
+            true => {
+                let importing = context.importing.borrow();
+                let chain = importing
+                    .iter()
+                    .chain([module])
+                    .map(|file| file.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" -> ");
+                let importer = import.span.file.to_string();
+                let span = import.span.clone();
+                Err(ImportError::Cycle(import.lexeme.clone(), importer, chain, span).into())
+            }
+            false => Self::build_module_program(module, import, context),
+        };
+        if is_root {
+            context.importing.borrow_mut().clear();
+        }
+
+        let runnable = result?;
+        context
+            .modules
+            .borrow_mut()
+            .insert(module.path.clone(), runnable.clone());
+        Ok(runnable)
+    }
+
+    fn build_module_program(
+        module: &ModuleFile,
+        import: &Token,
+        context: &BuilderContext,
+    ) -> Result<Rc<dyn Runnable>> {
+        let program = module.parse(import)?;
+        context.importing.borrow_mut().push(module.clone());
+        let runnable = program.accept_with_context(&Self, context);
+        context.importing.borrow_mut().pop();
+        runnable.map(Rc::from).map_err(|error| {
+            ImportError::InvalidModule(
+                import.lexeme.clone(),
+                error.to_string(),
+                import.span.clone(),
+            )
+            .into()
+        })
+    }
+
     fn declare_function(
         node: &Function,
         context: &BuilderContext,
@@ -27,7 +117,6 @@ impl RunnableBuilder {
         super_available: bool,
     ) -> Result<(usize, String)> {
         let function_ast = node;
-        let parameters = &function_ast.parameters.parameters;
 
         let function_ident = function_ast
             .token_list
@@ -38,50 +127,72 @@ impl RunnableBuilder {
         context
             .resolver
             .borrow_mut()
-            .declare(&function_ident_string, function_ident.line)?;
+            .declare(&function_ident_string, &function_ident.span)?;
         context
             .resolver
             .borrow_mut()
             .define(&function_ident_string)?;
+
+        let pointer = Self::build_function(
+            &function_ident_string,
+            &function_ast.parameters,
+            |context| function_ast.block.accept_with_context(&Self, context),
+            context,
+            is_method,
+            super_available,
+        )?;
+
+        Ok((pointer, function_ident_string))
+    }
+
+    /// Builds the body of a function with its parameters in a scope of their
+    /// own, and registers it under the pointer its callables refer to.
+    fn build_function(
+        name: &str,
+        parameters: &Parameters,
+        build_body: impl FnOnce(&BuilderContext) -> Result<Box<dyn Runnable>>,
+        context: &BuilderContext,
+        is_method: bool,
+        super_available: bool,
+    ) -> Result<usize> {
         context.resolver.borrow_mut().enter_scope()?;
         if is_method {
             context
                 .resolver
                 .borrow_mut()
-                .enter_method(function_ident_string.clone(), super_available);
+                .enter_method(name.to_string(), super_available);
         } else {
             context
                 .resolver
                 .borrow_mut()
-                .enter_function(function_ident_string.clone(), super_available);
+                .enter_function(name.to_string(), super_available);
         }
+        let parameters = &parameters.parameters;
         for parameter in parameters {
             context
                 .resolver
                 .borrow_mut()
-                .declare(&parameter.token.lexeme, parameter.token.line)?;
+                .declare(&parameter.token.lexeme, &parameter.token.span)?;
             context
                 .resolver
                 .borrow_mut()
                 .define(&&parameter.token.lexeme)?;
         }
 
-        let block_runnable = function_ast.block.accept_with_context(&Self, context)?;
+        let block_runnable = build_body(context)?;
         context.resolver.borrow_mut().exit_scope()?;
         context.resolver.borrow_mut().exit_function_or_method();
 
         let callable = FunctionCallable::new(
             block_runnable,
             parameters.iter().map(|p| p.token.lexeme.clone()).collect(),
-            function_ident_string.clone(),
+            name.to_string(),
         );
 
-        let pointer = context
+        Ok(context
             .functions_resolver
             .borrow_mut()
-            .add_function(Box::new(callable))?;
-
-        Ok((pointer, function_ident_string))
+            .add_function(Box::new(callable))?)
     }
 }
 
@@ -106,7 +217,7 @@ impl VisitorWithContext<&VarDeclaration, Result<Box<dyn Runnable>>, BuilderConte
         context
             .resolver
             .borrow_mut()
-            .declare(&ident_value, ident_token.line)?;
+            .declare(&ident_value, &ident_token.span)?;
         if let Some(expr) = &node.expr {
             evaluable = Some(expr.accept_with_context(&AssignmentEvaluatorBuilder, context)?);
         }
@@ -127,13 +238,67 @@ impl VisitorWithContext<&Declaration, Result<Box<dyn Runnable>>, BuilderContext>
         node: &Declaration,
         context: &BuilderContext,
     ) -> Result<Box<dyn Runnable>> {
-        match &node.token_type {
-            DeclarationType::VarDeclaration(var) => var.accept_with_context(&Self, context),
-            DeclarationType::Statement(stmt) => stmt.accept_with_context(&Self, context),
-            DeclarationType::FunctionDeclaration(func) => func.accept_with_context(&Self, context),
-            DeclarationType::ClassDeclaration(class) => class.accept_with_context(&Self, context),
-            DeclarationType::None => Err(RuntimeError::ASTInvalidStructure),
+        let (runnable, token) = match &node.token_type {
+            DeclarationType::VarDeclaration(var) => (
+                var.accept_with_context(&Self, context)?,
+                var.token_list.first(),
+            ),
+            // Statements are traced as they are built, wherever they are
+            DeclarationType::Statement(stmt) => return stmt.accept_with_context(&Self, context),
+            DeclarationType::FunctionDeclaration(func) => (
+                func.accept_with_context(&Self, context)?,
+                func.token_list.first(),
+            ),
+            DeclarationType::ClassDeclaration(class) => (
+                class.accept_with_context(&Self, context)?,
+                class.token_list.first(),
+            ),
+            DeclarationType::ImportDeclaration(import) => (
+                import.accept_with_context(&Self, context)?,
+                import.token_list.first(),
+            ),
+            DeclarationType::None => return Err(RuntimeError::ASTInvalidStructure),
+        };
+        Ok(Self::traced(runnable, token, context))
+    }
+}
+
+impl VisitorWithContext<&ImportDeclaration, Result<Box<dyn Runnable>>, BuilderContext>
+    for RunnableBuilder
+{
+    fn visit_with_context(
+        &self,
+        node: &ImportDeclaration,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Runnable>> {
+        let keyword = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        let import = node
+            .token_list
+            .get(1)
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        let TokenValue::String(target) = &import.token_value else {
+            return Err(RuntimeError::ASTInvalidStructure);
+        };
+        if !context.resolver.borrow().is_at_top_level() {
+            return Err(ResolverError::ImportOutsideTopLevel(keyword.span.clone()).into());
+        }
+
+        let module = ModuleFile::resolve(import, target)?;
+        let runnable = Self::build_module(&module, import, context)?;
+        let alias = node.alias.alias.as_ref().map(|alias| &alias.token);
+        if let Some(alias) = alias {
+            let mut resolver = context.resolver.borrow_mut();
+            resolver.declare(&alias.lexeme, &alias.span)?;
+            resolver.define(&alias.lexeme)?;
         }
+        Ok(Box::new(ImportRunnable::new(
+            module.path,
+            runnable,
+            alias.map(|alias| alias.lexeme.clone()),
+        )))
     }
 }
 
@@ -155,6 +320,32 @@ impl VisitorWithContext<&FunctionDeclaration, Result<Box<dyn Runnable>>, Builder
     }
 }
 
+impl VisitorWithContext<&Lambda, Result<Box<dyn Evaluable>>, BuilderContext> for RunnableBuilder {
+    fn visit_with_context(
+        &self,
+        node: &Lambda,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Evaluable>> {
+        let super_available = context.resolver.borrow().is_super_available();
+        let pointer = Self::build_function(
+            LAMBDA_NAME,
+            &node.parameters,
+            |context| match &node.body {
+                LambdaBody::Block(block) => block.accept_with_context(&Self, context),
+                LambdaBody::Expression(expression) => {
+                    let value =
+                        expression.accept_with_context(&AssignmentEvaluatorBuilder, context)?;
+                    Ok(Box::new(ReturnRunnable::new(Some(value))))
+                }
+            },
+            context,
+            false,
+            super_available,
+        )?;
+        Ok(Box::new(LambdaEvaluator::new(pointer)))
+    }
+}
+
 impl VisitorWithContext<&ClassDeclaration, Result<Box<dyn Runnable>>, BuilderContext>
     for RunnableBuilder
 {
@@ -172,7 +363,7 @@ impl VisitorWithContext<&ClassDeclaration, Result<Box<dyn Runnable>>, BuilderCon
         context
             .resolver
             .borrow_mut()
-            .declare(&class_ident_string, class_ident.line)?;
+            .declare(&class_ident_string, &class_ident.span)?;
         context.resolver.borrow_mut().define(&class_ident_string)?;
         context.resolver.borrow_mut().enter_class();
         let methods = node
@@ -193,7 +384,7 @@ impl VisitorWithContext<&ClassDeclaration, Result<Box<dyn Runnable>>, BuilderCon
             if super_class.token.lexeme == class_ident_string {
                 return Err(ResolverError::InheritFromItself(
                     super_class.token.lexeme.clone(),
-                    super_class.token.line,
+                    super_class.token.span.clone(),
                 )
                 .into());
             }
@@ -211,7 +402,7 @@ impl VisitorWithContext<&ClassDeclaration, Result<Box<dyn Runnable>>, BuilderCon
                 PrimaryEvaluator::from_raw_token(token_ref, &context.resolver.borrow())?;
             return Ok(Box::new(IfNoClassErrorRunnable::new(
                 primary_evaluator,
-                token_ref.line,
+                token_ref.span.clone(),
             )));
         }
 
@@ -229,10 +420,10 @@ impl VisitorWithContext<&ClassDeclaration, Result<Box<dyn Runnable>>, BuilderCon
             .super_class
             .super_class
             .as_ref()
-            .map::<Result<(PrimaryEvaluator, usize)>, _>(|ident| {
+            .map::<Result<(PrimaryEvaluator, Span)>, _>(|ident| {
                 let evaluator =
                     PrimaryEvaluator::from_raw_token(&ident.token, &context.resolver.borrow())?;
-                Ok((evaluator, ident.token.line))
+                Ok((evaluator, ident.token.span.clone()))
             })
             .transpose()?;
 
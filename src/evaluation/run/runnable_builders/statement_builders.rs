@@ -1,17 +1,19 @@
 use super::declaration_builders::RunnableBuilder;
 use crate::common::{Visitable, VisitorWithContext};
-use crate::evaluation::evaluator::{AssignmentEvaluatorBuilder, INIT_FUNCTION_NAME};
+use crate::evaluation::evaluator::{traced_branch, AssignmentEvaluatorBuilder, INIT_FUNCTION_NAME};
 use crate::evaluation::resolver::ResolverError;
 use crate::evaluation::run::runnable::{
-    ExpressionRunnable, ForStatementRunnable, IsStatementRunnable, PrintRunnable, ReturnRunnable,
-    Runnable, WhileStatementRunnable,
+    BreakRunnable, ContinueRunnable, ExpressionRunnable, ForStatementRunnable, IsStatementRunnable,
+    PrintRunnable, ReturnRunnable, Runnable, ThrowRunnable, TryStatementRunnable,
+    WhileStatementRunnable,
 };
 use crate::evaluation::runtime_value::Result;
 use crate::evaluation::BuilderContext;
 use crate::evaluation::RuntimeError;
 use crate::syntax_analysis::{
-    ExprStatement, ForStatement, ForStatementType, IfStatement, PrintStatement, ReturnStatement,
-    Statement, StatementType, WhileStatement,
+    BreakStatement, ContinueStatement, ExprStatement, ForStatement, ForStatementType, IfStatement,
+    PrintStatement, ReturnStatement, Statement, StatementType, ThrowStatement, TryStatement,
+    WhileStatement,
 };
 
 impl VisitorWithContext<&PrintStatement, Result<Box<dyn Runnable>>, BuilderContext>
@@ -50,10 +52,11 @@ impl VisitorWithContext<&Statement, Result<Box<dyn Runnable>>, BuilderContext> f
         node: &Statement,
         context: &BuilderContext,
     ) -> Result<Box<dyn Runnable>> {
-        match &node.token_type {
+        let runnable = match &node.token_type {
             StatementType::ExprStatement(expr) => expr.accept_with_context(&Self, context),
             StatementType::PrintStatement(print) => print.accept_with_context(&Self, context),
-            StatementType::Block(block) => block.accept_with_context(&Self, context),
+            // A block is only traced through the statements in it
+            StatementType::Block(block) => return block.accept_with_context(&Self, context),
             StatementType::IfStatement(if_stmt) => if_stmt.accept_with_context(&Self, context),
             StatementType::WhileStatement(while_stmt) => {
                 while_stmt.accept_with_context(&Self, context)
@@ -62,8 +65,19 @@ impl VisitorWithContext<&Statement, Result<Box<dyn Runnable>>, BuilderContext> f
             StatementType::ReturnStatement(return_stmt) => {
                 return_stmt.accept_with_context(&Self, context)
             }
+            StatementType::BreakStatement(break_stmt) => {
+                break_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ContinueStatement(continue_stmt) => {
+                continue_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ThrowStatement(throw_stmt) => {
+                throw_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::TryStatement(try_stmt) => try_stmt.accept_with_context(&Self, context),
             StatementType::None => Err(RuntimeError::ASTInvalidStructure),
-        }
+        }?;
+        Ok(Self::traced(runnable, node.first_token(), context))
     }
 }
 
@@ -78,6 +92,7 @@ impl VisitorWithContext<&IfStatement, Result<Box<dyn Runnable>>, BuilderContext>
         let if_expr = node
             .bool_expr
             .accept_with_context(&AssignmentEvaluatorBuilder, context)?;
+        let if_expr = traced_branch(if_expr, node.token_list.first(), context);
         let true_block = node.true_statement.accept_with_context(&Self, context)?;
         let else_block = node
             .false_statement
@@ -101,7 +116,9 @@ impl VisitorWithContext<&WhileStatement, Result<Box<dyn Runnable>>, BuilderConte
         let eval_expr = node
             .eval_expr
             .accept_with_context(&AssignmentEvaluatorBuilder, context)?;
+        context.resolver.borrow_mut().enter_loop();
         let statement = node.statement.accept_with_context(&Self, context)?;
+        context.resolver.borrow_mut().exit_loop();
         Ok(Box::new(WhileStatementRunnable::new(eval_expr, statement)))
     }
 }
@@ -136,7 +153,9 @@ impl VisitorWithContext<&ForStatement, Result<Box<dyn Runnable>>, BuilderContext
             .as_ref()
             .map(|expr| expr.accept_with_context(&AssignmentEvaluatorBuilder, context))
             .transpose()?;
+        context.resolver.borrow_mut().enter_loop();
         let statement = node.statement.accept_with_context(&Self, context)?;
+        context.resolver.borrow_mut().exit_loop();
         context.resolver.borrow_mut().exit_scope()?;
         Ok(Box::new(ForStatementRunnable::new(
             var_declaration,
@@ -158,7 +177,7 @@ impl VisitorWithContext<&ReturnStatement, Result<Box<dyn Runnable>>, BuilderCont
         let function_depth = context.resolver.borrow().function_depth();
         if function_depth == 0 {
             return Err(ResolverError::ReturnOutsideFunction(
-                node.token_list.first().unwrap().line,
+                node.token_list.first().unwrap().span.clone(),
             )
             .into());
         }
@@ -168,7 +187,7 @@ impl VisitorWithContext<&ReturnStatement, Result<Box<dyn Runnable>>, BuilderCont
         if let Some(f) = is_method {
             if node.expr.is_some() && f == INIT_FUNCTION_NAME {
                 return Err(ResolverError::ReturnFromInitializer(
-                    node.token_list.first().unwrap().line,
+                    node.token_list.first().unwrap().span.clone(),
                 )
                 .into());
             }
@@ -181,3 +200,105 @@ impl VisitorWithContext<&ReturnStatement, Result<Box<dyn Runnable>>, BuilderCont
         Ok(Box::new(ReturnRunnable::new(expr)))
     }
 }
+
+impl VisitorWithContext<&BreakStatement, Result<Box<dyn Runnable>>, BuilderContext>
+    for RunnableBuilder
+{
+    fn visit_with_context(
+        &self,
+        node: &BreakStatement,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Runnable>> {
+        if !context.resolver.borrow().is_in_loop() {
+            return Err(ResolverError::OutsideLoop(
+                "break",
+                node.token_list.first().unwrap().span.clone(),
+            )
+            .into());
+        }
+        Ok(Box::new(BreakRunnable))
+    }
+}
+
+impl VisitorWithContext<&ContinueStatement, Result<Box<dyn Runnable>>, BuilderContext>
+    for RunnableBuilder
+{
+    fn visit_with_context(
+        &self,
+        node: &ContinueStatement,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Runnable>> {
+        if !context.resolver.borrow().is_in_loop() {
+            return Err(ResolverError::OutsideLoop(
+                "continue",
+                node.token_list.first().unwrap().span.clone(),
+            )
+            .into());
+        }
+        Ok(Box::new(ContinueRunnable))
+    }
+}
+
+impl VisitorWithContext<&ThrowStatement, Result<Box<dyn Runnable>>, BuilderContext>
+    for RunnableBuilder
+{
+    fn visit_with_context(
+        &self,
+        node: &ThrowStatement,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Runnable>> {
+        let keyword = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        let expr = node
+            .expr
+            .accept_with_context(&AssignmentEvaluatorBuilder, context)?;
+        Ok(Box::new(ThrowRunnable::new(expr, keyword.span.clone())))
+    }
+}
+
+impl VisitorWithContext<&TryStatement, Result<Box<dyn Runnable>>, BuilderContext>
+    for RunnableBuilder
+{
+    fn visit_with_context(
+        &self,
+        node: &TryStatement,
+        context: &BuilderContext,
+    ) -> Result<Box<dyn Runnable>> {
+        let keyword = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        if node.catch_clause.is_none() && node.finally_clause.is_none() {
+            return Err(ResolverError::TryWithoutHandler(keyword.span.clone()).into());
+        }
+
+        let body = node.body.accept_with_context(&Self, context)?;
+        let catch = match &node.catch_clause {
+            Some(catch_clause) => {
+                let identifier = catch_clause
+                    .token_list
+                    .get(2)
+                    .ok_or(RuntimeError::ASTInvalidStructure)?;
+                // The caught value is declared in a scope of its own, around the block
+                context.resolver.borrow_mut().enter_scope()?;
+                context
+                    .resolver
+                    .borrow_mut()
+                    .declare(&identifier.lexeme, &identifier.span)?;
+                context.resolver.borrow_mut().define(&identifier.lexeme)?;
+                let handler = catch_clause.body.accept_with_context(&Self, context)?;
+                context.resolver.borrow_mut().exit_scope()?;
+                Some((identifier.lexeme.clone(), handler))
+            }
+            None => None,
+        };
+        let finally = node
+            .finally_clause
+            .as_ref()
+            .map(|finally_clause| finally_clause.body.accept_with_context(&Self, context))
+            .transpose()?;
+        Ok(Box::new(TryStatementRunnable::new(body, catch, finally)))
+    }
+}
@@ -0,0 +1,207 @@
+use crate::common::Visitable;
+use crate::evaluation::evaluator::AssignmentEvaluatorBuilder;
+use crate::evaluation::run::runnable::{
+    get_native_functions, BuiltinFunction, Callable, HostFunction, ProgramRunnable, Runnable,
+};
+use crate::evaluation::run::runnable_builders::RunnableBuilder;
+use crate::evaluation::run::{
+    InputSource, OutputSink, RunHook, RunScopeRef, RunScopes, RunState, SandboxPolicy,
+};
+use crate::evaluation::runtime_value::CallableType;
+use crate::evaluation::{BuilderContext, RuntimeError, RuntimeValue};
+use crate::syntax_analysis::{Declaration, Expression, ProgramAst};
+use crate::tokenizer::Span;
+use std::rc::Rc;
+
+/// A long-lived interpreter state: one `BuilderContext` and one `RunState` shared
+/// by every piece of code built and run through it, so declarations persist
+/// between calls (as in the REPL).
+pub struct Session {
+    context: Rc<BuilderContext>,
+    state: RunState,
+    globals: RunScopeRef,
+}
+
+impl Session {
+    pub fn new() -> Result<Self, RuntimeError> {
+        let context = BuilderContext::new()?;
+        let mut scopes = RunScopes::new(None);
+        Self::initialize_context(&context, &mut scopes)?;
+        let state = RunState::new(context.functions_resolver.take(), scopes);
+        let globals = state.get_current_scope();
+        Ok(Self {
+            context: Rc::new(context),
+            state,
+            globals,
+        })
+    }
+
+    fn initialize_context(
+        context: &BuilderContext,
+        scopes: &mut RunScopes,
+    ) -> Result<(), RuntimeError> {
+        let functions = get_native_functions();
+        for (name, function) in functions.into_iter() {
+            let callable_value = Self::add_native(context, name, function)?;
+            Self::declare_global(context, name)?;
+            scopes.declare_variable(name.to_string(), Some(callable_value), None);
+        }
+
+        Ok(())
+    }
+
+    fn add_native(
+        context: &BuilderContext,
+        name: &str,
+        function: Box<dyn Callable>,
+    ) -> Result<RuntimeValue, RuntimeError> {
+        let pointer = context
+            .functions_resolver
+            .borrow_mut()
+            .add_function(function)?;
+        Ok(RuntimeValue::callable(
+            pointer,
+            name.to_string(),
+            None,
+            CallableType::Function,
+        ))
+    }
+
+    fn declare_global(context: &BuilderContext, name: &str) -> Result<(), RuntimeError> {
+        let mut resolver = context.resolver.borrow_mut();
+        resolver.declare(name, &Span::default())?;
+        resolver.define(name)?;
+        Ok(())
+    }
+
+    /// Defines a global variable visible to everything built afterwards.
+    pub fn define_global(&self, name: &str, value: RuntimeValue) -> Result<(), RuntimeError> {
+        Self::declare_global(&self.context, name)?;
+        self.globals
+            .borrow_mut()
+            .declare_variable(name.to_string(), Some(value), None);
+        Ok(())
+    }
+
+    /// Defines a global native function taking `arity` arguments.
+    pub fn register_native(
+        &self,
+        name: &str,
+        arity: usize,
+        function: HostFunction,
+    ) -> Result<(), RuntimeError> {
+        let native = Box::new(BuiltinFunction::host(arity, function));
+        let callable_value = self.build(|context| Self::add_native(context, name, native))?;
+        self.define_global(name, callable_value)
+    }
+
+    /// Runs `build` against the builder context. The functions resolver lives in
+    /// the run state between builds, so it is lent to the context for the
+    /// duration of the build and handed back afterwards.
+    fn build<T, F>(&self, build: F) -> Result<T, RuntimeError>
+    where
+        F: FnOnce(&BuilderContext) -> Result<T, RuntimeError>,
+    {
+        self.context
+            .functions_resolver
+            .replace(self.state.take_functions_resolver());
+        let result = build(&self.context);
+        self.state
+            .replace_functions_resolver(self.context.functions_resolver.take());
+        let points = self.context.trace_points.take();
+        if result.is_err() {
+            self.context.resolver.borrow_mut().reset();
+        } else if !points.is_empty() {
+            self.state.traced(&points);
+        }
+        result
+    }
+
+    pub fn build_program(
+        &self,
+        program_ast: &ProgramAst,
+    ) -> Result<Box<dyn Runnable>, RuntimeError> {
+        self.build(|context| program_ast.accept_with_context(&RunnableBuilder, context))
+    }
+
+    /// Builds each top-level declaration on its own, collecting every error
+    /// instead of stopping at the first. Nothing is kept for running.
+    pub fn build_errors(&self, program_ast: &ProgramAst) -> Vec<RuntimeError> {
+        program_ast
+            .statements
+            .iter()
+            .filter_map(|(_, declaration)| {
+                self.build(|context| declaration.accept_with_context(&RunnableBuilder, context))
+                    .err()
+            })
+            .collect()
+    }
+
+    /// Sets how many instance allocations trigger a collection; `None` disables
+    /// automatic collection (the `gc()` native still works).
+    pub fn set_gc_threshold(&self, threshold: Option<usize>) {
+        self.state.set_gc_threshold(threshold);
+    }
+
+    pub fn set_sandbox(&self, sandbox: SandboxPolicy) {
+        self.state.set_sandbox(sandbox);
+    }
+
+    pub fn set_script_arguments(&self, arguments: Vec<String>) {
+        self.state.set_script_arguments(arguments);
+    }
+
+    pub fn set_output(&self, output: Box<dyn OutputSink>) {
+        self.state.set_output(output);
+    }
+
+    pub fn set_input(&self, input: Box<dyn InputSource>) {
+        self.state.set_input(input);
+    }
+
+    /// Writes a line to the output the program prints to.
+    pub fn write_line(&self, line: &str) {
+        self.state.write_line(line);
+    }
+
+    /// Shows every statement built from now on to `hook` before it runs.
+    pub fn set_hook(&self, hook: Box<dyn RunHook>) {
+        self.context.traced.set(true);
+        self.state.set_hook(hook, self.context.clone());
+    }
+
+    pub fn run(&self, runnable: &dyn Runnable) -> Result<(), RuntimeError> {
+        runnable
+            .run(&self.state)
+            .map(|_| ())
+            .map_err(|error| self.recover(error))
+    }
+
+    /// Attaches the calls an uncaught error escaped from.
+    fn recover(&self, error: RuntimeError) -> RuntimeError {
+        match self.state.take_stack_trace(&error) {
+            Some(_) if matches!(error, RuntimeError::Stopped) => error,
+            Some(trace) => RuntimeError::WithStackTrace(Box::new(error), trace),
+            None => error,
+        }
+    }
+
+    pub fn run_declarations(&self, declarations: &[Declaration]) -> Result<(), RuntimeError> {
+        let program = self.build(|context| {
+            declarations
+                .iter()
+                .map(|declaration| declaration.accept_with_context(&RunnableBuilder, context))
+                .collect::<Result<Vec<Box<dyn Runnable>>, RuntimeError>>()
+        })?;
+        self.run(&ProgramRunnable::new(program))
+    }
+
+    pub fn evaluate(&self, expression: &Expression) -> Result<RuntimeValue, RuntimeError> {
+        let evaluator = self.build(|context| {
+            expression.accept_with_context(&AssignmentEvaluatorBuilder, context)
+        })?;
+        evaluator
+            .eval(&self.state)
+            .map_err(|error| self.recover(error))
+    }
+}
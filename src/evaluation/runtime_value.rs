@@ -1,14 +1,20 @@
 use std::{
+    cell::{Ref, RefCell, RefMut},
     collections::TryReserveError,
     fmt::Display,
     ops::{Add, Div, Mul, Neg, Not, Sub},
+    rc::Rc,
 };
 
 use thiserror::Error;
 
-use crate::evaluation::{
-    evaluator::ClassAccessorError,
-    run::{NativeFunctionError, RunScopeRef},
+use crate::{
+    evaluation::{
+        evaluator::ClassAccessorError,
+        run::{NativeFunctionError, RunScopeRef, StackTrace},
+        MapKey, Table,
+    },
+    tokenizer::Span,
 };
 
 #[derive(Clone, Debug)]
@@ -103,6 +109,15 @@ impl Callable {
         self.pointer
     }
 
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Native functions are the only plain functions without a closure scope.
+    pub fn is_native(&self) -> bool {
+        matches!(self.ty, CallableType::Function) && self.scope.is_none()
+    }
+
     pub fn get_this_pointer(&self) -> Option<ThisInstance> {
         match &self.ty {
             CallableType::Method(pointer) => Some(pointer.clone()),
@@ -133,6 +148,192 @@ impl PartialEq for Callable {
     }
 }
 
+/// A list value. Copies of it share the same elements, so a list changed
+/// through one variable is changed for every other one holding it.
+#[derive(Clone, Default)]
+pub struct List(Rc<RefCell<Vec<RuntimeValue>>>);
+
+impl List {
+    pub fn new(elements: Vec<RuntimeValue>) -> Self {
+        Self(Rc::new(RefCell::new(elements)))
+    }
+
+    pub fn elements(&self) -> Ref<'_, Vec<RuntimeValue>> {
+        self.0.borrow()
+    }
+
+    pub fn elements_mut(&self) -> RefMut<'_, Vec<RuntimeValue>> {
+        self.0.borrow_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The position `index` refers to, if it is a whole number within the
+    /// list. `span` is where the list is indexed.
+    pub fn position(&self, index: &RuntimeValue, span: &Span) -> Result<usize> {
+        let index = match index {
+            RuntimeValue::Number(number) if number.fract() == 0.0 => *number,
+            _ => return Err(RuntimeError::InvalidIndex(span.clone())),
+        };
+        let len = self.len();
+        if index < 0.0 || index >= len as f64 {
+            return Err(RuntimeError::IndexOutOfRange(index, len, span.clone()));
+        }
+        Ok(index as usize)
+    }
+
+    pub fn get(&self, index: &RuntimeValue, span: &Span) -> Result<RuntimeValue> {
+        let position = self.position(index, span)?;
+        Ok(self.0.borrow()[position].clone())
+    }
+
+    pub fn set(&self, index: &RuntimeValue, value: RuntimeValue, span: &Span) -> Result<()> {
+        let position = self.position(index, span)?;
+        self.0.borrow_mut()[position] = value;
+        Ok(())
+    }
+
+    /// Identifies the list, to trace each one once.
+    pub fn as_ptr(&self) -> *const RefCell<Vec<RuntimeValue>> {
+        Rc::as_ptr(&self.0)
+    }
+}
+
+impl std::fmt::Debug for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "list {{ len: {} }}", self.len())
+    }
+}
+
+impl Display for List {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        print_once(f, self.as_ptr().cast(), "[...]", |f| {
+            write!(f, "[")?;
+            for (i, element) in self.0.borrow().iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", element)?;
+            }
+            write!(f, "]")
+        })
+    }
+}
+
+thread_local! {
+    /// The lists and maps being printed, outermost first.
+    static PRINTING: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Prints the list or map at `pointer` with `print`, or `placeholder` if it
+/// contains itself and is already being printed further out.
+pub(crate) fn print_once(
+    f: &mut std::fmt::Formatter<'_>,
+    pointer: *const (),
+    placeholder: &str,
+    print: impl FnOnce(&mut std::fmt::Formatter<'_>) -> std::fmt::Result,
+) -> std::fmt::Result {
+    if PRINTING.with(|printing| printing.borrow().contains(&pointer)) {
+        return write!(f, "{}", placeholder);
+    }
+    PRINTING.with(|printing| printing.borrow_mut().push(pointer));
+    let result = print(f);
+    PRINTING.with(|printing| printing.borrow_mut().pop());
+    result
+}
+
+/// Lists are equal when they are the same list, as instances are.
+impl PartialEq for List {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// A map value. Like lists, copies of it share the same entries.
+#[derive(Clone, Default)]
+pub struct Map(Rc<RefCell<Table<RuntimeValue>>>);
+
+impl Map {
+    pub fn new(entries: Table<RuntimeValue>) -> Self {
+        Self(Rc::new(RefCell::new(entries)))
+    }
+
+    pub fn entries(&self) -> Ref<'_, Table<RuntimeValue>> {
+        self.0.borrow()
+    }
+
+    pub fn entries_mut(&self) -> RefMut<'_, Table<RuntimeValue>> {
+        self.0.borrow_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The key `key` is stored under. `span` is where the map is indexed.
+    pub fn key(key: &RuntimeValue, span: &Span) -> Result<MapKey> {
+        MapKey::from_runtime_value(key).ok_or_else(|| RuntimeError::UnhashableKey(span.clone()))
+    }
+
+    pub fn get(&self, key: &RuntimeValue, span: &Span) -> Result<RuntimeValue> {
+        let key = Self::key(key, span)?;
+        self.0
+            .borrow()
+            .get(&key)
+            .cloned()
+            .ok_or_else(|| RuntimeError::UndefinedKey(key.to_string(), span.clone()))
+    }
+
+    pub fn set(&self, key: &RuntimeValue, value: RuntimeValue, span: &Span) -> Result<()> {
+        let key = Self::key(key, span)?;
+        self.0.borrow_mut().insert(key, value);
+        Ok(())
+    }
+
+    /// Identifies the map, to trace each one once.
+    pub fn as_ptr(&self) -> *const RefCell<Table<RuntimeValue>> {
+        Rc::as_ptr(&self.0)
+    }
+}
+
+impl std::fmt::Debug for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "map {{ len: {} }}", self.len())
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        print_once(f, self.as_ptr().cast(), "{...}", |f| {
+            write!(f, "{{")?;
+            for (i, (key, value)) in self.0.borrow().iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}: {}", key, value)?;
+            }
+            write!(f, "}}")
+        })
+    }
+}
+
+/// Maps are equal when they are the same map.
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum RuntimeValue {
     Number(f64),
@@ -140,6 +341,8 @@ pub enum RuntimeValue {
     Boolean(bool),
     Callable(Callable),
     ClassInstance(usize, String),
+    List(List),
+    Map(Map),
     Nil,
 }
 
@@ -152,54 +355,127 @@ impl Display for RuntimeValue {
             RuntimeValue::Nil => write!(f, "nil"),
             RuntimeValue::Callable(c) => write!(f, "{}", c),
             RuntimeValue::ClassInstance(_, s) => write!(f, "{} instance", s),
+            RuntimeValue::List(list) => write!(f, "{}", list),
+            RuntimeValue::Map(map) => write!(f, "{}", map),
         }
     }
 }
 
 #[derive(Debug, Error)]
 pub enum RuntimeError {
-    #[error("Operand must be a {0}")]
-    UnaryOperandError(String),
-    #[error("Operand must be {0}")]
-    BinaryOperandError(String),
+    #[error("Operand must be a number.\n[line {}]", .0.line)]
+    UnaryOperandError(Span),
+    #[error("Operands must be {0}.\n[line {}]", .1.line)]
+    BinaryOperandError(String, Span),
     #[error("AST invalid structure")]
     ASTInvalidStructure,
-    #[error("Undefined variable '{0}'.\nLine: {1}")]
-    UndefinedVariable(String, usize),
+    #[error("Undefined variable '{0}'.\nLine: {}", .1.line)]
+    UndefinedVariable(String, Span),
     #[error("{0}")]
     ResolverError(#[from] super::resolver::ResolverError),
     #[error("{0}")]
+    ImportError(#[from] super::modules::ImportError),
+    #[error("{0}")]
     FunctionsResolverError(#[from] super::functions_resolver::FunctionsResolverError),
     #[error("{0}")]
     FunctionEvaluationError(#[from] super::evaluator::FunctionEvaluationError),
-    #[error("Arity mismatch")]
-    ArityMismatch,
-    #[error("{0}")]
-    NativeFunctionError(#[from] NativeFunctionError),
+    #[error("Expected {0} arguments but got {1}.\n[line {}]", .2.line)]
+    ArityMismatch(usize, usize, Span),
+    #[error("{0}\n[line {}]", .1.line)]
+    NativeFunctionError(NativeFunctionError, Span),
     #[error("Function not found")]
     FunctionNotFound,
-    #[error("Out of scope")]
-    OutOfScope,
     #[error("Not enough space to allocate new scope")]
     NotEnoughSpace(#[from] TryReserveError),
     #[error("Not enough space to allocate new instance")]
     NotEnoughSpaceToAllocateNewInstance,
     #[error("Instance not found")]
     InstanceNotFound(usize),
-    #[error("Undefined property '{0}'\n[line {1}]")]
-    UndefinedProperty(String, usize),
+    #[error("Undefined property '{0}'\n[line {}]", .1.line)]
+    UndefinedProperty(String, Span),
     #[error("{0}")]
     ClassAccessorError(#[from] ClassAccessorError),
     #[error("This not in scope")]
     ThisNotInScope,
     #[error("Super class not found")]
     SuperClassNotFound,
-    #[error("Superclass must be a class.\n[line {0}]")]
-    SuperClassMustBeAClass(usize),
+    #[error("Superclass must be a class.\n[line {}]", .0.line)]
+    SuperClassMustBeAClass(Span),
+    #[error("Only lists and maps can be indexed.\n[line {}]", .0.line)]
+    NotIndexable(Span),
+    #[error("Only numbers, strings, booleans and nil can be map keys.\n[line {}]", .0.line)]
+    UnhashableKey(Span),
+    #[error("Undefined key '{0}'.\n[line {}]", .1.line)]
+    UndefinedKey(String, Span),
+    #[error("Index must be an integer.\n[line {}]", .0.line)]
+    InvalidIndex(Span),
+    #[error("Index {0} is out of range for length {1}.\n[line {}]", .2.line)]
+    IndexOutOfRange(f64, usize, Span),
+    #[error("{0}")]
+    CompileError(#[from] super::vm::CompileError),
+    #[error("Stack overflow.")]
+    StackOverflow,
+    #[error("{0} is not supported by the bytecode VM.")]
+    Unsupported(String),
+    #[error("{0}\n{1}")]
+    WithStackTrace(Box<RuntimeError>, StackTrace),
+    #[error("Program stopped by the debugger.")]
+    Stopped,
+    #[error("Uncaught exception: {0}\n[line {}]", .1.line)]
+    Thrown(RuntimeValue, Span),
 }
 
 pub type Result<T> = std::result::Result<T, RuntimeError>;
 
+impl RuntimeError {
+    /// The source the error points at, when it is known.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            RuntimeError::UndefinedVariable(_, span)
+            | RuntimeError::UndefinedProperty(_, span)
+            | RuntimeError::SuperClassMustBeAClass(span)
+            | RuntimeError::NotIndexable(span)
+            | RuntimeError::UnhashableKey(span)
+            | RuntimeError::UndefinedKey(_, span)
+            | RuntimeError::InvalidIndex(span)
+            | RuntimeError::IndexOutOfRange(_, _, span)
+            | RuntimeError::UnaryOperandError(span)
+            | RuntimeError::BinaryOperandError(_, span)
+            | RuntimeError::ArityMismatch(_, _, span)
+            | RuntimeError::NativeFunctionError(_, span)
+            | RuntimeError::Thrown(_, span) => Some(span),
+            RuntimeError::ResolverError(error) => error.span(),
+            RuntimeError::ImportError(error) => Some(error.span()),
+            RuntimeError::FunctionEvaluationError(error) => Some(error.span()),
+            RuntimeError::ClassAccessorError(error) => Some(error.span()),
+            RuntimeError::WithStackTrace(error, _) => error.span(),
+            _ => None,
+        }
+    }
+
+    /// Points an error raised by the value operators or a native function,
+    /// which don't know where they are used, at the operator or call that was
+    /// evaluated.
+    pub fn at(self, operator: &Span) -> Self {
+        match self {
+            RuntimeError::UnaryOperandError(_) => RuntimeError::UnaryOperandError(operator.clone()),
+            RuntimeError::BinaryOperandError(expected, _) => {
+                RuntimeError::BinaryOperandError(expected, operator.clone())
+            }
+            RuntimeError::NativeFunctionError(error, _) => {
+                RuntimeError::NativeFunctionError(error, operator.clone())
+            }
+            error => error,
+        }
+    }
+}
+
+impl From<NativeFunctionError> for RuntimeError {
+    fn from(error: NativeFunctionError) -> Self {
+        RuntimeError::NativeFunctionError(error, Span::default())
+    }
+}
+
 impl Not for RuntimeValue {
     type Output = Result<RuntimeValue>;
     fn not(self) -> Self::Output {
@@ -212,7 +488,7 @@ impl Neg for RuntimeValue {
     fn neg(self) -> Self::Output {
         match self {
             RuntimeValue::Number(f) => Ok(RuntimeValue::Number(-f)),
-            _ => Err(RuntimeError::UnaryOperandError("number".to_string())),
+            _ => Err(RuntimeError::UnaryOperandError(Span::default())),
         }
     }
 }
@@ -224,7 +500,10 @@ impl Mul for RuntimeValue {
             (RuntimeValue::Number(f1), RuntimeValue::Number(f2)) => {
                 Ok(RuntimeValue::Number(f1 * f2))
             }
-            _ => Err(RuntimeError::BinaryOperandError("numbers".to_string())),
+            _ => Err(RuntimeError::BinaryOperandError(
+                "numbers".to_string(),
+                Span::default(),
+            )),
         }
     }
 }
@@ -236,7 +515,10 @@ impl Div for RuntimeValue {
             (RuntimeValue::Number(f1), RuntimeValue::Number(f2)) => {
                 Ok(RuntimeValue::Number(f1 / f2))
             }
-            _ => Err(RuntimeError::BinaryOperandError("numbers".to_string())),
+            _ => Err(RuntimeError::BinaryOperandError(
+                "numbers".to_string(),
+                Span::default(),
+            )),
         }
     }
 }
@@ -252,7 +534,8 @@ impl Add for RuntimeValue {
                 Ok(RuntimeValue::String(s1 + s2.as_str()))
             }
             _ => Err(RuntimeError::BinaryOperandError(
-                "numbers or strings".to_string(),
+                "two numbers or two strings".to_string(),
+                Span::default(),
             )),
         }
     }
@@ -266,7 +549,8 @@ impl Sub for RuntimeValue {
                 Ok(RuntimeValue::Number(f1 - f2))
             }
             _ => Err(RuntimeError::BinaryOperandError(
-                "numbers or strings".to_string(),
+                "numbers".to_string(),
+                Span::default(),
             )),
         }
     }
@@ -296,6 +580,21 @@ impl RuntimeValue {
         }
     }
 
+    /// The name of the kind of value this is, as the `type` native returns it.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            RuntimeValue::Nil => "nil",
+            RuntimeValue::Boolean(_) => "boolean",
+            RuntimeValue::Number(_) => "number",
+            RuntimeValue::String(_) => "string",
+            RuntimeValue::Callable(callable) if callable.is_class_constructor() => "class",
+            RuntimeValue::Callable(_) => "function",
+            RuntimeValue::ClassInstance(_, _) => "instance",
+            RuntimeValue::List(_) => "list",
+            RuntimeValue::Map(_) => "map",
+        }
+    }
+
     pub fn to_bool(&self) -> Result<bool> {
         match self {
             RuntimeValue::Boolean(b) => Ok(*b),
@@ -309,7 +608,10 @@ impl RuntimeValue {
             (RuntimeValue::Number(f1), RuntimeValue::Number(f2)) => {
                 Ok(RuntimeValue::Boolean(f1 < f2))
             }
-            _ => Err(RuntimeError::BinaryOperandError("numbers".to_string())),
+            _ => Err(RuntimeError::BinaryOperandError(
+                "numbers".to_string(),
+                Span::default(),
+            )),
         }
     }
 
@@ -318,7 +620,10 @@ impl RuntimeValue {
             (RuntimeValue::Number(f1), RuntimeValue::Number(f2)) => {
                 Ok(RuntimeValue::Boolean(f1 <= f2))
             }
-            _ => Err(RuntimeError::BinaryOperandError("numbers".to_string())),
+            _ => Err(RuntimeError::BinaryOperandError(
+                "numbers".to_string(),
+                Span::default(),
+            )),
         }
     }
 
@@ -327,7 +632,10 @@ impl RuntimeValue {
             (RuntimeValue::Number(f1), RuntimeValue::Number(f2)) => {
                 Ok(RuntimeValue::Boolean(f1 > f2))
             }
-            _ => Err(RuntimeError::BinaryOperandError("numbers".to_string())),
+            _ => Err(RuntimeError::BinaryOperandError(
+                "numbers".to_string(),
+                Span::default(),
+            )),
         }
     }
 
@@ -336,7 +644,10 @@ impl RuntimeValue {
             (RuntimeValue::Number(f1), RuntimeValue::Number(f2)) => {
                 Ok(RuntimeValue::Boolean(f1 >= f2))
             }
-            _ => Err(RuntimeError::BinaryOperandError("numbers".to_string())),
+            _ => Err(RuntimeError::BinaryOperandError(
+                "numbers".to_string(),
+                Span::default(),
+            )),
         }
     }
 
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::tokenizer::Span;
+
+/// What a name was declared as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+    GlobalVariable,
+    LocalVariable,
+    Parameter,
+    Function,
+    Class,
+    Method,
+    Native,
+}
+
+impl Display for SymbolKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            SymbolKind::GlobalVariable => "global variable",
+            SymbolKind::LocalVariable => "local variable",
+            SymbolKind::Parameter => "parameter",
+            SymbolKind::Function => "function",
+            SymbolKind::Class => "class",
+            SymbolKind::Method => "method",
+            SymbolKind::Native => "native function",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Definition {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The name where it is declared; natives have no declaration.
+    pub span: Option<Span>,
+    /// The declaration as written, such as `fun add(a, b)` or `class B < A`.
+    pub detail: String,
+    /// The bytes of the source the name can be used in.
+    pub scope: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reference {
+    pub span: Span,
+    pub definition: usize,
+}
+
+/// Every declaration in a program and every use of a variable, function or
+/// class name resolved to its declaration, as the `Analyzer` finds them.
+/// Properties and methods are dynamic, so uses of those aren't resolved.
+#[derive(Debug, Default)]
+pub struct Symbols {
+    pub definitions: Vec<Definition>,
+    pub references: Vec<Reference>,
+}
+
+impl Symbols {
+    /// The definition of the name at `offset`, whether it is declared or used
+    /// there.
+    pub fn find(&self, offset: usize) -> Option<usize> {
+        let contains = |span: &Span| span.start <= offset && offset <= span.end;
+        self.references
+            .iter()
+            .find(|reference| contains(&reference.span))
+            .map(|reference| reference.definition)
+            .or_else(|| {
+                self.definitions
+                    .iter()
+                    .position(|definition| definition.span.as_ref().is_some_and(contains))
+            })
+    }
+
+    /// The uses of a definition, in source order.
+    pub fn references(&self, definition: usize) -> impl Iterator<Item = &Span> {
+        self.references
+            .iter()
+            .filter(move |reference| reference.definition == definition)
+            .map(|reference| &reference.span)
+    }
+
+    /// The variables, functions and classes that can be named at `offset`.
+    pub fn visible_at(&self, offset: usize) -> impl Iterator<Item = &Definition> {
+        self.definitions.iter().filter(move |definition| {
+            definition.kind != SymbolKind::Method && definition.scope.contains(&offset)
+        })
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions
+            .iter()
+            .filter(|definition| definition.kind == SymbolKind::Method)
+    }
+}
@@ -0,0 +1,151 @@
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use crate::evaluation::RuntimeValue;
+
+/// A value that can key a map. Only values compared by value are allowed, so
+/// a key can't change while it is stored. Numbers are keyed by their bits,
+/// with `-0` folded into `0` and every NaN into one key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapKey {
+    Nil,
+    Boolean(bool),
+    Number(u64),
+    String(Rc<str>),
+}
+
+impl MapKey {
+    pub fn number(number: f64) -> Self {
+        let number = if number == 0.0 {
+            0.0
+        } else if number.is_nan() {
+            f64::NAN
+        } else {
+            number
+        };
+        MapKey::Number(number.to_bits())
+    }
+
+    /// The key `value` is used as, if it can be one.
+    pub fn from_runtime_value(value: &RuntimeValue) -> Option<Self> {
+        match value {
+            RuntimeValue::Nil => Some(MapKey::Nil),
+            RuntimeValue::Boolean(b) => Some(MapKey::Boolean(*b)),
+            RuntimeValue::Number(n) => Some(MapKey::number(*n)),
+            RuntimeValue::String(s) => Some(MapKey::String(Rc::from(s.as_str()))),
+            _ => None,
+        }
+    }
+
+    pub fn to_runtime_value(&self) -> RuntimeValue {
+        match self {
+            MapKey::Nil => RuntimeValue::Nil,
+            MapKey::Boolean(b) => RuntimeValue::Boolean(*b),
+            MapKey::Number(bits) => RuntimeValue::Number(f64::from_bits(*bits)),
+            MapKey::String(s) => RuntimeValue::String(s.to_string()),
+        }
+    }
+}
+
+impl Display for MapKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_runtime_value())
+    }
+}
+
+/// The entries of a map, kept in the order their keys were first inserted so
+/// that printing a map and listing its keys is deterministic.
+#[derive(Debug, Clone)]
+pub struct Table<V> {
+    entries: Vec<(MapKey, V)>,
+    positions: HashMap<MapKey, usize>,
+}
+
+impl<V> Default for Table<V> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            positions: HashMap::new(),
+        }
+    }
+}
+
+impl<V> Table<V> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, key: &MapKey) -> Option<&V> {
+        self.positions
+            .get(key)
+            .map(|&position| &self.entries[position].1)
+    }
+
+    pub fn contains(&self, key: &MapKey) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    /// Sets the value of `key`. A key already present keeps its place.
+    pub fn insert(&mut self, key: MapKey, value: V) {
+        match self.positions.get(&key) {
+            Some(&position) => self.entries[position].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &MapKey) -> Option<V> {
+        let position = self.positions.remove(key)?;
+        let (_, value) = self.entries.remove(position);
+        for later in self.positions.values_mut() {
+            if *later > position {
+                *later -= 1;
+            }
+        }
+        Some(value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&MapKey, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &MapKey> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_keeps_insertion_order_across_removals() {
+        let mut table = Table::default();
+        table.insert(MapKey::String(Rc::from("a")), 1);
+        table.insert(MapKey::number(2.0), 2);
+        table.insert(MapKey::Nil, 3);
+        table.insert(MapKey::number(-0.0), 4);
+
+        assert_eq!(table.remove(&MapKey::String(Rc::from("a"))), Some(1));
+        assert_eq!(table.get(&MapKey::number(0.0)), Some(&4));
+        table.insert(MapKey::Nil, 5);
+
+        let entries: Vec<_> = table
+            .iter()
+            .map(|(key, value)| (key.clone(), *value))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (MapKey::number(2.0), 2),
+                (MapKey::Nil, 5),
+                (MapKey::number(0.0), 4)
+            ]
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use std::rc::Rc;
+
+use thiserror::Error;
+
+use crate::tokenizer::Span;
+
+use super::value::FunctionProto;
+
+#[derive(Debug, Error)]
+pub enum CompileError {
+    #[error("Too many constants in one chunk.")]
+    TooManyConstants,
+    #[error("Too many local variables in function.")]
+    TooManyLocals,
+    #[error("Too many closure variables in function.")]
+    TooManyUpvalues,
+    #[error("Too many arguments in call.")]
+    TooManyArguments,
+    #[error("Too much code to jump over.")]
+    JumpTooLarge,
+    #[error("Too many elements in list literal.")]
+    TooManyElements,
+}
+
+/// One byte opcodes. Operands follow the opcode as little endian `u16`s, except
+/// `Call` whose argument count is a single byte and `Closure` which is followed
+/// by one `(is_local, index)` pair per captured variable.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpCode {
+    Constant,
+    Nil,
+    True,
+    False,
+    Pop,
+    GetLocal,
+    SetLocal,
+    GetUpvalue,
+    SetUpvalue,
+    DefineGlobal,
+    GetGlobal,
+    SetGlobal,
+    GetProperty,
+    SetProperty,
+    GetSuper,
+    CheckInstance,
+    CheckCallable,
+    Equal,
+    NotEqual,
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Not,
+    Negate,
+    Print,
+    Jump,
+    JumpIfFalse,
+    Loop,
+    Call,
+    Closure,
+    CloseUpvalue,
+    Return,
+    Class,
+    Inherit,
+    InheritUnknown,
+    Method,
+    BuildList,
+    GetIndex,
+    SetIndex,
+    /// Pushes an empty map for the entries of a literal to be added to.
+    NewMap,
+    /// Pops a key and a value and adds them to the map below them.
+    InsertEntry,
+}
+
+impl OpCode {
+    const ALL: [OpCode; 46] = [
+        OpCode::Constant,
+        OpCode::Nil,
+        OpCode::True,
+        OpCode::False,
+        OpCode::Pop,
+        OpCode::GetLocal,
+        OpCode::SetLocal,
+        OpCode::GetUpvalue,
+        OpCode::SetUpvalue,
+        OpCode::DefineGlobal,
+        OpCode::GetGlobal,
+        OpCode::SetGlobal,
+        OpCode::GetProperty,
+        OpCode::SetProperty,
+        OpCode::GetSuper,
+        OpCode::CheckInstance,
+        OpCode::CheckCallable,
+        OpCode::Equal,
+        OpCode::NotEqual,
+        OpCode::Greater,
+        OpCode::GreaterEqual,
+        OpCode::Less,
+        OpCode::LessEqual,
+        OpCode::Add,
+        OpCode::Subtract,
+        OpCode::Multiply,
+        OpCode::Divide,
+        OpCode::Not,
+        OpCode::Negate,
+        OpCode::Print,
+        OpCode::Jump,
+        OpCode::JumpIfFalse,
+        OpCode::Loop,
+        OpCode::Call,
+        OpCode::Closure,
+        OpCode::CloseUpvalue,
+        OpCode::Return,
+        OpCode::Class,
+        OpCode::Inherit,
+        OpCode::InheritUnknown,
+        OpCode::Method,
+        OpCode::BuildList,
+        OpCode::GetIndex,
+        OpCode::SetIndex,
+        OpCode::NewMap,
+        OpCode::InsertEntry,
+    ];
+
+    #[inline]
+    pub fn from_byte(byte: u8) -> Option<OpCode> {
+        Self::ALL.get(byte as usize).copied()
+    }
+}
+
+#[derive(Debug)]
+pub enum Constant {
+    Number(f64),
+    String(Rc<str>),
+    Function(Rc<FunctionProto>),
+}
+
+/// A compiled function body: the code, the source span of every byte of it
+/// and the constants its instructions refer to. Consecutive bytes compiled
+/// from the same span share one entry of `spans`.
+#[derive(Debug, Default)]
+pub struct Chunk {
+    code: Vec<u8>,
+    locations: Vec<usize>,
+    spans: Vec<Span>,
+    constants: Vec<Constant>,
+}
+
+impl Chunk {
+    pub fn write_op(&mut self, op: OpCode, span: &Span) {
+        self.write_byte(op as u8, span);
+    }
+
+    pub fn write_byte(&mut self, byte: u8, span: &Span) {
+        if self.spans.last() != Some(span) {
+            self.spans.push(span.clone());
+        }
+        self.code.push(byte);
+        self.locations.push(self.spans.len() - 1);
+    }
+
+    pub fn write_u16(&mut self, value: u16, span: &Span) {
+        for byte in value.to_le_bytes() {
+            self.write_byte(byte, span);
+        }
+    }
+
+    pub fn patch_u16(&mut self, offset: usize, value: u16) {
+        self.code[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn add_constant(&mut self, constant: Constant) -> Result<u16, CompileError> {
+        let index =
+            u16::try_from(self.constants.len()).map_err(|_| CompileError::TooManyConstants)?;
+        self.constants.push(constant);
+        Ok(index)
+    }
+
+    /// Reuses the pool entry of an identical string so names looked up in a
+    /// loop do not grow the pool.
+    pub fn add_string(&mut self, value: &str) -> Result<u16, CompileError> {
+        let existing = self.constants.iter().position(|constant| match constant {
+            Constant::String(string) => string.as_ref() == value,
+            _ => false,
+        });
+        match existing {
+            Some(index) => Ok(index as u16),
+            None => self.add_constant(Constant::String(Rc::from(value))),
+        }
+    }
+
+    /// Offset the next written byte will have.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.code.len()
+    }
+
+    #[inline]
+    pub fn byte(&self, offset: usize) -> u8 {
+        self.code[offset]
+    }
+
+    #[inline]
+    pub fn read_u16(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.code[offset], self.code[offset + 1]])
+    }
+
+    pub fn span(&self, offset: usize) -> Span {
+        self.locations
+            .get(offset)
+            .map(|&location| self.spans[location].clone())
+            .unwrap_or_default()
+    }
+
+    #[inline]
+    pub fn constant(&self, index: u16) -> &Constant {
+        &self.constants[index as usize]
+    }
+}
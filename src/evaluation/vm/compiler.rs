@@ -0,0 +1,1171 @@
+use std::{cell::RefCell, collections::HashSet, rc::Rc};
+
+use crate::common::{Visitable, VisitorWithContext};
+use crate::evaluation::{
+    evaluator::LAMBDA_NAME, resolver::Resolver, runtime_value::Result, RuntimeError,
+};
+use crate::syntax_analysis::{
+    AccessorOrArgumentsType, ArgumentsList, Assignment, Block, BreakStatement, Call,
+    ClassDeclaration, Comparison, ComparisonType, ContinueStatement, Declaration, DeclarationType,
+    Equality, EqualityType, ExprStatement, Factor, FactorType, ForStatement, ForStatementType,
+    Function, FunctionDeclaration, IfStatement, Index, LambdaBody, LogicalAnd, LogicalOr,
+    Parameters, PrimaryExpression, PrimaryExpressionType, PrintStatement, ProgramAst,
+    ReturnStatement, Statement, StatementType, Term, TermType, UnaryExpression,
+    UnaryExpressionSelf, UnaryExpressionSelfType, UnaryExpressionType, VarDeclaration,
+    WhileStatement,
+};
+use crate::tokenizer::{Span, Token, TokenValue};
+
+use super::chunk::{Chunk, CompileError, Constant, OpCode};
+use super::value::FunctionProto;
+
+const THIS_SLOT_NAME: &str = "this";
+const SUPER_SLOT_NAME: &str = "super";
+
+/// Compiles a resolved program into bytecode for the `Vm`.
+pub struct Compiler;
+
+struct Local {
+    name: String,
+    scope: usize,
+    captured: bool,
+}
+
+struct FunctionState {
+    name: String,
+    class: Option<String>,
+    arity: usize,
+    chunk: Chunk,
+    locals: Vec<Local>,
+    upvalues: Vec<CapturedVariable>,
+}
+
+impl FunctionState {
+    fn new(name: String, class: Option<String>, scope: usize) -> Self {
+        let reserved: &[&str] = if class.is_some() {
+            &[THIS_SLOT_NAME, SUPER_SLOT_NAME]
+        } else {
+            &[""]
+        };
+        Self {
+            name,
+            class,
+            arity: 0,
+            chunk: Chunk::default(),
+            locals: reserved
+                .iter()
+                .map(|name| Local {
+                    name: name.to_string(),
+                    scope,
+                    captured: false,
+                })
+                .collect(),
+            upvalues: Vec::new(),
+        }
+    }
+}
+
+/// Where a closure finds a captured variable: a local slot of the enclosing
+/// function when `true`, otherwise one of the enclosing function's upvalues.
+type CapturedVariable = (bool, u16);
+
+/// A loop being compiled: where `continue` jumps back to, the scope its body
+/// runs in and the `break` jumps to patch once its end is known.
+struct Loop {
+    continue_target: usize,
+    scope: usize,
+    breaks: Vec<usize>,
+}
+
+enum Variable {
+    Local(u16),
+    Upvalue(u16),
+    Global(u16),
+}
+
+/// Compiler state. The resolver sees the same scopes, in the same order, as it
+/// does when the tree-walker builds the program, so every identifier resolves
+/// to the same depth; `scopes` records which function owns each of them so a
+/// depth can be turned into a local slot or an upvalue.
+pub struct CompilerContext {
+    resolver: RefCell<Resolver>,
+    functions: RefCell<Vec<FunctionState>>,
+    scopes: RefCell<Vec<usize>>,
+    class_names: RefCell<HashSet<String>>,
+    loops: RefCell<Vec<Loop>>,
+    span: RefCell<Span>,
+}
+
+impl Compiler {
+    pub fn compile(program: &ProgramAst) -> Result<Rc<FunctionProto>> {
+        let context = CompilerContext {
+            resolver: RefCell::new(Resolver::new()?),
+            functions: RefCell::new(vec![FunctionState::new("script".to_string(), None, 0)]),
+            scopes: RefCell::new(vec![0]),
+            class_names: RefCell::new(HashSet::new()),
+            loops: RefCell::new(Vec::new()),
+            span: RefCell::new(Span::default()),
+        };
+        program.accept_with_context(&Self, &context)?;
+        context.emit(OpCode::Nil);
+        context.emit(OpCode::Return);
+        let (proto, _) = context.pop_function()?;
+        Ok(proto)
+    }
+
+    fn compile_function(
+        node: &Function,
+        context: &CompilerContext,
+        class: Option<&str>,
+        super_available: bool,
+    ) -> Result<()> {
+        let is_method = class.is_some();
+        let function_ident = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        let name = function_ident.lexeme.clone();
+        context.set_span(&function_ident.span);
+
+        if is_method {
+            let mut resolver = context.resolver.borrow_mut();
+            resolver.declare(&name, &function_ident.span)?;
+            resolver.define(&name)?;
+        } else {
+            context.declare_variable(&name, &function_ident.span)?;
+            context.mark_defined(&name)?;
+        }
+
+        Self::compile_body(
+            &name,
+            class,
+            &node.parameters,
+            |context| node.block.accept_with_context(&Self, context),
+            context,
+            super_available,
+        )?;
+
+        if !is_method {
+            context.define_variable(&name)?;
+        }
+        Ok(())
+    }
+
+    /// Compiles a function's parameters and body into a new prototype and
+    /// emits the closure creating it.
+    fn compile_body(
+        name: &str,
+        class: Option<&str>,
+        parameters: &Parameters,
+        compile_body: impl FnOnce(&CompilerContext) -> Result<()>,
+        context: &CompilerContext,
+        super_available: bool,
+    ) -> Result<()> {
+        let is_method = class.is_some();
+        context.resolver.borrow_mut().enter_scope()?;
+        if is_method {
+            context
+                .resolver
+                .borrow_mut()
+                .enter_method(name.to_string(), super_available);
+        } else {
+            context
+                .resolver
+                .borrow_mut()
+                .enter_function(name.to_string(), super_available);
+        }
+        context.push_function(name.to_string(), class.map(str::to_string));
+
+        let parameters = &parameters.parameters;
+        context.functions.borrow_mut().last_mut().unwrap().arity = parameters.len();
+        for parameter in parameters {
+            context.declare_variable(&parameter.token.lexeme, &parameter.token.span)?;
+            context.mark_defined(&parameter.token.lexeme)?;
+        }
+
+        compile_body(context)?;
+        context.emit(OpCode::Nil);
+        context.emit(OpCode::Return);
+
+        context.resolver.borrow_mut().exit_scope()?;
+        context.resolver.borrow_mut().exit_function_or_method();
+        let (proto, upvalues) = context.pop_function()?;
+
+        let constant = context.add_constant(Constant::Function(proto))?;
+        context.emit_with_operand(OpCode::Closure, constant);
+        for (is_local, index) in upvalues {
+            context.emit_byte(is_local as u8);
+            context.emit_u16(index);
+        }
+        Ok(())
+    }
+
+    fn bracket_span(index: &Index) -> Result<&Span> {
+        index
+            .bracket()
+            .map(|token| &token.span)
+            .ok_or(RuntimeError::ASTInvalidStructure)
+    }
+
+    fn compile_arguments(node: &ArgumentsList, context: &CompilerContext) -> Result<u8> {
+        let Some(arguments) = node.maybe_arguments.as_ref() else {
+            return Ok(0);
+        };
+        arguments.first.accept_with_context(&Self, context)?;
+        for (_, argument) in &arguments.rest {
+            argument.accept_with_context(&Self, context)?;
+        }
+        u8::try_from(arguments.rest.len() + 1).map_err(|_| CompileError::TooManyArguments.into())
+    }
+}
+
+impl CompilerContext {
+    fn push_function(&self, name: String, class: Option<String>) {
+        let mut functions = self.functions.borrow_mut();
+        let mut scopes = self.scopes.borrow_mut();
+        scopes.push(functions.len());
+        functions.push(FunctionState::new(name, class, scopes.len() - 1));
+    }
+
+    fn pop_function(&self) -> Result<(Rc<FunctionProto>, Vec<CapturedVariable>)> {
+        self.scopes.borrow_mut().pop();
+        let function = self
+            .functions
+            .borrow_mut()
+            .pop()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        let proto = FunctionProto {
+            name: function.name,
+            class: function.class,
+            arity: function.arity,
+            upvalue_count: function.upvalues.len(),
+            chunk: function.chunk,
+        };
+        Ok((Rc::new(proto), function.upvalues))
+    }
+
+    fn with_chunk<T>(&self, f: impl FnOnce(&mut Chunk) -> T) -> T {
+        f(&mut self.functions.borrow_mut().last_mut().unwrap().chunk)
+    }
+
+    /// Sets the span of the code emitted next.
+    fn set_span(&self, span: &Span) {
+        self.span.replace(span.clone());
+    }
+
+    fn emit(&self, op: OpCode) {
+        let span = self.span.borrow();
+        self.with_chunk(|chunk| chunk.write_op(op, &span));
+    }
+
+    fn emit_byte(&self, byte: u8) {
+        let span = self.span.borrow();
+        self.with_chunk(|chunk| chunk.write_byte(byte, &span));
+    }
+
+    fn emit_u16(&self, operand: u16) {
+        let span = self.span.borrow();
+        self.with_chunk(|chunk| chunk.write_u16(operand, &span));
+    }
+
+    fn emit_with_operand(&self, op: OpCode, operand: u16) {
+        self.emit(op);
+        self.emit_u16(operand);
+    }
+
+    fn add_constant(&self, constant: Constant) -> Result<u16> {
+        Ok(self.with_chunk(|chunk| chunk.add_constant(constant))?)
+    }
+
+    fn name_constant(&self, name: &str) -> Result<u16> {
+        Ok(self.with_chunk(|chunk| chunk.add_string(name))?)
+    }
+
+    /// Emits a forward jump and returns the offset of its operand for `patch_jump`.
+    fn emit_jump(&self, op: OpCode) -> usize {
+        self.emit_with_operand(op, u16::MAX);
+        self.with_chunk(|chunk| chunk.position() - 2)
+    }
+
+    fn patch_jump(&self, operand_offset: usize) -> Result<()> {
+        self.with_chunk(|chunk| {
+            let distance = u16::try_from(chunk.position() - operand_offset - 2)
+                .map_err(|_| CompileError::JumpTooLarge)?;
+            chunk.patch_u16(operand_offset, distance);
+            Ok(())
+        })
+    }
+
+    fn emit_loop(&self, loop_start: usize) -> Result<()> {
+        self.emit(OpCode::Loop);
+        let distance = self.with_chunk(|chunk| chunk.position() + 2 - loop_start);
+        self.emit_u16(u16::try_from(distance).map_err(|_| CompileError::JumpTooLarge)?);
+        Ok(())
+    }
+
+    fn position(&self) -> usize {
+        self.with_chunk(|chunk| chunk.position())
+    }
+
+    /// Starts a loop whose body runs in the current scope's children.
+    fn enter_loop(&self, continue_target: usize) {
+        self.loops.borrow_mut().push(Loop {
+            continue_target,
+            scope: self.scopes.borrow().len() - 1,
+            breaks: Vec::new(),
+        });
+    }
+
+    /// Ends the innermost loop, sending its `break`s to the code emitted next.
+    fn exit_loop(&self) -> Result<()> {
+        let current = self
+            .loops
+            .borrow_mut()
+            .pop()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        for jump in current.breaks {
+            self.patch_jump(jump)?;
+        }
+        Ok(())
+    }
+
+    fn emit_break(&self) -> Result<()> {
+        let scope = self.innermost_loop(|current| current.scope)?;
+        self.discard_locals(scope);
+        let jump = self.emit_jump(OpCode::Jump);
+        if let Some(current) = self.loops.borrow_mut().last_mut() {
+            current.breaks.push(jump);
+        }
+        Ok(())
+    }
+
+    fn emit_continue(&self) -> Result<()> {
+        let (scope, target) =
+            self.innermost_loop(|current| (current.scope, current.continue_target))?;
+        self.discard_locals(scope);
+        self.emit_loop(target)
+    }
+
+    fn innermost_loop<T>(&self, f: impl FnOnce(&Loop) -> T) -> Result<T> {
+        self.loops
+            .borrow()
+            .last()
+            .map(f)
+            .ok_or(RuntimeError::ASTInvalidStructure)
+    }
+
+    /// Pops the locals of the scopes nested deeper than `scope` without
+    /// forgetting them, for a jump out of those scopes.
+    fn discard_locals(&self, scope: usize) {
+        let captured: Vec<bool> = self
+            .functions
+            .borrow()
+            .last()
+            .unwrap()
+            .locals
+            .iter()
+            .rev()
+            .take_while(|local| local.scope > scope)
+            .map(|local| local.captured)
+            .collect();
+        for captured in captured {
+            self.emit(if captured {
+                OpCode::CloseUpvalue
+            } else {
+                OpCode::Pop
+            });
+        }
+    }
+
+    fn is_global_scope(&self) -> bool {
+        self.scopes.borrow().len() == 1
+    }
+
+    fn enter_scope(&self) -> Result<()> {
+        self.resolver.borrow_mut().enter_scope()?;
+        let current = self.functions.borrow().len() - 1;
+        self.scopes.borrow_mut().push(current);
+        Ok(())
+    }
+
+    /// Drops the locals of the innermost scope, closing the captured ones.
+    fn exit_scope(&self) -> Result<()> {
+        self.resolver.borrow_mut().exit_scope()?;
+        let scope = self.scopes.borrow().len() - 1;
+        self.scopes.borrow_mut().pop();
+        loop {
+            let captured = {
+                let mut functions = self.functions.borrow_mut();
+                let locals = &mut functions.last_mut().unwrap().locals;
+                match locals.last() {
+                    Some(local) if local.scope == scope => locals.pop().unwrap().captured,
+                    _ => break,
+                }
+            };
+            self.emit(if captured {
+                OpCode::CloseUpvalue
+            } else {
+                OpCode::Pop
+            });
+        }
+        Ok(())
+    }
+
+    /// Declares `name` in the resolver and, outside the global scope, gives it
+    /// the stack slot its initializer is about to be pushed into.
+    fn declare_variable(&self, name: &str, span: &Span) -> Result<()> {
+        self.resolver.borrow_mut().declare(name, span)?;
+        if self.is_global_scope() {
+            return Ok(());
+        }
+        let scope = self.scopes.borrow().len() - 1;
+        let mut functions = self.functions.borrow_mut();
+        let locals = &mut functions.last_mut().unwrap().locals;
+        if locals.len() > u16::MAX as usize {
+            return Err(CompileError::TooManyLocals.into());
+        }
+        locals.push(Local {
+            name: name.to_string(),
+            scope,
+            captured: false,
+        });
+        Ok(())
+    }
+
+    fn mark_defined(&self, name: &str) -> Result<()> {
+        Ok(self.resolver.borrow_mut().define(name)?)
+    }
+
+    /// Binds the value on top of the stack to the variable just declared.
+    fn define_variable(&self, name: &str) -> Result<()> {
+        self.mark_defined(name)?;
+        if self.is_global_scope() {
+            let constant = self.name_constant(name)?;
+            self.emit_with_operand(OpCode::DefineGlobal, constant);
+        }
+        Ok(())
+    }
+
+    /// Looks `name` up from the scope the resolver found it in outwards, so a
+    /// name the resolver knows but that holds no value (a method name) falls
+    /// back to the enclosing declarations, then to the globals.
+    fn resolve(&self, name: &str, span: &Span) -> Result<Variable> {
+        let depth = self.resolver.borrow().resolve(name, span)?;
+        let scopes_len = self.scopes.borrow().len();
+        if let Some(depth) = depth {
+            for scope in (1..=scopes_len - 1 - depth).rev() {
+                let owner = self.scopes.borrow()[scope];
+                let slot = self.functions.borrow()[owner]
+                    .locals
+                    .iter()
+                    .rposition(|local| local.scope == scope && local.name == name);
+                if let Some(slot) = slot {
+                    return self.local_or_upvalue(owner, slot);
+                }
+            }
+        }
+        Ok(Variable::Global(self.name_constant(name)?))
+    }
+
+    /// `this` and `super` live in the reserved slots of the innermost method.
+    fn resolve_receiver(&self, name: &str) -> Result<Variable> {
+        let owner = {
+            let functions = self.functions.borrow();
+            (0..functions.len())
+                .rev()
+                .find_map(|index| {
+                    functions[index]
+                        .locals
+                        .iter()
+                        .position(|local| local.name == name)
+                        .map(|slot| (index, slot))
+                })
+                .ok_or(RuntimeError::ThisNotInScope)?
+        };
+        self.local_or_upvalue(owner.0, owner.1)
+    }
+
+    fn local_or_upvalue(&self, owner: usize, slot: usize) -> Result<Variable> {
+        let current = self.functions.borrow().len() - 1;
+        if owner == current {
+            return Ok(Variable::Local(slot as u16));
+        }
+        Ok(Variable::Upvalue(
+            self.resolve_upvalue(current, owner, slot)?,
+        ))
+    }
+
+    fn resolve_upvalue(&self, function: usize, owner: usize, slot: usize) -> Result<u16> {
+        let (is_local, index) = if function - 1 == owner {
+            self.functions.borrow_mut()[owner].locals[slot].captured = true;
+            (true, slot as u16)
+        } else {
+            (false, self.resolve_upvalue(function - 1, owner, slot)?)
+        };
+        let mut functions = self.functions.borrow_mut();
+        let upvalues = &mut functions[function].upvalues;
+        if let Some(existing) = upvalues.iter().position(|u| *u == (is_local, index)) {
+            return Ok(existing as u16);
+        }
+        if upvalues.len() > u16::MAX as usize {
+            return Err(CompileError::TooManyUpvalues.into());
+        }
+        upvalues.push((is_local, index));
+        Ok((upvalues.len() - 1) as u16)
+    }
+
+    fn emit_get(&self, variable: Variable) {
+        match variable {
+            Variable::Local(slot) => self.emit_with_operand(OpCode::GetLocal, slot),
+            Variable::Upvalue(index) => self.emit_with_operand(OpCode::GetUpvalue, index),
+            Variable::Global(name) => self.emit_with_operand(OpCode::GetGlobal, name),
+        }
+    }
+
+    fn emit_set(&self, variable: Variable) {
+        match variable {
+            Variable::Local(slot) => self.emit_with_operand(OpCode::SetLocal, slot),
+            Variable::Upvalue(index) => self.emit_with_operand(OpCode::SetUpvalue, index),
+            Variable::Global(name) => self.emit_with_operand(OpCode::SetGlobal, name),
+        }
+    }
+
+    fn get_identifier(&self, token: &Token) -> Result<()> {
+        self.set_span(&token.span);
+        let variable = self.resolve(&token.lexeme, &token.span)?;
+        self.emit_get(variable);
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&ProgramAst, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &ProgramAst, context: &CompilerContext) -> Result<()> {
+        for (_, declaration) in &node.statements {
+            declaration.accept_with_context(&Self, context)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Declaration, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Declaration, context: &CompilerContext) -> Result<()> {
+        match &node.token_type {
+            DeclarationType::VarDeclaration(var) => var.accept_with_context(&Self, context),
+            DeclarationType::Statement(stmt) => stmt.accept_with_context(&Self, context),
+            DeclarationType::FunctionDeclaration(func) => func.accept_with_context(&Self, context),
+            DeclarationType::ClassDeclaration(class) => class.accept_with_context(&Self, context),
+            DeclarationType::ImportDeclaration(_) => {
+                Err(RuntimeError::Unsupported("Importing a module".to_string()))
+            }
+            DeclarationType::None => Err(RuntimeError::ASTInvalidStructure),
+        }
+    }
+}
+
+impl VisitorWithContext<&VarDeclaration, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &VarDeclaration, context: &CompilerContext) -> Result<()> {
+        let ident_token = node
+            .token_list
+            .get(1)
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        context.set_span(&ident_token.span);
+        context.declare_variable(&ident_token.lexeme, &ident_token.span)?;
+        match &node.expr {
+            Some(expr) => expr.accept_with_context(&Self, context)?,
+            None => context.emit(OpCode::Nil),
+        }
+        context.define_variable(&ident_token.lexeme)
+    }
+}
+
+impl VisitorWithContext<&FunctionDeclaration, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(
+        &self,
+        node: &FunctionDeclaration,
+        context: &CompilerContext,
+    ) -> Result<()> {
+        let super_available = context.resolver.borrow().is_super_available();
+        Self::compile_function(&node.function, context, None, super_available)
+    }
+}
+
+impl VisitorWithContext<&ClassDeclaration, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &ClassDeclaration, context: &CompilerContext) -> Result<()> {
+        let class_ident = node
+            .token_list
+            .get(1)
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        let class_name = class_ident.lexeme.clone();
+        let super_class = node.super_class.super_class.as_ref();
+        context.set_span(&class_ident.span);
+
+        // As in the tree-walker, a superclass that names no class declared so
+        // far replaces the declaration with a runtime check of that name.
+        let known_super_class = super_class
+            .map(|super_class| {
+                context
+                    .class_names
+                    .borrow()
+                    .contains(&super_class.token.lexeme)
+            })
+            .unwrap_or(true);
+
+        if known_super_class {
+            context.declare_variable(&class_name, &class_ident.span)?;
+            let name_constant = context.name_constant(&class_name)?;
+            context.emit_with_operand(OpCode::Class, name_constant);
+            context.define_variable(&class_name)?;
+            let class_variable = context.resolve(&class_name, &class_ident.span)?;
+            context.emit_get(class_variable);
+        } else {
+            let mut resolver = context.resolver.borrow_mut();
+            resolver.declare(&class_name, &class_ident.span)?;
+            resolver.define(&class_name)?;
+        }
+
+        context.resolver.borrow_mut().enter_class();
+        for (_, function) in &node.functions {
+            Self::compile_function(function, context, Some(&class_name), super_class.is_some())?;
+            if known_super_class {
+                let method_name = function
+                    .token_list
+                    .first()
+                    .ok_or(RuntimeError::ASTInvalidStructure)?;
+                let name_constant = context.name_constant(&method_name.lexeme)?;
+                context.emit_with_operand(OpCode::Method, name_constant);
+            } else {
+                context.emit(OpCode::Pop);
+            }
+        }
+        context.resolver.borrow_mut().exit_class();
+
+        if let Some(super_class) = super_class {
+            context.get_identifier(&super_class.token)?;
+            context.emit(if known_super_class {
+                OpCode::Inherit
+            } else {
+                OpCode::InheritUnknown
+            });
+        }
+
+        if known_super_class {
+            context.emit(OpCode::Pop);
+            context.class_names.borrow_mut().insert(class_name);
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Statement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Statement, context: &CompilerContext) -> Result<()> {
+        match &node.token_type {
+            StatementType::ExprStatement(expr) => expr.accept_with_context(&Self, context),
+            StatementType::PrintStatement(print) => print.accept_with_context(&Self, context),
+            StatementType::Block(block) => block.accept_with_context(&Self, context),
+            StatementType::IfStatement(if_stmt) => if_stmt.accept_with_context(&Self, context),
+            StatementType::WhileStatement(while_stmt) => {
+                while_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ForStatement(for_stmt) => for_stmt.accept_with_context(&Self, context),
+            StatementType::ReturnStatement(return_stmt) => {
+                return_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::BreakStatement(break_stmt) => {
+                break_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ContinueStatement(continue_stmt) => {
+                continue_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ThrowStatement(_) | StatementType::TryStatement(_) => {
+                Err(RuntimeError::Unsupported("Exception handling".to_string()))
+            }
+            StatementType::None => Err(RuntimeError::ASTInvalidStructure),
+        }
+    }
+}
+
+impl VisitorWithContext<&PrintStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &PrintStatement, context: &CompilerContext) -> Result<()> {
+        node.expr.accept_with_context(&Self, context)?;
+        context.emit(OpCode::Print);
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&ExprStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &ExprStatement, context: &CompilerContext) -> Result<()> {
+        node.expr.accept_with_context(&Self, context)?;
+        context.emit(OpCode::Pop);
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Block, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Block, context: &CompilerContext) -> Result<()> {
+        context.enter_scope()?;
+        for (_, declaration) in &node.declarations {
+            declaration.accept_with_context(&Self, context)?;
+        }
+        context.exit_scope()
+    }
+}
+
+impl VisitorWithContext<&IfStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &IfStatement, context: &CompilerContext) -> Result<()> {
+        node.bool_expr.accept_with_context(&Self, context)?;
+        let else_jump = context.emit_jump(OpCode::JumpIfFalse);
+        context.emit(OpCode::Pop);
+        node.true_statement.accept_with_context(&Self, context)?;
+        let end_jump = context.emit_jump(OpCode::Jump);
+        context.patch_jump(else_jump)?;
+        context.emit(OpCode::Pop);
+        if let Some(false_statement) = &node.false_statement {
+            false_statement.accept_with_context(&Self, context)?;
+        }
+        context.patch_jump(end_jump)
+    }
+}
+
+impl VisitorWithContext<&WhileStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &WhileStatement, context: &CompilerContext) -> Result<()> {
+        let loop_start = context.position();
+        node.eval_expr.accept_with_context(&Self, context)?;
+        let exit_jump = context.emit_jump(OpCode::JumpIfFalse);
+        context.emit(OpCode::Pop);
+        context.enter_loop(loop_start);
+        node.statement.accept_with_context(&Self, context)?;
+        context.emit_loop(loop_start)?;
+        context.patch_jump(exit_jump)?;
+        context.emit(OpCode::Pop);
+        context.exit_loop()
+    }
+}
+
+impl VisitorWithContext<&ForStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &ForStatement, context: &CompilerContext) -> Result<()> {
+        context.enter_scope()?;
+        match &node.token_type {
+            ForStatementType::VarDeclaration(var) => var.accept_with_context(&Self, context)?,
+            ForStatementType::ExprStatement(expr) => expr.accept_with_context(&Self, context)?,
+            ForStatementType::Semicolon => {}
+            ForStatementType::None => return Err(RuntimeError::ASTInvalidStructure),
+        }
+
+        let mut loop_start = context.position();
+        let exit_jump = match &node.condition.expr {
+            Some(condition) => {
+                condition.accept_with_context(&Self, context)?;
+                let exit_jump = context.emit_jump(OpCode::JumpIfFalse);
+                context.emit(OpCode::Pop);
+                Some(exit_jump)
+            }
+            None => None,
+        };
+
+        if let Some(increment) = &node.increment.expr {
+            let body_jump = context.emit_jump(OpCode::Jump);
+            let increment_start = context.position();
+            increment.accept_with_context(&Self, context)?;
+            context.emit(OpCode::Pop);
+            context.emit_loop(loop_start)?;
+            loop_start = increment_start;
+            context.patch_jump(body_jump)?;
+        }
+
+        context.enter_loop(loop_start);
+        node.statement.accept_with_context(&Self, context)?;
+        context.emit_loop(loop_start)?;
+
+        if let Some(exit_jump) = exit_jump {
+            context.patch_jump(exit_jump)?;
+            context.emit(OpCode::Pop);
+        }
+        context.exit_loop()?;
+        context.exit_scope()
+    }
+}
+
+impl VisitorWithContext<&ReturnStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &ReturnStatement, context: &CompilerContext) -> Result<()> {
+        match &node.expr {
+            Some(expr) => expr.accept_with_context(&Self, context)?,
+            None => context.emit(OpCode::Nil),
+        }
+        context.emit(OpCode::Return);
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&BreakStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &BreakStatement, context: &CompilerContext) -> Result<()> {
+        let keyword = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        context.set_span(&keyword.span);
+        context.emit_break()
+    }
+}
+
+impl VisitorWithContext<&ContinueStatement, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(
+        &self,
+        node: &ContinueStatement,
+        context: &CompilerContext,
+    ) -> Result<()> {
+        let keyword = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        context.set_span(&keyword.span);
+        context.emit_continue()
+    }
+}
+
+impl VisitorWithContext<&Assignment, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Assignment, context: &CompilerContext) -> Result<()> {
+        match node {
+            Assignment::Assignment(value, identifier) => {
+                let variable = context.resolve(&identifier.token.lexeme, &identifier.token.span)?;
+                value.accept_with_context(&Self, context)?;
+                context.set_span(&identifier.token.span);
+                context.emit_set(variable);
+                Ok(())
+            }
+            Assignment::SetExpression(value, identifier, call) => {
+                call.accept_with_context(&Self, context)?;
+                context.emit(OpCode::CheckInstance);
+                value.accept_with_context(&Self, context)?;
+                let name_constant = context.name_constant(&identifier.token.lexeme)?;
+                context.emit_with_operand(OpCode::SetProperty, name_constant);
+                Ok(())
+            }
+            Assignment::SetIndex(value, index, call) => {
+                call.accept_with_context(&Self, context)?;
+                index.expression.accept_with_context(&Self, context)?;
+                value.accept_with_context(&Self, context)?;
+                context.set_span(Self::bracket_span(index)?);
+                context.emit(OpCode::SetIndex);
+                Ok(())
+            }
+            Assignment::Evaluable(evaluable) => evaluable.accept_with_context(&Self, context),
+        }
+    }
+}
+
+impl VisitorWithContext<&LogicalOr, Result<()>, CompilerContext> for Compiler {
+    /// `or` yields the first truthy operand, or `false` when neither is.
+    fn visit_with_context(&self, node: &LogicalOr, context: &CompilerContext) -> Result<()> {
+        node.main_and.accept_with_context(&Self, context)?;
+        for (_, and) in &node.ands {
+            let else_jump = context.emit_jump(OpCode::JumpIfFalse);
+            let left_jump = context.emit_jump(OpCode::Jump);
+            context.patch_jump(else_jump)?;
+            context.emit(OpCode::Pop);
+            and.accept_with_context(&Self, context)?;
+            let false_jump = context.emit_jump(OpCode::JumpIfFalse);
+            let right_jump = context.emit_jump(OpCode::Jump);
+            context.patch_jump(false_jump)?;
+            context.emit(OpCode::Pop);
+            context.emit(OpCode::False);
+            context.patch_jump(left_jump)?;
+            context.patch_jump(right_jump)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&LogicalAnd, Result<()>, CompilerContext> for Compiler {
+    /// `and` yields its right operand when both are truthy, `false` otherwise.
+    fn visit_with_context(&self, node: &LogicalAnd, context: &CompilerContext) -> Result<()> {
+        node.main_equality.accept_with_context(&Self, context)?;
+        for (_, equality) in &node.equalities {
+            let left_false = context.emit_jump(OpCode::JumpIfFalse);
+            context.emit(OpCode::Pop);
+            equality.accept_with_context(&Self, context)?;
+            let right_false = context.emit_jump(OpCode::JumpIfFalse);
+            let end_jump = context.emit_jump(OpCode::Jump);
+            context.patch_jump(left_false)?;
+            context.patch_jump(right_false)?;
+            context.emit(OpCode::Pop);
+            context.emit(OpCode::False);
+            context.patch_jump(end_jump)?;
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Equality, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Equality, context: &CompilerContext) -> Result<()> {
+        node.main_comparison.accept_with_context(&Self, context)?;
+        for (op_type, comparison) in &node.comparisons {
+            comparison.accept_with_context(&Self, context)?;
+            context.emit(match op_type {
+                EqualityType::EqualEqual => OpCode::Equal,
+                EqualityType::BangEqual => OpCode::NotEqual,
+                _ => return Err(RuntimeError::ASTInvalidStructure),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Comparison, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Comparison, context: &CompilerContext) -> Result<()> {
+        node.main_term.accept_with_context(&Self, context)?;
+        for ((op_type, term), operator) in node.terms.iter().zip(&node.token_list) {
+            term.accept_with_context(&Self, context)?;
+            context.set_span(&operator.span);
+            context.emit(match op_type {
+                ComparisonType::Less => OpCode::Less,
+                ComparisonType::LessEqual => OpCode::LessEqual,
+                ComparisonType::Greater => OpCode::Greater,
+                ComparisonType::GreaterEqual => OpCode::GreaterEqual,
+                _ => return Err(RuntimeError::ASTInvalidStructure),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Term, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Term, context: &CompilerContext) -> Result<()> {
+        node.main_factor.accept_with_context(&Self, context)?;
+        for ((op_type, factor), operator) in node.factors.iter().zip(&node.token_list) {
+            factor.accept_with_context(&Self, context)?;
+            context.set_span(&operator.span);
+            context.emit(match op_type {
+                TermType::Plus => OpCode::Add,
+                TermType::Minus => OpCode::Subtract,
+                _ => return Err(RuntimeError::ASTInvalidStructure),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Factor, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Factor, context: &CompilerContext) -> Result<()> {
+        node.main_unary.accept_with_context(&Self, context)?;
+        for ((op_type, unary), operator) in node.unaries.iter().zip(&node.token_list) {
+            unary.accept_with_context(&Self, context)?;
+            context.set_span(&operator.span);
+            context.emit(match op_type {
+                FactorType::Star => OpCode::Multiply,
+                FactorType::Slash => OpCode::Divide,
+                _ => return Err(RuntimeError::ASTInvalidStructure),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&UnaryExpression, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &UnaryExpression, context: &CompilerContext) -> Result<()> {
+        match &node.token_type {
+            UnaryExpressionType::Call(call) => call.accept_with_context(&Self, context),
+            UnaryExpressionType::UnaryExpressionSelf(unary) => {
+                unary.accept_with_context(&Self, context)
+            }
+            UnaryExpressionType::None => Err(RuntimeError::ASTInvalidStructure),
+        }
+    }
+}
+
+impl VisitorWithContext<&UnaryExpressionSelf, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(
+        &self,
+        node: &UnaryExpressionSelf,
+        context: &CompilerContext,
+    ) -> Result<()> {
+        node.expr.accept_with_context(&Self, context)?;
+        let operator = node
+            .token_list
+            .first()
+            .ok_or(RuntimeError::ASTInvalidStructure)?;
+        context.set_span(&operator.span);
+        context.emit(match &node.token_type {
+            UnaryExpressionSelfType::Bang => OpCode::Not,
+            UnaryExpressionSelfType::Minus => OpCode::Negate,
+            UnaryExpressionSelfType::None => return Err(RuntimeError::ASTInvalidStructure),
+        });
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&Call, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(&self, node: &Call, context: &CompilerContext) -> Result<()> {
+        node.primary.accept_with_context(&Self, context)?;
+        for (_, accessor_or_arguments) in &node.accessor_or_arguments {
+            match &accessor_or_arguments.ty {
+                AccessorOrArgumentsType::Accessor(accessor) => {
+                    let token = &accessor.identifier.token;
+                    context.set_span(&token.span);
+                    let name_constant = context.name_constant(&token.lexeme)?;
+                    context.emit_with_operand(OpCode::GetProperty, name_constant);
+                }
+                AccessorOrArgumentsType::ArgumentsList(arguments_list) => {
+                    let span = &arguments_list
+                        .token_list
+                        .last()
+                        .ok_or(RuntimeError::ASTInvalidStructure)?
+                        .span;
+                    // The callee is checked before its arguments run, as the
+                    // tree-walker does, when there are arguments to run.
+                    if arguments_list.maybe_arguments.is_some() {
+                        context.set_span(span);
+                        context.emit(OpCode::CheckCallable);
+                    }
+                    let argument_count = Self::compile_arguments(arguments_list, context)?;
+                    context.set_span(span);
+                    context.emit(OpCode::Call);
+                    context.emit_byte(argument_count);
+                }
+                AccessorOrArgumentsType::Index(index) => {
+                    index.expression.accept_with_context(&Self, context)?;
+                    context.set_span(Self::bracket_span(index)?);
+                    context.emit(OpCode::GetIndex);
+                }
+                AccessorOrArgumentsType::None => return Err(RuntimeError::ASTInvalidStructure),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VisitorWithContext<&PrimaryExpression, Result<()>, CompilerContext> for Compiler {
+    fn visit_with_context(
+        &self,
+        node: &PrimaryExpression,
+        context: &CompilerContext,
+    ) -> Result<()> {
+        match node {
+            PrimaryExpression::PrimaryWithoutSuperExpression(node) => {
+                let token = node
+                    .token_list
+                    .first()
+                    .ok_or(RuntimeError::ASTInvalidStructure)?;
+                context.set_span(&token.span);
+                match &node.token_type {
+                    PrimaryExpressionType::True => context.emit(OpCode::True),
+                    PrimaryExpressionType::False => context.emit(OpCode::False),
+                    PrimaryExpressionType::Nil => context.emit(OpCode::Nil),
+                    PrimaryExpressionType::This => {
+                        let this = context.resolve_receiver(THIS_SLOT_NAME)?;
+                        context.emit_get(this);
+                    }
+                    PrimaryExpressionType::Number
+                    | PrimaryExpressionType::String
+                    | PrimaryExpressionType::Identifier => match &token.token_value {
+                        TokenValue::Number(value) => {
+                            let constant = context.add_constant(Constant::Number(*value))?;
+                            context.emit_with_operand(OpCode::Constant, constant);
+                        }
+                        TokenValue::String(value) => {
+                            let constant =
+                                context.add_constant(Constant::String(Rc::from(value.as_str())))?;
+                            context.emit_with_operand(OpCode::Constant, constant);
+                        }
+                        TokenValue::Identifier(_) => context.get_identifier(token)?,
+                        _ => return Err(RuntimeError::ASTInvalidStructure),
+                    },
+                    PrimaryExpressionType::Expression(expr) => {
+                        expr.accept_with_context(&Self, context)?
+                    }
+                    PrimaryExpressionType::ListElements(elements) => {
+                        let count = match elements {
+                            Some(elements) => {
+                                elements.first.accept_with_context(&Self, context)?;
+                                for (_, element) in &elements.rest {
+                                    element.accept_with_context(&Self, context)?;
+                                }
+                                elements.rest.len() + 1
+                            }
+                            None => 0,
+                        };
+                        let count =
+                            u16::try_from(count).map_err(|_| CompileError::TooManyElements)?;
+                        context.set_span(&token.span);
+                        context.emit_with_operand(OpCode::BuildList, count);
+                    }
+                    PrimaryExpressionType::MapEntries(entries) => {
+                        context.emit(OpCode::NewMap);
+                        if let Some(entries) = entries {
+                            let rest = entries.rest.iter().map(|(_, entry)| entry);
+                            for entry in std::iter::once(&entries.first).chain(rest) {
+                                entry.key.accept_with_context(&Self, context)?;
+                                entry.value.accept_with_context(&Self, context)?;
+                                let colon = entry
+                                    .token_list
+                                    .first()
+                                    .ok_or(RuntimeError::ASTInvalidStructure)?;
+                                context.set_span(&colon.span);
+                                context.emit(OpCode::InsertEntry);
+                            }
+                        }
+                    }
+                    PrimaryExpressionType::None => return Err(RuntimeError::ASTInvalidStructure),
+                }
+                Ok(())
+            }
+            PrimaryExpression::Lambda(lambda) => {
+                context.set_span(&lambda.keyword.span);
+                let super_available = context.resolver.borrow().is_super_available();
+                Self::compile_body(
+                    LAMBDA_NAME,
+                    None,
+                    &lambda.parameters,
+                    |context| match &lambda.body {
+                        LambdaBody::Block(block) => block.accept_with_context(&Self, context),
+                        LambdaBody::Expression(expression) => {
+                            expression.accept_with_context(&Self, context)?;
+                            context.emit(OpCode::Return);
+                            Ok(())
+                        }
+                    },
+                    context,
+                    super_available,
+                )
+            }
+            PrimaryExpression::Super(identifier) => {
+                context.set_span(&identifier.token.span);
+                let super_class = context.resolve_receiver(SUPER_SLOT_NAME)?;
+                context.emit_get(super_class);
+                let name_constant = context.name_constant(&identifier.token.lexeme)?;
+                context.emit_with_operand(OpCode::GetSuper, name_constant);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax_analysis::ParseStream;
+    use crate::tokenizer::scan_tokens;
+
+    fn function_constant(chunk: &Chunk, index: u16) -> Rc<FunctionProto> {
+        match chunk.constant(index) {
+            Constant::Function(proto) => proto.clone(),
+            other => panic!("expected a function constant, found {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_closure_captures_enclosing_local() {
+        let (tokens, _) = scan_tokens(
+            "",
+            "fun outer() { var x = 1; fun inner() { return x; } return inner; }",
+        );
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        let script = Compiler::compile(&program).unwrap();
+
+        let outer = function_constant(&script.chunk, 0);
+        assert_eq!(outer.name, "outer");
+        assert_eq!(outer.upvalue_count, 0);
+        let inner = function_constant(&outer.chunk, 1);
+        assert_eq!(inner.name, "inner");
+        assert_eq!(inner.upvalue_count, 1);
+    }
+}
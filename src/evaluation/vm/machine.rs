@@ -0,0 +1,719 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::evaluation::{
+    evaluator::{ClassAccessorError, FunctionEvaluationError, INIT_FUNCTION_NAME},
+    run::{get_native_functions, CallFrame as TraceFrame, InputSource, StackTrace},
+    MapKey, OutputSink, RunState, RuntimeError, RuntimeValue, SandboxPolicy, Table,
+};
+use crate::tokenizer::Span;
+
+use super::chunk::{Constant, OpCode};
+use super::natives::collection_natives;
+use super::value::{
+    BoundMethod, Class, Closure, FunctionProto, Instance, Native, NativeFunction, This, Upvalue,
+    Value,
+};
+
+const MAX_FRAMES: usize = 16 * 1024;
+
+type Result<T> = std::result::Result<T, RuntimeError>;
+
+struct CallFrame {
+    closure: Rc<Closure>,
+    ip: usize,
+    base: usize,
+    /// Set when running an `init` method, which always returns its instance.
+    init_receiver: Option<Rc<Instance>>,
+}
+
+/// A stack machine running the bytecode produced by `Compiler`.
+pub struct Vm {
+    stack: Vec<Value>,
+    frames: Vec<CallFrame>,
+    globals: HashMap<Rc<str>, Value>,
+    open_upvalues: Vec<Rc<RefCell<Upvalue>>>,
+    /// Native functions are shared with the tree-walker and take a run state;
+    /// the VM has no scopes or instances of its own in there.
+    native_state: RunState,
+}
+
+impl Vm {
+    pub fn new() -> Self {
+        let globals = get_native_functions()
+            .into_iter()
+            .map(|(name, function)| (name, NativeFunction::Shared(function)))
+            .chain(collection_natives())
+            .map(|(name, function)| {
+                let native = Native {
+                    name: name.to_string(),
+                    function,
+                };
+                (Rc::from(name), Value::Native(Rc::new(native)))
+            })
+            .collect();
+        Self {
+            stack: Vec::new(),
+            frames: Vec::new(),
+            globals,
+            open_upvalues: Vec::new(),
+            native_state: RunState::void(),
+        }
+    }
+
+    pub fn set_sandbox(&self, sandbox: SandboxPolicy) {
+        self.native_state.set_sandbox(sandbox);
+    }
+
+    pub fn set_script_arguments(&self, arguments: Vec<String>) {
+        self.native_state.set_script_arguments(arguments);
+    }
+
+    pub fn set_output(&self, output: Box<dyn OutputSink>) {
+        self.native_state.set_output(output);
+    }
+
+    pub fn set_input(&self, input: Box<dyn InputSource>) {
+        self.native_state.set_input(input);
+    }
+
+    pub fn interpret(&mut self, script: Rc<FunctionProto>) -> Result<()> {
+        let closure = Rc::new(Closure {
+            proto: script,
+            upvalues: Vec::new(),
+        });
+        self.stack.push(Value::Function(closure.clone()));
+        self.push_frame(closure, 0, None)?;
+        self.run(0)
+            .map(|_| ())
+            .map_err(|error| match self.stack_trace(&error) {
+                Some(trace) => RuntimeError::WithStackTrace(Box::new(error), trace),
+                None => error,
+            })
+    }
+
+    /// The calls `error` escaped from, if it was raised inside any. Frames are
+    /// left in place by a failing `run`, and every caller's `ip` was saved
+    /// just past its `Call` instruction.
+    fn stack_trace(&self, error: &RuntimeError) -> Option<StackTrace> {
+        if self.frames.len() < 2 {
+            return None;
+        }
+        let frames = self
+            .frames
+            .windows(2)
+            .map(|pair| {
+                let (caller, callee) = (&pair[0], &pair[1]);
+                TraceFrame::new(
+                    callee.closure.proto.name.clone(),
+                    callee.closure.proto.class.clone(),
+                    caller.closure.proto.chunk.span(caller.ip - 1),
+                )
+            })
+            .collect();
+        Some(StackTrace::new(frames, error.span().map(|span| span.line)))
+    }
+
+    fn push_frame(
+        &mut self,
+        closure: Rc<Closure>,
+        base: usize,
+        init_receiver: Option<Rc<Instance>>,
+    ) -> Result<()> {
+        if self.frames.len() == MAX_FRAMES {
+            return Err(RuntimeError::StackOverflow);
+        }
+        self.frames.push(CallFrame {
+            closure,
+            ip: 0,
+            base,
+            init_receiver,
+        });
+        Ok(())
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Value {
+        self.stack.pop().expect("value stack underflow")
+    }
+
+    #[inline]
+    fn peek(&self, distance: usize) -> &Value {
+        &self.stack[self.stack.len() - 1 - distance]
+    }
+
+    /// Runs until the frame at `stop_depth` returns, yielding its return value.
+    fn run(&mut self, stop_depth: usize) -> Result<Value> {
+        let mut closure = self.frames.last().unwrap().closure.clone();
+        let mut ip = self.frames.last().unwrap().ip;
+        let mut base = self.frames.last().unwrap().base;
+
+        macro_rules! read_byte {
+            () => {{
+                let byte = closure.proto.chunk.byte(ip);
+                ip += 1;
+                byte
+            }};
+        }
+        macro_rules! read_u16 {
+            () => {{
+                let value = closure.proto.chunk.read_u16(ip);
+                ip += 2;
+                value
+            }};
+        }
+        macro_rules! read_name {
+            () => {{
+                match closure.proto.chunk.constant(read_u16!()) {
+                    Constant::String(name) => name.clone(),
+                    _ => return Err(RuntimeError::ASTInvalidStructure),
+                }
+            }};
+        }
+        macro_rules! binary {
+            ($method:ident, $span:ident) => {{
+                let right = self.pop();
+                let left = self.pop();
+                let result = left.$method(right).map_err(|error| error.at(&$span()))?;
+                self.stack.push(result);
+            }};
+        }
+
+        loop {
+            let op_offset = ip;
+            let span = || closure.proto.chunk.span(op_offset);
+            let op = OpCode::from_byte(read_byte!()).ok_or(RuntimeError::ASTInvalidStructure)?;
+            match op {
+                OpCode::Constant => {
+                    let value = match closure.proto.chunk.constant(read_u16!()) {
+                        Constant::Number(number) => Value::Number(*number),
+                        Constant::String(string) => Value::String(string.clone()),
+                        Constant::Function(_) => return Err(RuntimeError::ASTInvalidStructure),
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::Nil => self.stack.push(Value::Nil),
+                OpCode::True => self.stack.push(Value::Boolean(true)),
+                OpCode::False => self.stack.push(Value::Boolean(false)),
+                OpCode::Pop => {
+                    self.pop();
+                }
+                OpCode::GetLocal => {
+                    let slot = read_u16!() as usize;
+                    self.stack.push(self.stack[base + slot].clone());
+                }
+                OpCode::SetLocal => {
+                    let slot = read_u16!() as usize;
+                    self.stack[base + slot] = self.peek(0).clone();
+                }
+                OpCode::GetUpvalue => {
+                    let index = read_u16!() as usize;
+                    let value = match &*closure.upvalues[index].borrow() {
+                        Upvalue::Open(slot) => self.stack[*slot].clone(),
+                        Upvalue::Closed(value) => value.clone(),
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::SetUpvalue => {
+                    let index = read_u16!() as usize;
+                    let value = self.peek(0).clone();
+                    let mut upvalue = closure.upvalues[index].borrow_mut();
+                    match &mut *upvalue {
+                        Upvalue::Open(slot) => self.stack[*slot] = value,
+                        Upvalue::Closed(closed) => *closed = value,
+                    }
+                }
+                OpCode::DefineGlobal => {
+                    let name = read_name!();
+                    let value = self.pop();
+                    self.globals.insert(name, value);
+                }
+                OpCode::GetGlobal => {
+                    let name = read_name!();
+                    let value =
+                        self.globals.get(&name).cloned().ok_or_else(|| {
+                            RuntimeError::UndefinedVariable(name.to_string(), span())
+                        })?;
+                    self.stack.push(value);
+                }
+                OpCode::SetGlobal => {
+                    let name = read_name!();
+                    let value = self.peek(0).clone();
+                    match self.globals.get_mut(&name) {
+                        Some(global) => *global = value,
+                        None => {
+                            return Err(RuntimeError::UndefinedVariable(name.to_string(), span()))
+                        }
+                    }
+                }
+                OpCode::GetProperty => {
+                    let name = read_name!();
+                    let instance = match self.pop() {
+                        Value::Instance(instance) => instance,
+                        other => return Err(Self::unaccessible(&other, span())),
+                    };
+                    let value = instance
+                        .get(&name, 0)?
+                        .ok_or_else(|| RuntimeError::UndefinedProperty(name.to_string(), span()))?;
+                    self.stack.push(value);
+                }
+                OpCode::SetProperty => {
+                    let name = read_name!();
+                    let value = self.pop();
+                    match self.pop() {
+                        Value::Instance(instance) => instance.set(name, value.clone()),
+                        other => return Err(Self::unaccessible(&other, span())),
+                    }
+                    self.stack.push(value);
+                }
+                OpCode::GetSuper => {
+                    let name = read_name!();
+                    let instance = match self.pop() {
+                        Value::Instance(instance) => instance,
+                        _ => return Err(RuntimeError::SuperClassNotFound),
+                    };
+                    let value = instance
+                        .get(&name, 1)?
+                        .ok_or_else(|| RuntimeError::UndefinedProperty(name.to_string(), span()))?;
+                    self.stack.push(value);
+                }
+                OpCode::CheckInstance => {
+                    if !matches!(self.peek(0), Value::Instance(_)) {
+                        return Err(Self::unaccessible(self.peek(0), span()));
+                    }
+                }
+                OpCode::CheckCallable => {
+                    if !matches!(
+                        self.peek(0),
+                        Value::Function(_) | Value::Method(_) | Value::Class(_) | Value::Native(_)
+                    ) {
+                        return Err(FunctionEvaluationError::UnCallableFunction(span()).into());
+                    }
+                }
+                OpCode::Equal => {
+                    let right = self.pop();
+                    let left = self.pop();
+                    self.stack.push(Value::Boolean(left == right));
+                }
+                OpCode::NotEqual => {
+                    let right = self.pop();
+                    let left = self.pop();
+                    self.stack.push(Value::Boolean(left != right));
+                }
+                OpCode::Greater => binary!(gt, span),
+                OpCode::GreaterEqual => binary!(ge, span),
+                OpCode::Less => binary!(lt, span),
+                OpCode::LessEqual => binary!(le, span),
+                OpCode::Add => binary!(add, span),
+                OpCode::Subtract => binary!(subtract, span),
+                OpCode::Multiply => binary!(multiply, span),
+                OpCode::Divide => binary!(divide, span),
+                OpCode::Not => {
+                    let value = self.pop();
+                    self.stack.push(Value::Boolean(!value.to_bool()));
+                }
+                OpCode::Negate => match self.pop() {
+                    Value::Number(number) => self.stack.push(Value::Number(-number)),
+                    _ => return Err(RuntimeError::UnaryOperandError(span())),
+                },
+                OpCode::Print => {
+                    let value = self.pop();
+                    self.native_state.write_line(&value.to_string());
+                }
+                OpCode::Jump => {
+                    let distance = read_u16!() as usize;
+                    ip += distance;
+                }
+                OpCode::JumpIfFalse => {
+                    let distance = read_u16!() as usize;
+                    if !self.peek(0).to_bool() {
+                        ip += distance;
+                    }
+                }
+                OpCode::Loop => {
+                    let distance = read_u16!() as usize;
+                    ip -= distance;
+                }
+                OpCode::Call => {
+                    let argument_count = read_byte!() as usize;
+                    let span = span();
+                    self.frames.last_mut().unwrap().ip = ip;
+                    self.call_value(argument_count, span)?;
+                    let frame = self.frames.last().unwrap();
+                    closure = frame.closure.clone();
+                    ip = frame.ip;
+                    base = frame.base;
+                }
+                OpCode::Closure => {
+                    let proto = match closure.proto.chunk.constant(read_u16!()) {
+                        Constant::Function(proto) => proto.clone(),
+                        _ => return Err(RuntimeError::ASTInvalidStructure),
+                    };
+                    let mut upvalues = Vec::with_capacity(proto.upvalue_count);
+                    for _ in 0..proto.upvalue_count {
+                        let is_local = read_byte!() == 1;
+                        let index = read_u16!() as usize;
+                        upvalues.push(if is_local {
+                            self.capture_upvalue(base + index)
+                        } else {
+                            closure.upvalues[index].clone()
+                        });
+                    }
+                    self.stack
+                        .push(Value::Function(Rc::new(Closure { proto, upvalues })));
+                }
+                OpCode::CloseUpvalue => {
+                    self.close_upvalues(self.stack.len() - 1);
+                    self.pop();
+                }
+                OpCode::Return => {
+                    let result = self.pop();
+                    let frame = self.frames.pop().unwrap();
+                    self.close_upvalues(frame.base);
+                    self.stack.truncate(frame.base);
+                    let result = match frame.init_receiver {
+                        Some(instance) => Value::Instance(instance),
+                        None => result,
+                    };
+                    if self.frames.len() == stop_depth {
+                        return Ok(result);
+                    }
+                    self.stack.push(result);
+                    let frame = self.frames.last().unwrap();
+                    closure = frame.closure.clone();
+                    ip = frame.ip;
+                    base = frame.base;
+                }
+                OpCode::Class => {
+                    let name = read_name!();
+                    self.stack.push(Value::Class(Rc::new(Class {
+                        name,
+                        super_class: RefCell::new(None),
+                        methods: RefCell::new(Vec::new()),
+                    })));
+                }
+                OpCode::Inherit => {
+                    let super_class = match self.pop() {
+                        Value::Class(super_class) => super_class,
+                        _ => return Err(RuntimeError::SuperClassMustBeAClass(span())),
+                    };
+                    if let Value::Class(class) = self.peek(0) {
+                        class.super_class.replace(Some(super_class));
+                    }
+                }
+                OpCode::InheritUnknown => {
+                    if !matches!(self.pop(), Value::Instance(_)) {
+                        return Err(RuntimeError::SuperClassMustBeAClass(span()));
+                    }
+                }
+                OpCode::BuildList => {
+                    let count = read_u16!() as usize;
+                    let elements = self.stack.split_off(self.stack.len() - count);
+                    self.stack
+                        .push(Value::List(Rc::new(RefCell::new(elements))));
+                }
+                OpCode::NewMap => {
+                    self.stack
+                        .push(Value::Map(Rc::new(RefCell::new(Table::default()))));
+                }
+                OpCode::InsertEntry => {
+                    let value = self.pop();
+                    let key = Self::key(&self.pop(), span())?;
+                    if let Value::Map(map) = self.peek(0) {
+                        map.borrow_mut().insert(key, value);
+                    }
+                }
+                OpCode::GetIndex => {
+                    let index = self.pop();
+                    let value = match self.pop() {
+                        Value::List(list) => {
+                            let position = Self::position(&list.borrow(), &index, span())?;
+                            let element = list.borrow()[position].clone();
+                            element
+                        }
+                        Value::Map(map) => {
+                            let key = Self::key(&index, span())?;
+                            let value = map.borrow().get(&key).cloned();
+                            value.ok_or_else(|| {
+                                RuntimeError::UndefinedKey(key.to_string(), span())
+                            })?
+                        }
+                        _ => return Err(RuntimeError::NotIndexable(span())),
+                    };
+                    self.stack.push(value);
+                }
+                OpCode::SetIndex => {
+                    let value = self.pop();
+                    let index = self.pop();
+                    match self.pop() {
+                        Value::List(list) => {
+                            let position = Self::position(&list.borrow(), &index, span())?;
+                            list.borrow_mut()[position] = value.clone();
+                        }
+                        Value::Map(map) => {
+                            let key = Self::key(&index, span())?;
+                            map.borrow_mut().insert(key, value.clone());
+                        }
+                        _ => return Err(RuntimeError::NotIndexable(span())),
+                    }
+                    self.stack.push(value);
+                }
+                OpCode::Method => {
+                    let name = read_name!();
+                    let method = match self.pop() {
+                        Value::Function(method) => method,
+                        _ => return Err(RuntimeError::ASTInvalidStructure),
+                    };
+                    if let Value::Class(class) = self.peek(0) {
+                        class.methods.borrow_mut().push((name, method));
+                    }
+                }
+            }
+        }
+    }
+
+    fn unaccessible(value: &Value, span: Span) -> RuntimeError {
+        ClassAccessorError::Unaccessible(value.to_runtime_value(), span).into()
+    }
+
+    fn key(value: &Value, span: Span) -> Result<MapKey> {
+        value.to_map_key().ok_or(RuntimeError::UnhashableKey(span))
+    }
+
+    /// The element of `elements` that `index` refers to, with the errors of
+    /// the tree-walker's `List::position`.
+    fn position(elements: &[Value], index: &Value, span: Span) -> Result<usize> {
+        let index = match index {
+            Value::Number(number) if number.fract() == 0.0 => *number,
+            _ => return Err(RuntimeError::InvalidIndex(span)),
+        };
+        if index < 0.0 || index >= elements.len() as f64 {
+            return Err(RuntimeError::IndexOutOfRange(index, elements.len(), span));
+        }
+        Ok(index as usize)
+    }
+
+    /// Calls the value below the `argument_count` arguments on top of the
+    /// stack. Functions and methods get a new frame the caller's loop picks up;
+    /// classes and natives leave their result in place of the callee.
+    fn call_value(&mut self, argument_count: usize, span: Span) -> Result<()> {
+        let callee_slot = self.stack.len() - argument_count - 1;
+        match self.stack[callee_slot].clone() {
+            Value::Function(closure) => {
+                if closure.proto.arity != argument_count {
+                    return Err(RuntimeError::ArityMismatch(
+                        closure.proto.arity,
+                        argument_count,
+                        span,
+                    ));
+                }
+                self.push_frame(closure, callee_slot, None)
+            }
+            Value::Method(method) => {
+                if method.closure.proto.arity != argument_count {
+                    return Err(RuntimeError::ArityMismatch(
+                        method.closure.proto.arity,
+                        argument_count,
+                        span,
+                    ));
+                }
+                let BoundMethod { this, closure } = method.as_ref();
+                self.stack[callee_slot] = Value::Instance(this.current.clone());
+                self.stack
+                    .insert(callee_slot + 1, Value::Instance(this.super_class.clone()));
+                let init_receiver =
+                    (closure.proto.name == INIT_FUNCTION_NAME).then(|| this.current.clone());
+                self.push_frame(closure.clone(), callee_slot, init_receiver)
+            }
+            Value::Class(class) => {
+                if class.arity() != argument_count {
+                    return Err(RuntimeError::ArityMismatch(
+                        class.arity(),
+                        argument_count,
+                        span,
+                    ));
+                }
+                let arguments = self.stack.split_off(callee_slot + 1);
+                let instance = self.instantiate(&class, arguments, &span)?;
+                self.stack[callee_slot] = Value::Instance(instance);
+                Ok(())
+            }
+            Value::Native(native) => {
+                let arity = match &native.function {
+                    NativeFunction::Shared(function) => function.arity(&self.native_state)?,
+                    NativeFunction::Collection(arity, _) => *arity,
+                };
+                if arity != argument_count {
+                    return Err(RuntimeError::ArityMismatch(arity, argument_count, span));
+                }
+                let arguments = self.stack.split_off(callee_slot + 1);
+                self.stack[callee_slot] = match &native.function {
+                    NativeFunction::Shared(function) => {
+                        let arguments = arguments
+                            .iter()
+                            .map(Value::to_runtime_value)
+                            .collect::<Vec<RuntimeValue>>();
+                        let result = function
+                            .call(arguments, None, &self.native_state)
+                            .map_err(|error| error.at(&span))?;
+                        Value::from_runtime_value(result)?
+                    }
+                    NativeFunction::Collection(_, function) => function(&arguments)
+                        .map_err(|error| RuntimeError::NativeFunctionError(error, span))?,
+                };
+                Ok(())
+            }
+            _ => Err(FunctionEvaluationError::UnCallableFunction(span).into()),
+        }
+    }
+
+    /// Builds an instance the way the tree-walker does: the superclass is
+    /// constructed first with as many leading arguments as its `init` takes,
+    /// then this class's methods are bound and `init`, looked up through the
+    /// whole chain, runs with every argument.
+    fn instantiate(
+        &mut self,
+        class: &Rc<Class>,
+        arguments: Vec<Value>,
+        span: &Span,
+    ) -> Result<Rc<Instance>> {
+        let super_class = class.super_class.borrow().clone();
+        let super_instance = match super_class {
+            Some(super_class) => {
+                let arity = super_class.arity();
+                if arity > arguments.len() {
+                    return Err(RuntimeError::ArityMismatch(
+                        arity,
+                        arguments.len(),
+                        span.clone(),
+                    ));
+                }
+                Some(self.instantiate(&super_class, arguments[..arity].to_vec(), span)?)
+            }
+            None => None,
+        };
+
+        let instance = Rc::new(Instance::new(class.name.clone(), super_instance));
+        let this = This::current(instance.clone());
+        for (name, method) in class.methods.borrow().iter() {
+            instance.set_method(name.clone(), method.clone(), &this);
+        }
+        Instance::map_this_pointer(&instance);
+
+        if let Some(Value::Method(init)) = instance.get(INIT_FUNCTION_NAME, 0)? {
+            self.call_method(init.closure.clone(), this, arguments, span)?;
+        }
+        Ok(instance)
+    }
+
+    /// Runs a method to completion from Rust, outside the caller's dispatch loop.
+    fn call_method(
+        &mut self,
+        closure: Rc<Closure>,
+        this: This,
+        arguments: Vec<Value>,
+        span: &Span,
+    ) -> Result<Value> {
+        if closure.proto.arity != arguments.len() {
+            return Err(RuntimeError::ArityMismatch(
+                closure.proto.arity,
+                arguments.len(),
+                span.clone(),
+            ));
+        }
+        let base = self.stack.len();
+        let init_receiver =
+            (closure.proto.name == INIT_FUNCTION_NAME).then(|| this.current.clone());
+        self.stack.push(Value::Instance(this.current));
+        self.stack.push(Value::Instance(this.super_class));
+        self.stack.extend(arguments);
+        let stop_depth = self.frames.len();
+        self.push_frame(closure, base, init_receiver)?;
+        self.run(stop_depth)
+    }
+
+    fn capture_upvalue(&mut self, slot: usize) -> Rc<RefCell<Upvalue>> {
+        let existing = self
+            .open_upvalues
+            .iter()
+            .find(|upvalue| matches!(&*upvalue.borrow(), Upvalue::Open(open) if *open == slot));
+        if let Some(existing) = existing {
+            return existing.clone();
+        }
+        let upvalue = Rc::new(RefCell::new(Upvalue::Open(slot)));
+        self.open_upvalues.push(upvalue.clone());
+        upvalue
+    }
+
+    /// Moves every captured variable at or above `from` off the stack.
+    fn close_upvalues(&mut self, from: usize) {
+        let stack = &self.stack;
+        self.open_upvalues.retain(|upvalue| {
+            let slot = match &*upvalue.borrow() {
+                Upvalue::Open(slot) if *slot >= from => *slot,
+                _ => return true,
+            };
+            upvalue.replace(Upvalue::Closed(stack[slot].clone()));
+            false
+        });
+    }
+}
+
+impl Value {
+    fn number_operands(self, right: Value, expected: &str) -> Result<(f64, f64)> {
+        match (self, right) {
+            (Value::Number(left), Value::Number(right)) => Ok((left, right)),
+            _ => Err(RuntimeError::BinaryOperandError(
+                expected.to_string(),
+                Span::default(),
+            )),
+        }
+    }
+
+    fn add(self, right: Value) -> Result<Value> {
+        match (self, right) {
+            (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left + right)),
+            (Value::String(left), Value::String(right)) => {
+                Ok(Value::String(Rc::from(format!("{}{}", left, right))))
+            }
+            _ => Err(RuntimeError::BinaryOperandError(
+                "two numbers or two strings".to_string(),
+                Span::default(),
+            )),
+        }
+    }
+
+    fn subtract(self, right: Value) -> Result<Value> {
+        let (left, right) = self.number_operands(right, "numbers")?;
+        Ok(Value::Number(left - right))
+    }
+
+    fn multiply(self, right: Value) -> Result<Value> {
+        let (left, right) = self.number_operands(right, "numbers")?;
+        Ok(Value::Number(left * right))
+    }
+
+    fn divide(self, right: Value) -> Result<Value> {
+        let (left, right) = self.number_operands(right, "numbers")?;
+        Ok(Value::Number(left / right))
+    }
+
+    fn gt(self, right: Value) -> Result<Value> {
+        let (left, right) = self.number_operands(right, "numbers")?;
+        Ok(Value::Boolean(left > right))
+    }
+
+    fn ge(self, right: Value) -> Result<Value> {
+        let (left, right) = self.number_operands(right, "numbers")?;
+        Ok(Value::Boolean(left >= right))
+    }
+
+    fn lt(self, right: Value) -> Result<Value> {
+        let (left, right) = self.number_operands(right, "numbers")?;
+        Ok(Value::Boolean(left < right))
+    }
+
+    fn le(self, right: Value) -> Result<Value> {
+        let (left, right) = self.number_operands(right, "numbers")?;
+        Ok(Value::Boolean(left <= right))
+    }
+}
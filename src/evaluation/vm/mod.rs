@@ -0,0 +1,98 @@
+mod chunk;
+mod compiler;
+mod machine;
+mod natives;
+mod value;
+
+use std::rc::Rc;
+
+pub use chunk::CompileError;
+use compiler::Compiler;
+use machine::Vm;
+use value::FunctionProto;
+
+use crate::evaluation::{run::InputSource, OutputSink, RuntimeError, SandboxPolicy, Session};
+use crate::syntax_analysis::ProgramAst;
+
+/// A program compiled to bytecode, run by the stack VM instead of the
+/// tree-walker.
+pub struct BytecodeProgram {
+    script: Rc<FunctionProto>,
+    vm: Vm,
+}
+
+impl BytecodeProgram {
+    /// The program is first built for the tree-walker, so that both backends
+    /// reject the same programs with the same resolver errors.
+    pub fn new(program_ast: &ProgramAst) -> Result<Self, RuntimeError> {
+        Session::new()?.build_program(program_ast)?;
+        let script = Compiler::compile(program_ast)?;
+        Ok(Self {
+            script,
+            vm: Vm::new(),
+        })
+    }
+
+    pub fn set_sandbox(&self, sandbox: SandboxPolicy) {
+        self.vm.set_sandbox(sandbox);
+    }
+
+    pub fn set_script_arguments(&self, arguments: Vec<String>) {
+        self.vm.set_script_arguments(arguments);
+    }
+
+    pub fn set_output(&self, output: Box<dyn OutputSink>) {
+        self.vm.set_output(output);
+    }
+
+    pub fn set_input(&self, input: Box<dyn InputSource>) {
+        self.vm.set_input(input);
+    }
+
+    pub fn run(&mut self) -> Result<(), RuntimeError> {
+        self.vm.interpret(self.script.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax_analysis::ParseStream;
+    use crate::tokenizer::scan_tokens;
+
+    fn run(source: &str) -> Result<(), RuntimeError> {
+        // Imports are resolved against the directory of the file
+        let filename = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/lox/modules/main.lox");
+        let (tokens, _) = scan_tokens(filename, source);
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        BytecodeProgram::new(&program)?.run()
+    }
+
+    #[test]
+    fn test_class_call_checks_arity() {
+        let error = run("class A {}\nA(1);").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected 0 arguments but got 1.\n[line 2]"
+        );
+        run("class A { init(a) {} }\nclass B < A {}\nB(1);").unwrap();
+    }
+
+    #[test]
+    fn test_reports_unsupported_imports() {
+        let error = run("import \"lib/shapes.lox\";").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Importing a module is not supported by the bytecode VM."
+        );
+    }
+
+    #[test]
+    fn test_reports_unsupported_exceptions() {
+        let error = run("try { throw 1; } catch (e) {}").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Exception handling is not supported by the bytecode VM."
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::evaluation::{run::NativeFunctionError, MapKey, Table};
+
+use super::value::{NativeFunction, Value};
+
+type NativeResult = Result<Value, NativeFunctionError>;
+
+/// The natives that change lists and maps or would otherwise copy them. They
+/// replace the tree-walker's natives of the same name.
+pub fn collection_natives() -> Vec<(&'static str, NativeFunction)> {
+    vec![
+        ("len", NativeFunction::Collection(1, len)),
+        ("push", NativeFunction::Collection(2, push)),
+        ("pop", NativeFunction::Collection(1, pop)),
+        ("insert", NativeFunction::Collection(3, insert)),
+        ("remove", NativeFunction::Collection(2, remove)),
+        ("keys", NativeFunction::Collection(1, keys)),
+        ("values", NativeFunction::Collection(1, values)),
+        ("has", NativeFunction::Collection(2, has)),
+        ("delete", NativeFunction::Collection(2, delete)),
+    ]
+}
+
+fn list(
+    arguments: &[Value],
+    index: usize,
+) -> Result<&Rc<RefCell<Vec<Value>>>, NativeFunctionError> {
+    match &arguments[index] {
+        Value::List(list) => Ok(list),
+        _ => Err(NativeFunctionError::InvalidArgument(index + 1, "a list")),
+    }
+}
+
+fn map(
+    arguments: &[Value],
+    index: usize,
+) -> Result<&Rc<RefCell<Table<Value>>>, NativeFunctionError> {
+    match &arguments[index] {
+        Value::Map(map) => Ok(map),
+        _ => Err(NativeFunctionError::InvalidArgument(index + 1, "a map")),
+    }
+}
+
+fn key(arguments: &[Value], index: usize) -> Result<MapKey, NativeFunctionError> {
+    arguments[index]
+        .to_map_key()
+        .ok_or(NativeFunctionError::InvalidArgument(
+            index + 1,
+            "a number, string, boolean or nil",
+        ))
+}
+
+fn position(arguments: &[Value], index: usize) -> Result<usize, NativeFunctionError> {
+    match &arguments[index] {
+        Value::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Ok(*number as usize),
+        _ => Err(NativeFunctionError::InvalidArgument(
+            index + 1,
+            "a non-negative integer",
+        )),
+    }
+}
+
+fn len(arguments: &[Value]) -> NativeResult {
+    let len = match &arguments[0] {
+        Value::String(string) => string.chars().count(),
+        Value::List(list) => list.borrow().len(),
+        Value::Map(map) => map.borrow().len(),
+        _ => {
+            return Err(NativeFunctionError::InvalidArgument(
+                1,
+                "a string, a list or a map",
+            ))
+        }
+    };
+    Ok(Value::Number(len as f64))
+}
+
+fn push(arguments: &[Value]) -> NativeResult {
+    list(arguments, 0)?.borrow_mut().push(arguments[1].clone());
+    Ok(Value::Nil)
+}
+
+fn pop(arguments: &[Value]) -> NativeResult {
+    list(arguments, 0)?
+        .borrow_mut()
+        .pop()
+        .ok_or(NativeFunctionError::EmptyList)
+}
+
+fn insert(arguments: &[Value]) -> NativeResult {
+    let list = list(arguments, 0)?;
+    let index = position(arguments, 1)?;
+    let mut elements = list.borrow_mut();
+    if index > elements.len() {
+        return Err(NativeFunctionError::IndexOutOfRange(index, elements.len()));
+    }
+    elements.insert(index, arguments[2].clone());
+    Ok(Value::Nil)
+}
+
+fn remove(arguments: &[Value]) -> NativeResult {
+    let list = list(arguments, 0)?;
+    let index = position(arguments, 1)?;
+    let mut elements = list.borrow_mut();
+    if index >= elements.len() {
+        return Err(NativeFunctionError::IndexOutOfRange(index, elements.len()));
+    }
+    Ok(elements.remove(index))
+}
+
+fn keys(arguments: &[Value]) -> NativeResult {
+    let keys = map(arguments, 0)?
+        .borrow()
+        .keys()
+        .map(Value::from_map_key)
+        .collect();
+    Ok(Value::List(Rc::new(RefCell::new(keys))))
+}
+
+fn values(arguments: &[Value]) -> NativeResult {
+    let values = map(arguments, 0)?.borrow().values().cloned().collect();
+    Ok(Value::List(Rc::new(RefCell::new(values))))
+}
+
+fn has(arguments: &[Value]) -> NativeResult {
+    let map = map(arguments, 0)?;
+    let key = key(arguments, 1)?;
+    let has = map.borrow().contains(&key);
+    Ok(Value::Boolean(has))
+}
+
+fn delete(arguments: &[Value]) -> NativeResult {
+    let map = map(arguments, 0)?;
+    let key = key(arguments, 1)?;
+    let removed = map.borrow_mut().remove(&key);
+    Ok(removed.unwrap_or(Value::Nil))
+}
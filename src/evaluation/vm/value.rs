@@ -0,0 +1,424 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Display,
+    rc::{Rc, Weak},
+};
+
+use crate::evaluation::{
+    evaluator::INIT_FUNCTION_NAME,
+    run::{Callable, NativeFunctionError},
+    runtime_value::{print_once, CallableType, ThisInstance},
+    List, Map, MapKey, RuntimeError, RuntimeValue, Table,
+};
+
+use super::chunk::Chunk;
+
+/// A compiled function. Methods reserve two slots before their parameters for
+/// `this` and `super`; every other function reserves one for itself.
+#[derive(Debug)]
+pub struct FunctionProto {
+    pub name: String,
+    /// The class a method is declared in.
+    pub class: Option<String>,
+    pub arity: usize,
+    pub upvalue_count: usize,
+    pub chunk: Chunk,
+}
+
+#[derive(Debug)]
+pub enum Upvalue {
+    Open(usize),
+    Closed(Value),
+}
+
+#[derive(Debug)]
+pub struct Closure {
+    pub proto: Rc<FunctionProto>,
+    pub upvalues: Vec<Rc<RefCell<Upvalue>>>,
+}
+
+#[derive(Debug)]
+pub struct Class {
+    pub name: Rc<str>,
+    pub super_class: RefCell<Option<Rc<Class>>>,
+    pub methods: RefCell<Vec<(Rc<str>, Rc<Closure>)>>,
+}
+
+impl Class {
+    /// Arity of the `init` that runs, the class's own or else the one it
+    /// inherits, which is how many arguments the class takes.
+    pub fn arity(&self) -> usize {
+        let init = self
+            .methods
+            .borrow()
+            .iter()
+            .find(|(name, _)| name.as_ref() == INIT_FUNCTION_NAME)
+            .map(|(_, closure)| closure.proto.arity);
+        match (init, self.super_class.borrow().as_ref()) {
+            (Some(arity), _) => arity,
+            (None, Some(super_class)) => super_class.arity(),
+            (None, None) => 0,
+        }
+    }
+}
+
+/// Which instance a bound method sees as `this` and which one `super` looks
+/// past, mirroring the tree-walker's `ThisInstance`.
+#[derive(Debug, Clone)]
+pub struct This {
+    pub current: Rc<Instance>,
+    pub super_class: Rc<Instance>,
+}
+
+impl This {
+    pub fn current(instance: Rc<Instance>) -> Self {
+        Self {
+            super_class: instance.clone(),
+            current: instance,
+        }
+    }
+
+    pub fn remap_to_super(&self, current: &Rc<Instance>) -> Self {
+        Self {
+            current: current.clone(),
+            super_class: self.current.clone(),
+        }
+    }
+
+    fn downgrade(&self) -> WeakThis {
+        WeakThis {
+            current: Rc::downgrade(&self.current),
+            super_class: Rc::downgrade(&self.super_class),
+        }
+    }
+}
+
+/// Methods stored on an instance point back at it (or at a subclass instance
+/// owning it), so they hold weak references to avoid reference cycles.
+#[derive(Debug)]
+pub struct WeakThis {
+    current: Weak<Instance>,
+    super_class: Weak<Instance>,
+}
+
+impl WeakThis {
+    fn upgrade(&self) -> Option<This> {
+        Some(This {
+            current: self.current.upgrade()?,
+            super_class: self.super_class.upgrade()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct BoundMethod {
+    pub this: This,
+    pub closure: Rc<Closure>,
+}
+
+#[derive(Debug)]
+pub enum Field {
+    Value(Value),
+    Method(Rc<Closure>, WeakThis),
+}
+
+/// An instance of one class in a chain: the fields and methods of its own
+/// class, and the instance built for its superclass.
+#[derive(Debug)]
+pub struct Instance {
+    pub class_name: Rc<str>,
+    pub fields: RefCell<HashMap<Rc<str>, Field>>,
+    pub super_instance: Option<Rc<Instance>>,
+}
+
+impl Instance {
+    pub fn new(class_name: Rc<str>, super_instance: Option<Rc<Instance>>) -> Self {
+        Self {
+            class_name,
+            fields: RefCell::new(HashMap::new()),
+            super_instance,
+        }
+    }
+
+    /// Looks `key` up in this instance and then up the superclass chain,
+    /// skipping the first `min_depth` instances.
+    pub fn get(&self, key: &str, min_depth: usize) -> Result<Option<Value>, RuntimeError> {
+        if min_depth > 0 {
+            return match &self.super_instance {
+                Some(super_instance) => super_instance.get(key, min_depth - 1),
+                None => Ok(None),
+            };
+        }
+        if let Some(field) = self.fields.borrow().get(key) {
+            return match field {
+                Field::Value(value) => Ok(Some(value.clone())),
+                Field::Method(closure, this) => {
+                    let this = this.upgrade().ok_or(RuntimeError::ThisNotInScope)?;
+                    Ok(Some(Value::Method(Rc::new(BoundMethod {
+                        this,
+                        closure: closure.clone(),
+                    }))))
+                }
+            };
+        }
+        match &self.super_instance {
+            Some(super_instance) => super_instance.get(key, 0),
+            None => Ok(None),
+        }
+    }
+
+    pub fn set(&self, key: Rc<str>, value: Value) {
+        self.fields.borrow_mut().insert(key, Field::Value(value));
+    }
+
+    pub fn set_method(&self, key: Rc<str>, closure: Rc<Closure>, this: &This) {
+        self.fields
+            .borrow_mut()
+            .insert(key, Field::Method(closure, this.downgrade()));
+    }
+
+    /// Rebinds every method stored on the superclass instances so that `this`
+    /// refers to `current`, the instance being constructed.
+    pub fn map_this_pointer(current: &Rc<Instance>) {
+        let mut super_instance = current.super_instance.clone();
+        while let Some(instance) = super_instance {
+            for field in instance.fields.borrow_mut().values_mut() {
+                match field {
+                    Field::Method(_, this) => {
+                        if let Some(old) = this.upgrade() {
+                            *this = old.remap_to_super(current).downgrade();
+                        }
+                    }
+                    Field::Value(Value::Method(method)) => {
+                        *method = Rc::new(BoundMethod {
+                            this: method.this.remap_to_super(current),
+                            closure: method.closure.clone(),
+                        });
+                    }
+                    Field::Value(_) => {}
+                }
+            }
+            super_instance = instance.super_instance.clone();
+        }
+    }
+}
+
+pub enum NativeFunction {
+    /// Shared with the tree-walker, which gets copies of the arguments.
+    Shared(Box<dyn Callable>),
+    /// Changes a list or map in place, so it needs the VM's own value rather
+    /// than a copy of it.
+    Collection(usize, fn(&[Value]) -> Result<Value, NativeFunctionError>),
+}
+
+pub struct Native {
+    pub name: String,
+    pub function: NativeFunction,
+}
+
+impl std::fmt::Debug for Native {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "native {{ name: {} }}", self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Value {
+    Nil,
+    Boolean(bool),
+    Number(f64),
+    String(Rc<str>),
+    Function(Rc<Closure>),
+    Method(Rc<BoundMethod>),
+    Class(Rc<Class>),
+    Instance(Rc<Instance>),
+    Native(Rc<Native>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<Table<Value>>>),
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::String(s) => write!(f, "{}", s),
+            Value::Function(closure) => write!(f, "<fn {}>", closure.proto.name),
+            Value::Method(method) => write!(f, "{}", method.closure.proto.name),
+            Value::Class(class) => write!(f, "{}", class.name),
+            Value::Instance(instance) => write!(f, "{} instance", instance.class_name),
+            Value::Native(native) => write!(f, "<fn {}>", native.name),
+            Value::List(list) => print_once(f, Rc::as_ptr(list).cast(), "[...]", |f| {
+                write!(f, "[")?;
+                for (i, element) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }),
+            Value::Map(map) => print_once(f, Rc::as_ptr(map).cast(), "{...}", |f| {
+                write!(f, "{{")?;
+                for (i, (key, value)) in map.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }),
+        }
+    }
+}
+
+impl PartialEq for Value {
+    /// Callables compare by declaration, as the tree-walker compares them by
+    /// function pointer.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) => true,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Function(a), Value::Function(b)) => Rc::ptr_eq(&a.proto, &b.proto),
+            (Value::Method(a), Value::Method(b)) => Rc::ptr_eq(&a.closure.proto, &b.closure.proto),
+            (Value::Class(a), Value::Class(b)) => Rc::ptr_eq(a, b),
+            (Value::Instance(a), Value::Instance(b)) => Rc::ptr_eq(a, b),
+            (Value::Native(a), Value::Native(b)) => Rc::ptr_eq(a, b),
+            (Value::List(a), Value::List(b)) => Rc::ptr_eq(a, b),
+            (Value::Map(a), Value::Map(b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl Value {
+    #[inline]
+    pub fn to_bool(&self) -> bool {
+        match self {
+            Value::Boolean(b) => *b,
+            Value::Nil => false,
+            _ => true,
+        }
+    }
+
+    /// The key this value is stored under in a map, if it can be one.
+    pub fn to_map_key(&self) -> Option<MapKey> {
+        match self {
+            Value::Nil => Some(MapKey::Nil),
+            Value::Boolean(b) => Some(MapKey::Boolean(*b)),
+            Value::Number(n) => Some(MapKey::number(*n)),
+            Value::String(s) => Some(MapKey::String(s.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn from_map_key(key: &MapKey) -> Self {
+        match key {
+            MapKey::Nil => Value::Nil,
+            MapKey::Boolean(b) => Value::Boolean(*b),
+            MapKey::Number(bits) => Value::Number(f64::from_bits(*bits)),
+            MapKey::String(s) => Value::String(s.clone()),
+        }
+    }
+
+    /// Native functions are shared with the tree-walker and only inspect the
+    /// values they are given, so callables and instances are passed with a
+    /// placeholder pointer that is never dereferenced, and lists and maps as
+    /// a copy.
+    pub fn to_runtime_value(&self) -> RuntimeValue {
+        self.copy_to_runtime_value(&mut HashMap::new())
+    }
+
+    /// Copies each list and map once, so the copy of one that contains itself
+    /// contains the copy. `copies` holds the ones copied so far.
+    fn copy_to_runtime_value(&self, copies: &mut HashMap<*const (), RuntimeValue>) -> RuntimeValue {
+        match self {
+            Value::Nil => RuntimeValue::Nil,
+            Value::Boolean(b) => RuntimeValue::Boolean(*b),
+            Value::Number(n) => RuntimeValue::Number(*n),
+            Value::String(s) => RuntimeValue::String(s.to_string()),
+            Value::Function(closure) => {
+                RuntimeValue::callable(0, closure.proto.name.clone(), None, CallableType::Function)
+            }
+            Value::Native(native) => {
+                RuntimeValue::callable(0, native.name.clone(), None, CallableType::Function)
+            }
+            Value::Method(method) => RuntimeValue::callable(
+                0,
+                method.closure.proto.name.clone(),
+                None,
+                CallableType::Method(ThisInstance::current(0)),
+            ),
+            Value::Class(class) => RuntimeValue::callable(
+                0,
+                class.name.to_string(),
+                None,
+                CallableType::ClassConstructor,
+            ),
+            Value::Instance(instance) => {
+                RuntimeValue::ClassInstance(0, instance.class_name.to_string())
+            }
+            Value::List(list) => {
+                let pointer = Rc::as_ptr(list).cast();
+                if let Some(copy) = copies.get(&pointer) {
+                    return copy.clone();
+                }
+                let copy = List::default();
+                copies.insert(pointer, RuntimeValue::List(copy.clone()));
+                let elements = list
+                    .borrow()
+                    .iter()
+                    .map(|element| element.copy_to_runtime_value(copies))
+                    .collect();
+                *copy.elements_mut() = elements;
+                RuntimeValue::List(copy)
+            }
+            Value::Map(map) => {
+                let pointer = Rc::as_ptr(map).cast();
+                if let Some(copy) = copies.get(&pointer) {
+                    return copy.clone();
+                }
+                let copy = Map::default();
+                copies.insert(pointer, RuntimeValue::Map(copy.clone()));
+                for (key, value) in map.borrow().iter() {
+                    let value = value.copy_to_runtime_value(copies);
+                    copy.entries_mut().insert(key.clone(), value);
+                }
+                RuntimeValue::Map(copy)
+            }
+        }
+    }
+
+    pub fn from_runtime_value(value: RuntimeValue) -> Result<Self, RuntimeError> {
+        match value {
+            RuntimeValue::Nil => Ok(Value::Nil),
+            RuntimeValue::Boolean(b) => Ok(Value::Boolean(b)),
+            RuntimeValue::Number(n) => Ok(Value::Number(n)),
+            RuntimeValue::String(s) => Ok(Value::String(Rc::from(s))),
+            RuntimeValue::List(list) => {
+                let elements = list
+                    .elements()
+                    .iter()
+                    .cloned()
+                    .map(Value::from_runtime_value)
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::List(Rc::new(RefCell::new(elements))))
+            }
+            RuntimeValue::Map(map) => {
+                let mut entries = Table::default();
+                for (key, value) in map.entries().iter() {
+                    entries.insert(key.clone(), Value::from_runtime_value(value.clone())?);
+                }
+                Ok(Value::Map(Rc::new(RefCell::new(entries))))
+            }
+            other => Err(RuntimeError::Unsupported(format!(
+                "Returning {} from a native function",
+                other
+            ))),
+        }
+    }
+}
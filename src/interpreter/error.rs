@@ -0,0 +1,44 @@
+use thiserror::Error;
+
+use crate::error::InterpreterError;
+use crate::evaluation::{NativeFunctionError, RuntimeError};
+
+/// Any error reported by an [`Interpreter`](super::Interpreter): invalid
+/// source, a runtime error or a file that couldn't be read.
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct Error(#[from] InterpreterError);
+
+impl Error {
+    /// An error for a native function registered by the host to raise. It is
+    /// reported at the line of the call.
+    pub fn message(message: impl Into<String>) -> Self {
+        NativeFunctionError::Host(message.into()).into()
+    }
+
+    /// The exit code the command line interpreter uses for this error.
+    pub fn exit_code(&self) -> i32 {
+        self.0.exit_code()
+    }
+
+    /// The error as raised inside the interpreter, for errors returned by a
+    /// host native function.
+    pub(crate) fn into_runtime_error(self) -> RuntimeError {
+        match self.0 {
+            InterpreterError::Runtime(error) => error,
+            other => NativeFunctionError::Host(other.to_string()).into(),
+        }
+    }
+}
+
+impl From<RuntimeError> for Error {
+    fn from(error: RuntimeError) -> Self {
+        Self(error.into())
+    }
+}
+
+impl From<NativeFunctionError> for Error {
+    fn from(error: NativeFunctionError) -> Self {
+        RuntimeError::from(error).into()
+    }
+}
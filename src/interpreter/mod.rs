@@ -0,0 +1,183 @@
+mod error;
+mod value;
+
+use std::path::Path;
+
+pub use error::Error;
+
+use crate::error::InterpreterError;
+use crate::evaluation::{OutputSink, RuntimeValue, SandboxPolicy, Session};
+use crate::syntax_analysis::{Expression, ParseStream, ProgramAst};
+use crate::tokenizer::{scan_tokens, Token};
+
+/// A Lox interpreter to embed in a Rust program. Globals, functions and
+/// classes defined by one call stay defined for the next ones.
+///
+/// ```
+/// # use codecrafters_interpreter::{Error, Interpreter};
+/// # fn main() -> Result<(), Error> {
+/// let interpreter = Interpreter::new()?;
+/// interpreter.register_native("double", 1, |arguments| {
+///     Ok((f64::try_from(&arguments[0])? * 2.0).into())
+/// })?;
+/// interpreter.define_global("answer", 21.0)?;
+/// assert_eq!(interpreter.eval_str("double(answer)")?, 42.0.into());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Interpreter {
+    session: Session,
+}
+
+impl Interpreter {
+    pub fn new() -> Result<Self, Error> {
+        Ok(Self {
+            session: Session::new()?,
+        })
+    }
+
+    /// Sets what the file natives may access. Nothing is allowed by default.
+    pub fn set_sandbox(&self, sandbox: SandboxPolicy) {
+        self.session.set_sandbox(sandbox);
+    }
+
+    /// Sets what the `args` native returns. It returns an empty list by
+    /// default.
+    pub fn set_script_arguments(&self, arguments: Vec<String>) {
+        self.session.set_script_arguments(arguments);
+    }
+
+    /// Sends what the program prints to `output` instead of standard output.
+    pub fn set_output(&self, output: impl OutputSink + 'static) {
+        self.session.set_output(Box::new(output));
+    }
+
+    /// Runs `source`. A lone expression is evaluated and its value returned;
+    /// anything else is run as a program and returns `nil`.
+    pub fn eval_str(&self, source: &str) -> Result<RuntimeValue, Error> {
+        let tokens = Self::scan("<eval>", source)?;
+        let mut stream = ParseStream::new(tokens.clone());
+        if let Ok(expression) = stream.parse::<Expression>() {
+            if stream.is_at_end() {
+                return Ok(self.session.evaluate(&expression)?);
+            }
+        }
+        self.run_tokens(tokens)?;
+        Ok(RuntimeValue::Nil)
+    }
+
+    /// Runs the program in the file at `path`.
+    pub fn run_file(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref().to_string_lossy();
+        let source = std::fs::read_to_string(path.as_ref())
+            .map_err(|error| InterpreterError::io_error(path.to_string(), error))?;
+        let tokens = Self::scan(&path, &source)?;
+        self.run_tokens(tokens)
+    }
+
+    /// Defines a global variable visible to everything run afterwards.
+    pub fn define_global(&self, name: &str, value: impl Into<RuntimeValue>) -> Result<(), Error> {
+        Ok(self.session.define_global(name, value.into())?)
+    }
+
+    /// Defines a global function taking `arity` arguments that runs
+    /// `function`. Errors it returns are reported at the line of the call.
+    pub fn register_native<F>(&self, name: &str, arity: usize, function: F) -> Result<(), Error>
+    where
+        F: Fn(&[RuntimeValue]) -> Result<RuntimeValue, Error> + 'static,
+    {
+        let function = Box::new(move |arguments: &[RuntimeValue]| {
+            function(arguments).map_err(Error::into_runtime_error)
+        });
+        Ok(self.session.register_native(name, arity, function)?)
+    }
+
+    fn scan(name: &str, source: &str) -> Result<Vec<Token>, Error> {
+        let (tokens, errors) = scan_tokens(name, source);
+        match errors.into_iter().next() {
+            Some(error) => Err(InterpreterError::from(error).into()),
+            None => Ok(tokens),
+        }
+    }
+
+    fn run_tokens(&self, tokens: Vec<Token>) -> Result<(), Error> {
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).map_err(|errors| {
+            let first = errors.into_iter().next();
+            InterpreterError::from(first.expect("parse_all reports at least one error"))
+        })?;
+        let runnable = self.session.build_program(&program)?;
+        Ok(self.session.run(runnable.as_ref())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evaluation::BufferSink;
+
+    #[test]
+    fn test_host_natives_and_globals() {
+        let interpreter = Interpreter::new().unwrap();
+        interpreter
+            .register_native("double", 1, |arguments| {
+                Ok((f64::try_from(&arguments[0])? * 2.0).into())
+            })
+            .unwrap();
+        interpreter.define_global("answer", 21.0).unwrap();
+        interpreter
+            .eval_str("var result = double(answer);")
+            .unwrap();
+        assert_eq!(
+            interpreter.eval_str("result").unwrap(),
+            RuntimeValue::Number(42.0)
+        );
+
+        let error = interpreter.eval_str("double(\"x\");").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected a number but got string.\n[line 1]"
+        );
+    }
+
+    #[test]
+    fn test_print_writes_to_output_sink() {
+        let interpreter = Interpreter::new().unwrap();
+        let output = BufferSink::new();
+        interpreter.set_output(output.clone());
+        interpreter
+            .eval_str("for (var i = 0; i < 2; i = i + 1) print i;")
+            .unwrap();
+        assert_eq!(output.contents(), "0\n1\n");
+    }
+
+    #[test]
+    fn test_globals_after_error_in_loop() {
+        let interpreter = Interpreter::new().unwrap();
+        interpreter
+            .eval_str("var x = 1; fun show() { return x; }")
+            .unwrap();
+        interpreter
+            .eval_str(
+                "fun f() { for (var i = 0; i < 2; i = i + 1) { var x = 2; { nil(); } } } f();",
+            )
+            .unwrap_err();
+        interpreter.eval_str("var x = 3;").unwrap();
+        assert_eq!(
+            interpreter.eval_str("show()").unwrap(),
+            RuntimeValue::Number(3.0)
+        );
+    }
+
+    #[test]
+    fn test_assigning_undefined_variable_is_runtime_error() {
+        let interpreter = Interpreter::new().unwrap();
+        let error = interpreter.eval_str("nope = 1;").unwrap_err();
+        // 70 is the exit code of runtime errors
+        assert_eq!(error.exit_code(), 70);
+        assert_eq!(error.to_string(), "Undefined variable 'nope'.\nLine: 1");
+        assert_eq!(
+            interpreter.eval_str("1 + 1").unwrap(),
+            RuntimeValue::Number(2.0)
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use super::Error;
+
+use crate::evaluation::{NativeFunctionError, RuntimeValue};
+
+impl From<f64> for RuntimeValue {
+    fn from(number: f64) -> Self {
+        RuntimeValue::Number(number)
+    }
+}
+
+impl From<bool> for RuntimeValue {
+    fn from(boolean: bool) -> Self {
+        RuntimeValue::Boolean(boolean)
+    }
+}
+
+impl From<String> for RuntimeValue {
+    fn from(string: String) -> Self {
+        RuntimeValue::String(string)
+    }
+}
+
+impl From<&str> for RuntimeValue {
+    fn from(string: &str) -> Self {
+        RuntimeValue::String(string.to_string())
+    }
+}
+
+impl From<()> for RuntimeValue {
+    fn from(_: ()) -> Self {
+        RuntimeValue::Nil
+    }
+}
+
+impl<T: Into<RuntimeValue>> From<Option<T>> for RuntimeValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(RuntimeValue::Nil, Into::into)
+    }
+}
+
+fn type_mismatch(expected: &'static str, value: &RuntimeValue) -> Error {
+    NativeFunctionError::TypeMismatch(expected, value.type_name()).into()
+}
+
+impl TryFrom<&RuntimeValue> for f64 {
+    type Error = Error;
+
+    fn try_from(value: &RuntimeValue) -> Result<Self, Self::Error> {
+        match value {
+            RuntimeValue::Number(number) => Ok(*number),
+            other => Err(type_mismatch("a number", other)),
+        }
+    }
+}
+
+impl TryFrom<&RuntimeValue> for bool {
+    type Error = Error;
+
+    fn try_from(value: &RuntimeValue) -> Result<Self, Self::Error> {
+        match value {
+            RuntimeValue::Boolean(boolean) => Ok(*boolean),
+            other => Err(type_mismatch("a boolean", other)),
+        }
+    }
+}
+
+impl TryFrom<&RuntimeValue> for String {
+    type Error = Error;
+
+    fn try_from(value: &RuntimeValue) -> Result<Self, Self::Error> {
+        match value {
+            RuntimeValue::String(string) => Ok(string.clone()),
+            other => Err(type_mismatch("a string", other)),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+//! A Lox interpreter. Embed it through [`Interpreter`]; the command line
+//! interpreter is a thin client of this crate.
+
+#[doc(hidden)]
+pub mod commands;
+mod common;
+mod error;
+mod evaluation;
+mod interpreter;
+mod lsp;
+mod syntax_analysis;
+mod tokenizer;
+
+pub use evaluation::{
+    BufferSink, LineCallbackSink, List, Map, MapKey, OutputSink, RuntimeValue, SandboxPolicy,
+    StdoutSink,
+};
+pub use interpreter::{Error, Interpreter};
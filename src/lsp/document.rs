@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+use serde_json::{json, Value};
+
+use crate::evaluation::{Analyzer, Session, Symbols};
+use crate::syntax_analysis::{ParseStream, ProgramAst};
+use crate::tokenizer::{scan_tokens, Span, TokenEnum};
+
+const ERROR: u8 = 1;
+const WARNING: u8 = 2;
+
+/// An error or warning found in a document
+pub struct Diagnostic {
+    /// Where it points at; errors at the end of the input have no span.
+    pub span: Option<Span>,
+    pub message: String,
+    pub severity: u8,
+}
+
+impl Diagnostic {
+    fn new(message: impl Display, span: Option<&Span>, severity: u8) -> Self {
+        let message = message.to_string();
+        // The position is already given by the range
+        let message = match message.strip_prefix("[line ") {
+            Some(rest) => rest
+                .split_once("] ")
+                .map_or(message.clone(), |(_, message)| message.to_string()),
+            None => message,
+        };
+        Self {
+            span: span.cloned(),
+            message,
+            severity,
+        }
+    }
+}
+
+/// An identifier in the text of a document, which is completed from when the
+/// text doesn't parse
+pub struct Word {
+    pub name: String,
+    /// The byte offset it ends at
+    pub end: usize,
+    /// Whether it follows a `.`, as the name of a property or method does
+    pub property: bool,
+}
+
+/// An open document and what was found analyzing its text
+pub struct Document {
+    pub text: String,
+    /// The byte offset each line starts at
+    line_starts: Vec<usize>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub symbols: Symbols,
+    /// Whether `symbols` are for the current text. A document that doesn't
+    /// parse keeps the symbols of the last text that did, for completion.
+    pub up_to_date: bool,
+    pub words: Vec<Word>,
+}
+
+impl Document {
+    pub fn new(uri: &str, text: String) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        let mut document = Self {
+            text,
+            line_starts,
+            diagnostics: Vec::new(),
+            symbols: Symbols::default(),
+            up_to_date: false,
+            words: Vec::new(),
+        };
+        document.analyze(uri);
+        document
+    }
+
+    fn analyze(&mut self, uri: &str) {
+        let (tokens, scanner_errors) = scan_tokens(uri, &self.text);
+        for (index, token) in tokens.iter().enumerate() {
+            if token.token_type == TokenEnum::Identifier {
+                let previous = index.checked_sub(1).map(|index| &tokens[index]);
+                self.words.push(Word {
+                    name: token.lexeme.clone(),
+                    end: token.span.end,
+                    property: previous.is_some_and(|token| token.token_type == TokenEnum::Dot),
+                });
+            }
+        }
+        for error in &scanner_errors {
+            let diagnostic = Diagnostic::new(error, Some(error.span()), ERROR);
+            self.diagnostics.push(diagnostic);
+        }
+        let program = match ProgramAst::parse_all(&mut ParseStream::new(tokens)) {
+            Ok(program) => program,
+            Err(errors) => {
+                for error in &errors {
+                    self.diagnostics
+                        .push(Diagnostic::new(error, error.span(), ERROR));
+                }
+                return;
+            }
+        };
+        if !scanner_errors.is_empty() {
+            return;
+        }
+
+        let errors = match Session::new() {
+            Ok(session) => session.build_errors(&program),
+            Err(error) => vec![error],
+        };
+        for error in &errors {
+            self.diagnostics
+                .push(Diagnostic::new(error, error.span(), ERROR));
+        }
+        if errors.is_empty() {
+            for warning in Analyzer::analyze(&program).unwrap_or_default() {
+                let diagnostic = Diagnostic::new(&warning, Some(warning.span()), WARNING);
+                self.diagnostics.push(diagnostic);
+            }
+        }
+        if let Ok(symbols) = Analyzer::symbols(&program) {
+            self.symbols = symbols;
+            self.up_to_date = true;
+        }
+    }
+
+    /// The LSP position of a byte offset: a line and a character counted in
+    /// UTF-16 code units, both from 0.
+    pub fn position(&self, offset: usize) -> Value {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let character = self.text[self.line_starts[line]..offset]
+            .encode_utf16()
+            .count();
+        json!({ "line": line, "character": character })
+    }
+
+    pub fn range(&self, span: Option<&Span>) -> Value {
+        let (start, end) = span.map_or((self.text.len(), self.text.len()), |span| {
+            (span.start, span.end)
+        });
+        json!({ "start": self.position(start), "end": self.position(end) })
+    }
+
+    /// The byte offset of an LSP position. Positions past the end of a line
+    /// are at its end.
+    pub fn offset(&self, position: &Value) -> Option<usize> {
+        let line = position["line"].as_u64()? as usize;
+        let character = position["character"].as_u64()? as usize;
+        let Some(&start) = self.line_starts.get(line) else {
+            return Some(self.text.len());
+        };
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.text.len(), |&next| next - 1);
+
+        let mut units = 0;
+        for (index, char) in self.text[start..end].char_indices() {
+            if units >= character {
+                return Some(start + index);
+            }
+            units += char.len_utf16();
+        }
+        Some(end)
+    }
+}
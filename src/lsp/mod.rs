@@ -0,0 +1,23 @@
+//! A language server for Lox, speaking the Language Server Protocol over a
+//! pair of streams. Documents are analyzed with the same scanner, parser,
+//! resolver and analyzer the `check` command uses.
+
+mod document;
+mod server;
+mod transport;
+
+use thiserror::Error;
+
+pub use server::Server;
+
+#[derive(Error, Debug)]
+pub enum LspError {
+    #[error("Language server connection failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Language server message has no Content-Length header")]
+    MissingContentLength,
+
+    #[error("Language server message is not valid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+}
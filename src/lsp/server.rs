@@ -0,0 +1,446 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, Write};
+
+use serde_json::{json, Value};
+
+use super::document::Document;
+use super::transport::{read_message, write_message};
+use super::LspError;
+use crate::evaluation::SymbolKind;
+use crate::tokenizer::Span;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const KEYWORDS: [&str; 23] = [
+    "and", "break", "catch", "class", "continue", "else", "false", "finally", "for", "fun", "if",
+    "import", "nil", "or", "print", "return", "super", "this", "throw", "true", "try", "var",
+    "while",
+];
+
+/// The `CompletionItemKind` of a name only known from the text
+const TEXT_COMPLETION: u8 = 1;
+
+/// The `CompletionItemKind` of a symbol
+fn completion_kind(kind: SymbolKind) -> u8 {
+    match kind {
+        SymbolKind::Method => 2,
+        SymbolKind::Function | SymbolKind::Native => 3,
+        SymbolKind::GlobalVariable | SymbolKind::LocalVariable | SymbolKind::Parameter => 6,
+        SymbolKind::Class => 7,
+    }
+}
+
+/// Serves one client. Documents are kept in memory as the client sends them;
+/// every change is analyzed again in full and its diagnostics published.
+pub struct Server<W: Write> {
+    output: W,
+    documents: HashMap<String, Document>,
+    shut_down: bool,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            documents: HashMap::new(),
+            shut_down: false,
+        }
+    }
+
+    /// Handles messages from `input` until the client exits or closes it.
+    pub fn serve(&mut self, input: &mut impl BufRead) -> Result<(), LspError> {
+        loop {
+            let message = match read_message(input) {
+                Ok(Some(message)) => message,
+                Ok(None) => return Ok(()),
+                Err(LspError::Json(error)) => {
+                    self.respond_error(Value::Null, PARSE_ERROR, error.to_string())?;
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+
+            let method = message["method"].as_str().unwrap_or_default();
+            let params = &message["params"];
+            if method == "exit" {
+                return Ok(());
+            }
+            let Some(id) = message.get("id") else {
+                self.notify(method, params)?;
+                continue;
+            };
+            if self.shut_down {
+                self.respond_error(id.clone(), INVALID_REQUEST, "Server is shut down")?;
+                continue;
+            }
+            match self.request(method, params) {
+                Ok(result) => {
+                    let response = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+                    write_message(&mut self.output, &response)?;
+                }
+                Err((code, message)) => self.respond_error(id.clone(), code, message)?,
+            }
+        }
+    }
+
+    fn respond_error(
+        &mut self,
+        id: Value,
+        code: i64,
+        message: impl Into<String>,
+    ) -> Result<(), LspError> {
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": code, "message": message.into() },
+        });
+        write_message(&mut self.output, &response)
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) -> Result<(), LspError> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.open(uri, text.to_string())
+            }
+            "textDocument/didChange" => {
+                // Changes are whole documents, as the server asks for
+                let changes = params["contentChanges"].as_array();
+                match changes.and_then(|changes| changes.last()) {
+                    Some(change) => {
+                        let text = change["text"].as_str().unwrap_or_default();
+                        self.open(uri, text.to_string())
+                    }
+                    None => Ok(()),
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                self.publish(uri, json!([]))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn open(&mut self, uri: &str, text: String) -> Result<(), LspError> {
+        let mut document = Document::new(uri, text);
+        if !document.up_to_date {
+            if let Some(previous) = self.documents.remove(uri) {
+                document.symbols = previous.symbols;
+            }
+        }
+
+        let diagnostics = document
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                json!({
+                    "range": document.range(diagnostic.span.as_ref()),
+                    "severity": diagnostic.severity,
+                    "source": "lox",
+                    "message": diagnostic.message,
+                })
+            })
+            .collect();
+        self.documents.insert(uri.to_string(), document);
+        self.publish(uri, Value::Array(diagnostics))
+    }
+
+    fn publish(&mut self, uri: &str, diagnostics: Value) -> Result<(), LspError> {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        });
+        write_message(&mut self.output, &notification)
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "completionProvider": { "triggerCharacters": ["."] },
+                },
+                "serverInfo": { "name": "lox" },
+            })),
+            "shutdown" => {
+                self.shut_down = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => self.with_position(params, Self::definition),
+            "textDocument/references" => self.with_position(params, Self::references),
+            "textDocument/hover" => self.with_position(params, Self::hover),
+            "textDocument/completion" => self.with_position(params, Self::completion),
+            _ => Err((METHOD_NOT_FOUND, format!("Unhandled method {}", method))),
+        }
+    }
+
+    /// Runs a request about a position in an open document.
+    fn with_position(
+        &self,
+        params: &Value,
+        handle: fn(&Document, &str, usize, &Value) -> Value,
+    ) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let Some(document) = self.documents.get(uri) else {
+            return Err((INVALID_PARAMS, format!("{} is not open", uri)));
+        };
+        let offset = document
+            .offset(&params["position"])
+            .ok_or((INVALID_PARAMS, "Invalid position".to_string()))?;
+        Ok(handle(document, uri, offset, params))
+    }
+
+    fn location(document: &Document, uri: &str, span: &Span) -> Value {
+        json!({ "uri": uri, "range": document.range(Some(span)) })
+    }
+
+    fn definition(document: &Document, uri: &str, offset: usize, _: &Value) -> Value {
+        let symbols = &document.symbols;
+        document
+            .up_to_date
+            .then(|| symbols.find(offset))
+            .flatten()
+            .and_then(|definition| symbols.definitions[definition].span.as_ref())
+            .map_or(Value::Null, |span| Self::location(document, uri, span))
+    }
+
+    fn references(document: &Document, uri: &str, offset: usize, params: &Value) -> Value {
+        let symbols = &document.symbols;
+        let Some(definition) = document.up_to_date.then(|| symbols.find(offset)).flatten() else {
+            return Value::Null;
+        };
+        let include_declaration = params["context"]["includeDeclaration"]
+            .as_bool()
+            .unwrap_or(true);
+        let declaration = symbols.definitions[definition]
+            .span
+            .as_ref()
+            .filter(|_| include_declaration);
+        declaration
+            .into_iter()
+            .chain(symbols.references(definition))
+            .map(|span| Self::location(document, uri, span))
+            .collect()
+    }
+
+    fn hover(document: &Document, _: &str, offset: usize, _: &Value) -> Value {
+        let symbols = &document.symbols;
+        let Some(definition) = document.up_to_date.then(|| symbols.find(offset)).flatten() else {
+            return Value::Null;
+        };
+        let definition = &symbols.definitions[definition];
+        json!({
+            "contents": {
+                "kind": "plaintext",
+                "value": format!("({}) {}", definition.kind, definition.detail),
+            }
+        })
+    }
+
+    /// Completes the name being typed at `offset`: a method after a `.`, or
+    /// else a name in scope or a keyword. Until the text parses again, the
+    /// names it uses are offered too.
+    fn completion(document: &Document, _: &str, offset: usize, _: &Value) -> Value {
+        let before = &document.text[..offset];
+        let word_start = before
+            .trim_end_matches(|char: char| char.is_ascii_alphanumeric() || char == '_')
+            .len();
+        let symbols = &document.symbols;
+
+        let mut seen = HashSet::new();
+        let mut items = Vec::new();
+        // The name being typed isn't offered back
+        let words = document
+            .words
+            .iter()
+            .filter(|word| !document.up_to_date && word.end != offset);
+        if before[..word_start].ends_with('.') {
+            for method in symbols.methods() {
+                if seen.insert(method.name.as_str()) {
+                    let kind = completion_kind(method.kind);
+                    items.push(json!({ "label": method.name, "kind": kind }));
+                }
+            }
+            for word in words.filter(|word| word.property) {
+                if seen.insert(word.name.as_str()) {
+                    items.push(json!({ "label": word.name, "kind": TEXT_COMPLETION }));
+                }
+            }
+            return Value::Array(items);
+        }
+
+        // Later definitions are the innermost, so they come first
+        let visible = symbols.visible_at(offset).collect::<Vec<_>>();
+        for definition in visible.into_iter().rev() {
+            if seen.insert(definition.name.as_str()) {
+                items.push(json!({
+                    "label": definition.name,
+                    "kind": completion_kind(definition.kind),
+                    "detail": definition.detail,
+                }));
+            }
+        }
+        for word in words.filter(|word| !word.property) {
+            if seen.insert(word.name.as_str()) {
+                items.push(json!({ "label": word.name, "kind": TEXT_COMPLETION }));
+            }
+        }
+        for keyword in KEYWORDS {
+            items.push(json!({ "label": keyword, "kind": 14 }));
+        }
+        Value::Array(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const URI: &str = "file:///main.lox";
+
+    /// Sends `messages` to a server as a client would and returns what it
+    /// wrote back.
+    fn exchange(messages: &[Value]) -> Vec<Value> {
+        let mut input = Vec::new();
+        for message in messages {
+            write_message(&mut input, message).unwrap();
+        }
+        let mut output = Vec::new();
+        Server::new(&mut output)
+            .serve(&mut Cursor::new(input))
+            .unwrap();
+
+        let mut output = Cursor::new(output);
+        std::iter::from_fn(|| read_message(&mut output).unwrap()).collect()
+    }
+
+    fn open(text: &str) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": URI, "text": text } },
+        })
+    }
+
+    fn request(id: u64, method: &str, line: u64, character: u64) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": {
+                "textDocument": { "uri": URI },
+                "position": { "line": line, "character": character },
+                "context": { "includeDeclaration": true },
+            },
+        })
+    }
+
+    fn start(value: &Value) -> (u64, u64) {
+        let start = &value["range"]["start"];
+        (
+            start["line"].as_u64().unwrap(),
+            start["character"].as_u64().unwrap(),
+        )
+    }
+
+    fn labels(response: &Value) -> Vec<&str> {
+        response["result"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_publishes_diagnostics() {
+        let responses = exchange(&[
+            open("var a = 1;\nprint a +;\nfun f(b) {}\n"),
+            open("{ var a = 1; var a = 2; }\nfun f(b) {}\nf();\n"),
+        ]);
+        let diagnostics = |index: usize| {
+            let diagnostics = responses[index]["params"]["diagnostics"].as_array();
+            diagnostics.unwrap().clone()
+        };
+
+        let syntax = diagnostics(0);
+        assert_eq!(syntax.len(), 1);
+        assert_eq!(syntax[0]["message"], "Error at ';': Expect 'expression'.");
+        assert_eq!(start(&syntax[0]), (1, 9));
+
+        let resolver = diagnostics(1);
+        assert_eq!(resolver.len(), 1);
+        assert_eq!(
+            resolver[0]["message"],
+            "Error at 'a': Already a variable with this name in this scope."
+        );
+        assert_eq!(start(&resolver[0]), (0, 17));
+    }
+
+    #[test]
+    fn test_navigates_to_declarations() {
+        let source =
+            "fun add(a, b) {\n    var sum = a + b;\n    return sum;\n}\nprint add(1, 2);\n";
+        let responses = exchange(&[
+            open(source),
+            request(1, "textDocument/definition", 2, 12),
+            request(2, "textDocument/references", 0, 5),
+            request(3, "textDocument/hover", 4, 7),
+            request(4, "textDocument/hover", 1, 15),
+        ]);
+
+        assert_eq!(start(&responses[1]["result"]), (1, 8));
+        let references = responses[2]["result"].as_array().unwrap();
+        let references = references.iter().map(start).collect::<Vec<_>>();
+        assert_eq!(references, vec![(0, 4), (4, 6)]);
+        let hover = |index: usize| responses[index]["result"]["contents"]["value"].clone();
+        assert_eq!(hover(3), "(function) fun add(a, b)");
+        assert_eq!(hover(4), "(parameter) a");
+    }
+
+    #[test]
+    fn test_completes_names_in_scope() {
+        let source = "class A {\n    get() {}\n}\nfun f(a) {\n    var b = a;\n}\nvar c = A();\n";
+        let responses = exchange(&[
+            open(source),
+            request(1, "textDocument/completion", 4, 14),
+            request(2, "textDocument/completion", 6, 12),
+            open(&format!("{}c.\n", source)),
+            request(3, "textDocument/completion", 7, 2),
+        ]);
+
+        let in_function = labels(&responses[1]);
+        assert!(in_function.starts_with(&["b", "a", "c", "f", "A"]));
+        assert!(in_function.contains(&"clock") && in_function.contains(&"while"));
+        let at_top_level = labels(&responses[2]);
+        assert!(!at_top_level.contains(&"a") && !at_top_level.contains(&"b"));
+        // The last document didn't parse, so the symbols are the previous ones
+        assert_eq!(labels(&responses[4]), vec!["get"]);
+    }
+
+    #[test]
+    fn test_completes_names_in_text_that_never_parsed() {
+        let source = "var total = 1;\nfun add(a) {\n    point.size = a;\n    to";
+        let responses = exchange(&[
+            open(source),
+            request(1, "textDocument/completion", 3, 6),
+            open("point.size = 1;\npoint."),
+            request(2, "textDocument/completion", 1, 6),
+        ]);
+
+        let names = labels(&responses[1]);
+        assert!(names.starts_with(&["total", "add", "a", "point", "and"]));
+        assert!(!names.contains(&"to") && !names.contains(&"size"));
+        assert_eq!(labels(&responses[3]), vec!["size"]);
+    }
+}
@@ -0,0 +1,49 @@
+use std::io::{BufRead, Write};
+
+use serde_json::Value;
+
+use super::LspError;
+
+/// Reads one message, framed by a `Content-Length` header. Returns `None` when
+/// the input is closed between messages.
+pub fn read_message(input: &mut impl BufRead) -> Result<Option<Value>, LspError> {
+    let mut content_length = None;
+    let mut at_start = true;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            if at_start {
+                return Ok(None);
+            }
+            return Err(LspError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+        }
+        at_start = false;
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let content_length = content_length.ok_or(LspError::MissingContentLength)?;
+    let mut content = vec![0; content_length];
+    input.read_exact(&mut content)?;
+    Ok(Some(serde_json::from_slice(&content)?))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Value) -> Result<(), LspError> {
+    let content = message.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()?;
+    Ok(())
+}
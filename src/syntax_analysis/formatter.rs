@@ -0,0 +1,743 @@
+use std::cell::{Cell, RefCell};
+
+use crate::common::{Visitable, VisitorWithContext};
+use crate::syntax_analysis::{
+    AccessorOrArgumentsType, Arguments, Assignment, Block, BreakStatement, Call, ClassDeclaration,
+    Comparison, ContinueStatement, Declaration, DeclarationType, Equality, ExprStatement, Factor,
+    ForStatement, ForStatementType, Function, FunctionDeclaration, IfStatement, ImportDeclaration,
+    Index, LambdaBody, LogicalAnd, LogicalOr, Parameters, PrimaryExpression, PrimaryExpressionType,
+    PrintStatement, ProgramAst, ReturnStatement, Statement, StatementType, Term, ThrowStatement,
+    TryStatement, UnaryExpression, UnaryExpressionSelf, UnaryExpressionType, VarDeclaration,
+    WhileStatement,
+};
+use crate::tokenizer::{Comment, Span, Token, TokenEnum};
+
+const INDENT: &str = "    ";
+
+/// Prints a parsed program back in the canonical style: four-space indents,
+/// one declaration per line, opening braces on the line they belong to and
+/// single spaces around binary operators. At most one blank line between
+/// declarations is kept.
+///
+/// Comments are put back before the token that followed them, or at the end
+/// of the line when they followed code on the same line in the source.
+pub struct Formatter;
+
+pub struct FormatterContext {
+    output: RefCell<String>,
+    comments: Vec<Comment>,
+    next_comment: Cell<usize>,
+    indent: Cell<usize>,
+    at_line_start: Cell<bool>,
+    /// Whether the current line has ended without its `\n` written yet, so
+    /// that the comments that followed its code in the source can still be
+    /// put at its end
+    line_break_pending: Cell<bool>,
+    /// The source line the last printed token or comment ends on
+    last_line: Cell<usize>,
+    /// Whether a blank line before the next thing printed may be kept
+    blank_line_allowed: Cell<bool>,
+}
+
+impl Formatter {
+    /// Formats `program`, putting back `comments`, which are the comments of
+    /// the tokens it was parsed from.
+    pub fn format(program: &ProgramAst, comments: Vec<Comment>) -> String {
+        let context = FormatterContext::new(comments);
+        program.accept_with_context(&Self, &context);
+        context.comments_before(None);
+        context.newline();
+        context.break_line();
+        context.output.take()
+    }
+
+    fn declarations<T>(declarations: &[(T, Declaration)], context: &FormatterContext) {
+        for (position, (_, declaration)) in declarations.iter().enumerate() {
+            context.blank_line_allowed.set(position > 0);
+            declaration.accept_with_context(&Self, context);
+            context.newline();
+        }
+    }
+
+    /// Prints `{`, the indented lines `body` prints and `}`, or just `{}` when
+    /// there is nothing in between.
+    fn braced(
+        tokens: &[Token],
+        is_empty: bool,
+        body: impl FnOnce(&FormatterContext),
+        context: &FormatterContext,
+    ) {
+        let (Some(open), Some(close)) = (tokens.first(), tokens.last()) else {
+            return;
+        };
+        context.token(open);
+        if is_empty && !context.has_comment_before(&close.span) {
+            context.token(close);
+            return;
+        }
+        context.indent.set(context.indent.get() + 1);
+        context.newline();
+        body(context);
+        context.comments_before(Some(&close.span));
+        context.blank_line_allowed.set(false);
+        context.indent.set(context.indent.get() - 1);
+        context.token(close);
+    }
+
+    fn block(node: &Block, context: &FormatterContext) {
+        Self::braced(
+            &node.token_list,
+            node.declarations.is_empty(),
+            |context| Self::declarations(&node.declarations, context),
+            context,
+        );
+    }
+
+    fn function(node: &Function, context: &FormatterContext) {
+        if let Some(name) = node.token_list.first() {
+            context.token(name);
+        }
+        Self::parameters(&node.parameters, context);
+        context.write(" ");
+        Self::block(&node.block, context);
+    }
+
+    fn parameters(node: &Parameters, context: &FormatterContext) {
+        context.write("(");
+        for (position, parameter) in node.parameters.iter().enumerate() {
+            if position > 0 {
+                context.write(", ");
+            }
+            context.token(&parameter.token);
+        }
+        context.write(")");
+    }
+
+    fn arguments(arguments: &Option<Arguments>, context: &FormatterContext) {
+        if let Some(arguments) = arguments {
+            arguments.first.accept_with_context(&Self, context);
+            for (_, argument) in &arguments.rest {
+                context.write(", ");
+                argument.accept_with_context(&Self, context);
+            }
+        }
+    }
+
+    fn index(node: &Index, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        node.expression.accept_with_context(&Self, context);
+        context.token_at(&node.token_list, 1);
+    }
+
+    /// The body of an `if`, `else`, `while` or `for`, on the same line.
+    fn body(node: &Statement, context: &FormatterContext) {
+        context.write(" ");
+        node.accept_with_context(&Self, context);
+    }
+
+    /// A binary operator with a space on each side.
+    fn operator(token: Option<&Token>, context: &FormatterContext) {
+        context.write(" ");
+        if let Some(token) = token {
+            context.token(token);
+        }
+        context.write(" ");
+    }
+}
+
+impl FormatterContext {
+    fn new(comments: Vec<Comment>) -> Self {
+        Self {
+            output: RefCell::new(String::new()),
+            comments,
+            next_comment: Cell::new(0),
+            indent: Cell::new(0),
+            at_line_start: Cell::new(true),
+            line_break_pending: Cell::new(false),
+            last_line: Cell::new(0),
+            blank_line_allowed: Cell::new(false),
+        }
+    }
+
+    fn write(&self, text: &str) {
+        if self.at_line_start.replace(false) {
+            self.break_line();
+            self.output
+                .borrow_mut()
+                .push_str(&INDENT.repeat(self.indent.get()));
+        }
+        self.output.borrow_mut().push_str(text);
+    }
+
+    /// Ends the current line. Its `\n` is only written once the next token
+    /// is, after the comments between them that are on the same line in the
+    /// source, as those followed the code of this line.
+    fn newline(&self) {
+        if self.at_line_start.replace(true) {
+            return;
+        }
+        self.line_break_pending.set(true);
+    }
+
+    fn break_line(&self) {
+        if self.line_break_pending.replace(false) {
+            self.output.borrow_mut().push('\n');
+        }
+    }
+
+    fn has_comment_before(&self, span: &Span) -> bool {
+        self.comments
+            .get(self.next_comment.get())
+            .is_some_and(|comment| comment.span.start < span.start)
+    }
+
+    /// Prints the comments that come before `span`, or all the comments left
+    /// when there is no span.
+    fn comments_before(&self, span: Option<&Span>) {
+        while let Some(comment) = self.comments.get(self.next_comment.get()) {
+            if span.is_some_and(|span| comment.span.start >= span.start) {
+                break;
+            }
+            self.next_comment.set(self.next_comment.get() + 1);
+
+            if self.line_break_pending.get() && comment.span.line == self.last_line.get() {
+                let mut output = self.output.borrow_mut();
+                output.push(' ');
+                output.push_str(&comment.text);
+                self.last_line.set(comment.end_line());
+                continue;
+            }
+
+            if self.at_line_start.get() {
+                self.keep_blank_line(comment.span.line);
+                self.write(&comment.text);
+                self.last_line.set(comment.end_line());
+                // A comment on lines of its own may be set apart from the code after it
+                self.blank_line_allowed.set(true);
+                let next_on_same_line = span.is_some_and(|span| span.line == comment.end_line());
+                if comment.is_line_comment() || !next_on_same_line {
+                    self.newline();
+                } else {
+                    self.write(" ");
+                }
+                continue;
+            }
+
+            let needs_space = !self.output.borrow().ends_with([' ', '(', '[']);
+            if needs_space {
+                self.write(" ");
+            }
+            self.write(&comment.text);
+            self.last_line.set(comment.end_line());
+            if comment.is_line_comment() {
+                self.newline();
+            } else {
+                self.write(" ");
+            }
+        }
+    }
+
+    fn keep_blank_line(&self, line: usize) {
+        if self.blank_line_allowed.replace(false) && line > self.last_line.get() + 1 {
+            self.break_line();
+            self.output.borrow_mut().push('\n');
+        }
+    }
+
+    fn token(&self, token: &Token) {
+        self.comments_before(Some(&token.span));
+        if self.at_line_start.get() {
+            self.keep_blank_line(token.line);
+        }
+        // A number followed by a `.` is scanned with the dot in its lexeme.
+        let text = match token.token_type {
+            TokenEnum::Number => token.lexeme.trim_end_matches('.'),
+            _ => &token.lexeme,
+        };
+        self.write(text);
+        self.last_line.set(token.line + text.matches('\n').count());
+    }
+
+    fn token_at(&self, tokens: &[Token], index: usize) {
+        if let Some(token) = tokens.get(index) {
+            self.token(token);
+        }
+    }
+}
+
+impl VisitorWithContext<&ProgramAst, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ProgramAst, context: &FormatterContext) {
+        Self::declarations(&node.statements, context);
+    }
+}
+
+impl VisitorWithContext<&Declaration, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Declaration, context: &FormatterContext) {
+        match &node.token_type {
+            DeclarationType::VarDeclaration(var) => var.accept_with_context(&Self, context),
+            DeclarationType::Statement(stmt) => stmt.accept_with_context(&Self, context),
+            DeclarationType::FunctionDeclaration(func) => func.accept_with_context(&Self, context),
+            DeclarationType::ClassDeclaration(class) => class.accept_with_context(&Self, context),
+            DeclarationType::ImportDeclaration(import) => {
+                import.accept_with_context(&Self, context)
+            }
+            DeclarationType::None => {}
+        }
+    }
+}
+
+impl VisitorWithContext<&VarDeclaration, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &VarDeclaration, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        context.token_at(&node.token_list, 1);
+        if let Some(expr) = &node.expr {
+            Self::operator(node.token_list.get(2), context);
+            expr.accept_with_context(&Self, context);
+        }
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&FunctionDeclaration, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &FunctionDeclaration, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        Self::function(&node.function, context);
+    }
+}
+
+impl VisitorWithContext<&ClassDeclaration, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ClassDeclaration, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        context.token_at(&node.token_list, 1);
+        if let Some(super_class) = &node.super_class.super_class {
+            context.write(" < ");
+            context.token(&super_class.token);
+        }
+        context.write(" ");
+        Self::braced(
+            &node.token_list[2..],
+            node.functions.is_empty(),
+            |context| {
+                for (position, (_, function)) in node.functions.iter().enumerate() {
+                    context.blank_line_allowed.set(position > 0);
+                    Self::function(function, context);
+                    context.newline();
+                }
+            },
+            context,
+        );
+    }
+}
+
+impl VisitorWithContext<&ImportDeclaration, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ImportDeclaration, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        context.token_at(&node.token_list, 1);
+        if let Some(alias) = &node.alias.alias {
+            context.write(" as ");
+            context.token(&alias.token);
+        }
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&Statement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Statement, context: &FormatterContext) {
+        match &node.token_type {
+            StatementType::ExprStatement(expr) => expr.accept_with_context(&Self, context),
+            StatementType::PrintStatement(print) => print.accept_with_context(&Self, context),
+            StatementType::Block(block) => block.accept_with_context(&Self, context),
+            StatementType::IfStatement(if_stmt) => if_stmt.accept_with_context(&Self, context),
+            StatementType::WhileStatement(while_stmt) => {
+                while_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ForStatement(for_stmt) => for_stmt.accept_with_context(&Self, context),
+            StatementType::ReturnStatement(return_stmt) => {
+                return_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::BreakStatement(break_stmt) => {
+                break_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ContinueStatement(continue_stmt) => {
+                continue_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::ThrowStatement(throw_stmt) => {
+                throw_stmt.accept_with_context(&Self, context)
+            }
+            StatementType::TryStatement(try_stmt) => try_stmt.accept_with_context(&Self, context),
+            StatementType::None => {}
+        }
+    }
+}
+
+impl VisitorWithContext<&PrintStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &PrintStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        node.expr.accept_with_context(&Self, context);
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&ExprStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ExprStatement, context: &FormatterContext) {
+        node.expr.accept_with_context(&Self, context);
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&Block, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Block, context: &FormatterContext) {
+        Self::block(node, context);
+    }
+}
+
+impl VisitorWithContext<&IfStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &IfStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        context.token_at(&node.token_list, 1);
+        node.bool_expr.accept_with_context(&Self, context);
+        context.token_at(&node.token_list, 2);
+        Self::body(&node.true_statement, context);
+        if let Some(false_statement) = &node.false_statement {
+            context.write(" ");
+            context.token_at(&node.token_list, 3);
+            Self::body(false_statement, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&WhileStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &WhileStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        context.token_at(&node.token_list, 1);
+        node.eval_expr.accept_with_context(&Self, context);
+        context.token_at(&node.token_list, 2);
+        Self::body(&node.statement, context);
+    }
+}
+
+impl VisitorWithContext<&ForStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ForStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        context.token_at(&node.token_list, 1);
+        match &node.token_type {
+            ForStatementType::VarDeclaration(var) => var.accept_with_context(&Self, context),
+            ForStatementType::ExprStatement(expr) => expr.accept_with_context(&Self, context),
+            ForStatementType::Semicolon | ForStatementType::None => context.write(";"),
+        }
+        if let Some(condition) = &node.condition.expr {
+            context.write(" ");
+            condition.accept_with_context(&Self, context);
+        }
+        context.write(";");
+        if let Some(increment) = &node.increment.expr {
+            context.write(" ");
+            increment.accept_with_context(&Self, context);
+        }
+        if let Some(close) = node.token_list.last() {
+            context.token(close);
+        }
+        Self::body(&node.statement, context);
+    }
+}
+
+impl VisitorWithContext<&ReturnStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ReturnStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        if let Some(expr) = &node.expr {
+            context.write(" ");
+            expr.accept_with_context(&Self, context);
+        }
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&BreakStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &BreakStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&ContinueStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ContinueStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&ThrowStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &ThrowStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        node.expr.accept_with_context(&Self, context);
+        context.write(";");
+    }
+}
+
+impl VisitorWithContext<&TryStatement, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &TryStatement, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        context.write(" ");
+        Self::block(&node.body, context);
+        if let Some(catch_clause) = &node.catch_clause {
+            context.write(" ");
+            context.token_at(&catch_clause.token_list, 0);
+            context.write(" ");
+            context.token_at(&catch_clause.token_list, 1);
+            context.token_at(&catch_clause.token_list, 2);
+            context.token_at(&catch_clause.token_list, 3);
+            context.write(" ");
+            Self::block(&catch_clause.body, context);
+        }
+        if let Some(finally_clause) = &node.finally_clause {
+            context.write(" ");
+            context.token_at(&finally_clause.token_list, 0);
+            context.write(" ");
+            Self::block(&finally_clause.body, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&Assignment, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Assignment, context: &FormatterContext) {
+        match node {
+            Assignment::Assignment(value, identifier) => {
+                context.token(&identifier.token);
+                context.write(" = ");
+                value.accept_with_context(&Self, context);
+            }
+            Assignment::SetExpression(value, identifier, call) => {
+                call.accept_with_context(&Self, context);
+                context.write(".");
+                context.token(&identifier.token);
+                context.write(" = ");
+                value.accept_with_context(&Self, context);
+            }
+            Assignment::SetIndex(value, index, call) => {
+                call.accept_with_context(&Self, context);
+                Self::index(index, context);
+                context.write(" = ");
+                value.accept_with_context(&Self, context);
+            }
+            Assignment::Evaluable(evaluable) => evaluable.accept_with_context(&Self, context),
+        }
+    }
+}
+
+impl VisitorWithContext<&LogicalOr, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &LogicalOr, context: &FormatterContext) {
+        node.main_and.accept_with_context(&Self, context);
+        for (position, (_, and)) in node.ands.iter().enumerate() {
+            Self::operator(node.token_list.get(position), context);
+            and.accept_with_context(&Self, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&LogicalAnd, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &LogicalAnd, context: &FormatterContext) {
+        node.main_equality.accept_with_context(&Self, context);
+        for (position, (_, equality)) in node.equalities.iter().enumerate() {
+            Self::operator(node.token_list.get(position), context);
+            equality.accept_with_context(&Self, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&Equality, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Equality, context: &FormatterContext) {
+        node.main_comparison.accept_with_context(&Self, context);
+        for (position, (_, comparison)) in node.comparisons.iter().enumerate() {
+            Self::operator(node.token_list.get(position), context);
+            comparison.accept_with_context(&Self, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&Comparison, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Comparison, context: &FormatterContext) {
+        node.main_term.accept_with_context(&Self, context);
+        for (position, (_, term)) in node.terms.iter().enumerate() {
+            Self::operator(node.token_list.get(position), context);
+            term.accept_with_context(&Self, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&Term, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Term, context: &FormatterContext) {
+        node.main_factor.accept_with_context(&Self, context);
+        for (position, (_, factor)) in node.factors.iter().enumerate() {
+            Self::operator(node.token_list.get(position), context);
+            factor.accept_with_context(&Self, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&Factor, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Factor, context: &FormatterContext) {
+        node.main_unary.accept_with_context(&Self, context);
+        for (position, (_, unary)) in node.unaries.iter().enumerate() {
+            Self::operator(node.token_list.get(position), context);
+            unary.accept_with_context(&Self, context);
+        }
+    }
+}
+
+impl VisitorWithContext<&UnaryExpression, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &UnaryExpression, context: &FormatterContext) {
+        match &node.token_type {
+            UnaryExpressionType::Call(call) => call.accept_with_context(&Self, context),
+            UnaryExpressionType::UnaryExpressionSelf(unary) => {
+                unary.accept_with_context(&Self, context)
+            }
+            UnaryExpressionType::None => {}
+        }
+    }
+}
+
+impl VisitorWithContext<&UnaryExpressionSelf, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &UnaryExpressionSelf, context: &FormatterContext) {
+        context.token_at(&node.token_list, 0);
+        node.expr.accept_with_context(&Self, context);
+    }
+}
+
+impl VisitorWithContext<&Call, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &Call, context: &FormatterContext) {
+        node.primary.accept_with_context(&Self, context);
+        for (_, accessor_or_arguments) in &node.accessor_or_arguments {
+            match &accessor_or_arguments.ty {
+                AccessorOrArgumentsType::Accessor(accessor) => {
+                    context.write(".");
+                    context.token(&accessor.identifier.token);
+                }
+                AccessorOrArgumentsType::ArgumentsList(arguments_list) => {
+                    context.token_at(&arguments_list.token_list, 0);
+                    Self::arguments(&arguments_list.maybe_arguments, context);
+                    context.token_at(&arguments_list.token_list, 1);
+                }
+                AccessorOrArgumentsType::Index(index) => Self::index(index, context),
+                AccessorOrArgumentsType::None => {}
+            }
+        }
+    }
+}
+
+impl VisitorWithContext<&PrimaryExpression, (), FormatterContext> for Formatter {
+    fn visit_with_context(&self, node: &PrimaryExpression, context: &FormatterContext) {
+        match node {
+            PrimaryExpression::PrimaryWithoutSuperExpression(node) => {
+                context.token_at(&node.token_list, 0);
+                match &node.token_type {
+                    PrimaryExpressionType::Expression(expr) => {
+                        expr.accept_with_context(&Self, context)
+                    }
+                    PrimaryExpressionType::ListElements(elements) => {
+                        Self::arguments(elements, context)
+                    }
+                    PrimaryExpressionType::MapEntries(Some(entries)) => {
+                        let rest = entries.rest.iter().map(|(_, entry)| entry);
+                        for (position, entry) in
+                            std::iter::once(&entries.first).chain(rest).enumerate()
+                        {
+                            if position > 0 {
+                                context.write(", ");
+                            }
+                            entry.key.accept_with_context(&Self, context);
+                            context.token_at(&entry.token_list, 0);
+                            context.write(" ");
+                            entry.value.accept_with_context(&Self, context);
+                        }
+                    }
+                    _ => {}
+                }
+                context.token_at(&node.token_list, 1);
+            }
+            PrimaryExpression::Lambda(lambda) => match &lambda.body {
+                LambdaBody::Block(block) => {
+                    context.token(&lambda.keyword);
+                    context.write(" ");
+                    Self::parameters(&lambda.parameters, context);
+                    context.write(" ");
+                    Self::block(block, context);
+                }
+                LambdaBody::Expression(expression) => {
+                    Self::parameters(&lambda.parameters, context);
+                    context.write(" ");
+                    context.token(&lambda.keyword);
+                    context.write(" ");
+                    expression.accept_with_context(&Self, context);
+                }
+            },
+            PrimaryExpression::Super(identifier) => {
+                // The comments ending the line before have to be put there
+                // before `super` is written
+                context.comments_before(Some(&identifier.token.span));
+                context.write("super.");
+                context.token(&identifier.token);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax_analysis::ParseStream;
+    use crate::tokenizer::scan_tokens;
+
+    fn format(source: &str) -> String {
+        let (tokens, _) = scan_tokens("", source);
+        let comments = tokens
+            .iter()
+            .flat_map(|token| token.comments.iter().cloned())
+            .collect();
+        let program = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap();
+        Formatter::format(&program, comments)
+    }
+
+    #[test]
+    fn test_formats_canonically() {
+        let source = "class  A<B{init(x){this.x=x;}\n\n\nget(){return this.x;}}\n\
+                      fun f(a,b){if(a>b)return -a;else{while(a<b)a=a+1;}\
+                      for(var i=0;i<3;i=i+1)print [i,{\"k\":i}][0];}\n\
+                      var g=(x)=>x*2;var h=fun(){};\n";
+        let expected = "class A < B {\n    init(x) {\n        this.x = x;\n    }\n\n    \
+                        get() {\n        return this.x;\n    }\n}\n\
+                        fun f(a, b) {\n    if (a > b) return -a; else {\n        \
+                        while (a < b) a = a + 1;\n    }\n    \
+                        for (var i = 0; i < 3; i = i + 1) print [i, {\"k\": i}][0];\n}\n\
+                        var g = (x) => x * 2;\nvar h = fun () {};\n";
+        assert_eq!(format(source), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn test_keeps_comments() {
+        let source = "// header\n\nvar a = 1; // trailing\n{\n  /* inside */ print a;\n  \
+                      // before the brace\n}\nprint f(a, // why\n  2);\n// end\n";
+        let expected = "// header\n\nvar a = 1; // trailing\n{\n    /* inside */ print a;\n    \
+                        // before the brace\n}\nprint f(a, // why\n2);\n// end\n";
+        assert_eq!(format(source), expected);
+        assert_eq!(format(expected), expected);
+    }
+
+    #[test]
+    fn test_keeps_comments_after_expanded_blocks() {
+        let source = "class A { init() {} /* c */ }\nwhile (x) { x = x - 1; } // after while\n";
+        let expected = "class A {\n    init() {} /* c */\n}\n\
+                        while (x) {\n    x = x - 1;\n} // after while\n";
+        assert_eq!(format(source), expected);
+        assert_eq!(format(expected), expected);
+    }
+}
@@ -1,23 +1,30 @@
+mod formatter;
 mod parsing;
 mod productions;
 
+pub use formatter::Formatter;
 pub use parsing::{ParseError, ParseStream};
 
 pub use productions::expression::{
     Comparison, ComparisonType, Equality, EqualityType, Factor, FactorType, LogicalAnd, LogicalOr,
-    PrimaryExpression, PrimaryExpressionType, Term, TermType, UnaryExpression, UnaryExpressionSelf,
-    UnaryExpressionSelfType, UnaryExpressionType,
+    MapEntry, PrimaryExpression, PrimaryExpressionType, Term, TermType, UnaryExpression,
+    UnaryExpressionSelf, UnaryExpressionSelfType, UnaryExpressionType,
 };
 
 pub use productions::assignments::{Assignment, Expression};
 
 pub use productions::declarations::{
-    ClassDeclaration, Declaration, DeclarationType, FunctionDeclaration, ProgramAst, VarDeclaration,
+    ClassDeclaration, Declaration, DeclarationType, FunctionDeclaration, ImportDeclaration,
+    ProgramAst, VarDeclaration,
 };
 
 pub use productions::statement::{
-    Block, ExprStatement, ForStatement, ForStatementType, IfStatement, PrintStatement,
-    ReturnStatement, Statement, StatementType, WhileStatement,
+    Block, BreakStatement, ContinueStatement, ExprStatement, ForStatement, ForStatementType,
+    IfStatement, PrintStatement, ReturnStatement, Statement, StatementType, ThrowStatement,
+    TryStatement, WhileStatement,
 };
 
-pub use productions::functions::{AccessorOrArgumentsType, ArgumentsList, Call, Function};
+pub use productions::functions::{
+    AccessorOrArguments, AccessorOrArgumentsType, Arguments, ArgumentsList, Call, Function, Index,
+    Lambda, LambdaBody, Parameters,
+};
@@ -2,6 +2,6 @@ mod parse_error;
 mod parse_stream;
 pub mod primitives;
 
-pub use parse_error::{ExpectedEnum, ParseError, Result, UnexpectedTokenError};
+pub use parse_error::{ExpectedEnum, NoTokenError, ParseError, Result, UnexpectedTokenError};
 pub use parse_stream::ParseStream;
 pub use parse_stream::Parser;
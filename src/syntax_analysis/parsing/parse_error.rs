@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::tokenizer::{Token, TokenEnum};
+use crate::tokenizer::{Span, Token, TokenEnum};
 
 use thiserror::Error;
 
@@ -111,10 +111,25 @@ pub enum ParseError {
 pub type Result<T> = std::result::Result<T, ParseError>;
 
 impl ParseError {
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            ParseError::UnexpectedToken(error) => Some(&error.token.span),
+            ParseError::NoToken(_) => None,
+        }
+    }
+
     pub fn found_token(&self) -> Option<Token> {
         match self {
             ParseError::UnexpectedToken(error) => Some(error.token.clone()),
             ParseError::NoToken(_) => None,
         }
     }
+
+    /// True when parsing failed only because the input ended too early.
+    pub fn is_at_end_of_input(&self) -> bool {
+        match self {
+            ParseError::UnexpectedToken(error) => error.token.token_type == TokenEnum::Eof,
+            ParseError::NoToken(_) => true,
+        }
+    }
 }
@@ -1,11 +1,28 @@
 use std::path::Display;
 
-use super::parse_error::Result;
-use crate::tokenizer::Token;
+use super::parse_error::{ParseError, Result};
+use crate::tokenizer::{Token, TokenEnum};
+
+/// Tokens that start a new statement; panic-mode recovery resumes at them.
+const SYNCHRONIZATION_TOKENS: [TokenEnum; 11] = [
+    TokenEnum::Class,
+    TokenEnum::Fun,
+    TokenEnum::Var,
+    TokenEnum::For,
+    TokenEnum::If,
+    TokenEnum::While,
+    TokenEnum::Print,
+    TokenEnum::Return,
+    TokenEnum::Import,
+    TokenEnum::Throw,
+    TokenEnum::Try,
+];
 
 pub struct ParseStream {
     tokens: Vec<Token>,
     current_index: usize,
+    /// `Some` while recovering from errors, holding every error recovered from so far.
+    recovered_errors: Option<Vec<ParseError>>,
 }
 
 pub trait Parser {
@@ -21,6 +38,59 @@ impl ParseStream {
         Self {
             tokens,
             current_index: 0,
+            recovered_errors: None,
+        }
+    }
+
+    /// Makes productions marked `#[Synchronize]` record errors in their items and
+    /// skip to the next statement instead of failing.
+    pub fn enable_recovery(&mut self) {
+        self.recovered_errors.get_or_insert_with(Vec::new);
+    }
+
+    /// Every error recovered from since recovery was enabled.
+    pub fn take_errors(&mut self) -> Vec<ParseError> {
+        self.recovered_errors
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Records `error` and synchronizes when recovery is enabled, otherwise hands
+    /// the error back.
+    pub fn recover(&mut self, error: ParseError) -> Result<()> {
+        if self.recovered_errors.is_none() {
+            return Err(error);
+        }
+        self.synchronize(&error);
+        if let Some(errors) = self.recovered_errors.as_mut() {
+            errors.push(error);
+        }
+        Ok(())
+    }
+
+    /// Discards tokens until just after a `;` or just before a token that starts
+    /// a statement, beginning at the token the error was reported at.
+    fn synchronize(&mut self, error: &ParseError) {
+        if let Some(found) = error.found_token() {
+            if let Some(index) = self.tokens.iter().position(|token| *token == found) {
+                self.current_index = index;
+            }
+        }
+
+        if self.is_at_end() {
+            return;
+        }
+        self.current_index += 1;
+        while !self.is_at_end() {
+            if self.tokens[self.current_index - 1].token_type == TokenEnum::Semicolon {
+                return;
+            }
+            let next = &self.tokens[self.current_index].token_type;
+            if SYNCHRONIZATION_TOKENS.iter().any(|token| next == token) {
+                return;
+            }
+            self.current_index += 1;
         }
     }
 
@@ -42,6 +112,13 @@ impl ParseStream {
         self.tokens.get(self.current_index)
     }
 
+    /// True once only the end-of-file token (or nothing) is left to consume.
+    pub fn is_at_end(&self) -> bool {
+        self.peek1()
+            .map(|token| token.token_type == TokenEnum::Eof)
+            .unwrap_or(true)
+    }
+
     pub fn peek_n(&self, n: usize) -> Option<&Token> {
         self.tokens.get(self.current_index + n - 1)
     }
@@ -67,7 +67,10 @@ impl_display_debug_for_token!(
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
     Dot,
     Minus,
     Plus,
@@ -84,21 +87,29 @@ impl_display_debug_for_token!(
     EqualEqual,
     GreaterEqual,
     LessEqual,
+    Arrow,
     // KeywordToken
     And,
+    Break,
+    Catch,
     Class,
+    Continue,
     Else,
     False,
+    Finally,
     Fun,
     For,
     If,
+    Import,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
     // LiteralToken
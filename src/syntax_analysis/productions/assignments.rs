@@ -5,23 +5,38 @@ use super::expression::LogicalOr;
 use super::super::parsing::{ParseStream, Parser, Result};
 
 use crate::syntax_analysis::parsing::primitives::{Equal, Identifier, Semicolon};
-use crate::syntax_analysis::{AccessorOrArgumentsType, Call, ParseError};
-use crate::tokenizer::TokenEnum;
+use crate::syntax_analysis::{AccessorOrArgumentsType, Call, Index, ParseError};
+use crate::tokenizer::{Token, TokenEnum};
 
 #[derive(Debug)]
 enum AssignmentPreParse {
     NoAssignment,
     Identifier,
     SetExpression(Call, Identifier),
+    SetIndex(Call, Index),
     Error(ParseError),
 }
 #[derive(Debug, PartialEq, Clone)]
 pub enum Assignment {
     Assignment(Box<Assignment>, Identifier),
     SetExpression(Box<Assignment>, Identifier, Call),
+    SetIndex(Box<Assignment>, Index, Call),
     Evaluable(LogicalOr),
 }
 impl crate::common::Visitable for Assignment {}
+
+impl Assignment {
+    /// The token the expression starts with.
+    pub fn first_token(&self) -> Option<&Token> {
+        match self {
+            Assignment::Assignment(_, identifier) => Some(&identifier.token),
+            Assignment::SetExpression(_, _, call) | Assignment::SetIndex(_, _, call) => {
+                call.primary.first_token()
+            }
+            Assignment::Evaluable(evaluable) => evaluable.first_token(),
+        }
+    }
+}
 impl Parser for Assignment {
     fn parse(input: &mut ParseStream) -> Result<Self> {
         let pre_parse = input.run_and_restore(|input| {
@@ -44,13 +59,7 @@ impl Parser for Assignment {
             }
 
             let (_, last) = call.accessor_or_arguments.last().unwrap();
-            let identifier = match &last.ty {
-                AccessorOrArgumentsType::Accessor(ac) => ac.identifier.clone(),
-                _ => {
-                    let err = input.parse::<Semicolon>().err().unwrap();
-                    return (AssignmentPreParse::Error(err), false);
-                }
-            };
+            let last = last.ty.clone();
 
             let mut without_last: Vec<_> = Vec::new();
             without_last.reserve_exact(call.accessor_or_arguments.len() - 1);
@@ -64,10 +73,19 @@ impl Parser for Assignment {
                 accessor_or_arguments: without_last,
             };
 
-            return (
-                AssignmentPreParse::SetExpression(new_call, identifier),
-                true,
-            );
+            match last {
+                AccessorOrArgumentsType::Accessor(ac) => (
+                    AssignmentPreParse::SetExpression(new_call, ac.identifier),
+                    true,
+                ),
+                AccessorOrArgumentsType::Index(index) => {
+                    (AssignmentPreParse::SetIndex(new_call, index), true)
+                }
+                _ => {
+                    let err = input.parse::<Semicolon>().err().unwrap();
+                    (AssignmentPreParse::Error(err), false)
+                }
+            }
         });
 
         match pre_parse {
@@ -89,6 +107,10 @@ impl Parser for Assignment {
                     call,
                 ))
             }
+            AssignmentPreParse::SetIndex(call, index) => {
+                let assignment = input.parse::<Assignment>()?;
+                Ok(Assignment::SetIndex(Box::new(assignment), index, call))
+            }
             AssignmentPreParse::Error(err) => Err(err),
         }
     }
@@ -111,6 +133,9 @@ impl Display for Assignment {
             Assignment::SetExpression(assignment, identifier, call) => {
                 write!(f, "{}.{} = {}", call, identifier, assignment)
             }
+            Assignment::SetIndex(assignment, index, call) => {
+                write!(f, "{}[{}] = {}", call, index.expression, assignment)
+            }
             Assignment::Evaluable(evaluable) => write!(f, "{}", evaluable),
         }
     }
@@ -3,9 +3,11 @@ use std::fmt::Debug;
 use ast_leaf::ast_leaf;
 
 use super::super::parsing::primitives::{
-    Class, Equal, Fun, Identifier, LeftBrace, Less, RightBrace, Semicolon, Var,
+    Class, Equal, Fun, Identifier, Import, LeftBrace, Less, RightBrace, Semicolon, String, Var,
+};
+use super::super::parsing::{
+    ExpectedEnum, NoTokenError, ParseError, ParseStream, Parser, Result, UnexpectedTokenError,
 };
-use super::super::parsing::{ParseStream, Parser, Result};
 use super::assignments::Expression;
 
 use super::statement::Statement;
@@ -28,6 +30,8 @@ pub struct FunctionDeclaration {
     #[Type]
     pub token_type: FunctionDeclarationType,
     pub function: Function,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -61,7 +65,39 @@ pub struct ClassDeclaration {
     pub token_list: Vec<Token>,
 }
 
-#[ast_leaf((VarDeclaration | Statement | FunctionDeclaration | ClassDeclaration))]
+/// The `as <name>` an import may end with. `as` is only a keyword here, so it
+/// is still a valid identifier everywhere else.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportAlias {
+    pub alias: Option<Identifier>,
+}
+
+impl Parser for ImportAlias {
+    fn parse(stream: &mut ParseStream) -> Result<Self> {
+        let mut alias = None;
+        if stream.peek1().is_some_and(|token| token.lexeme == "as") {
+            stream.parse::<Identifier>()?;
+            alias = Some(stream.parse()?);
+        }
+        Ok(Self { alias })
+    }
+
+    fn peek(_stream: &ParseStream) -> bool {
+        true
+    }
+}
+
+#[ast_leaf("import" "STRING" alias ";")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ImportDeclaration {
+    #[Type]
+    pub token_type: ImportDeclarationType,
+    pub alias: ImportAlias,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+#[ast_leaf((VarDeclaration | Statement | FunctionDeclaration | ClassDeclaration | ImportDeclaration))]
 #[derive(Debug, PartialEq, Clone)]
 pub struct Declaration {
     #[Type]
@@ -75,3 +111,64 @@ pub struct ProgramAst {
     pub token_type: ProgramType,
     pub statements: Vec<(ProgramType, Declaration)>,
 }
+
+impl ProgramAst {
+    /// Parses the whole stream, synchronizing at the next statement after each
+    /// syntax error so that every error in the file is reported at once.
+    pub fn parse_all(input: &mut ParseStream) -> std::result::Result<Self, Vec<ParseError>> {
+        input.enable_recovery();
+        let mut statements = Vec::new();
+        while !input.is_at_end() {
+            if !input.peek::<Declaration>() {
+                let error = Self::expect_expression(input);
+                input.recover(error).map_err(|error| vec![error])?;
+                continue;
+            }
+            match input.parse::<Declaration>() {
+                Ok(declaration) => statements.push((ProgramType::None, declaration)),
+                Err(error) => input.recover(error).map_err(|error| vec![error])?,
+            }
+        }
+
+        let errors = input.take_errors();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        Ok(Self {
+            token_type: ProgramType::None,
+            statements,
+        })
+    }
+
+    /// A token that cannot start a declaration is reported as a missing expression.
+    fn expect_expression(input: &ParseStream) -> ParseError {
+        match input.peek1() {
+            Some(token) => ParseError::UnexpectedToken(UnexpectedTokenError::unexpected_token(
+                token.clone(),
+                ExpectedEnum::NonTerminal("expression".to_string()),
+                None,
+            )),
+            None => ParseError::NoToken(NoTokenError::no_token(
+                ExpectedEnum::NonTerminal("expression".to_string()),
+                None,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::scan_tokens;
+
+    #[test]
+    fn test_parse_all_reports_every_error() {
+        let (tokens, _) = scan_tokens("", "var = 1;\nprint 2;\n{ print ; }\nprint 3\n}");
+        let errors = ProgramAst::parse_all(&mut ParseStream::new(tokens)).unwrap_err();
+        let lines = errors
+            .iter()
+            .map(|error| error.found_token().map(|token| token.line))
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![Some(1), Some(3), Some(5)]);
+    }
+}
@@ -3,19 +3,66 @@ use std::fmt::{Debug, Display};
 use ast_leaf::ast_leaf;
 
 use super::super::parsing::primitives::{
-    And, Bang, BangEqual, EqualEqual, False, Greater, GreaterEqual, Identifier, LeftParen, Less,
-    LessEqual, Minus, Nil, Number, Or, Plus, RightParen, Slash, Star, String, This, True,
+    And, Bang, BangEqual, Colon, Comma, EqualEqual, False, Greater, GreaterEqual, Identifier,
+    LeftBrace, LeftBracket, LeftParen, Less, LessEqual, Minus, Nil, Number, Or, Plus, RightBrace,
+    RightBracket, RightParen, Slash, Star, String, This, True,
 };
 use super::super::parsing::{
     ExpectedEnum, ParseError, ParseStream, Parser, Result, UnexpectedTokenError,
 };
 use super::assignments::Expression;
-use super::functions::Call;
+use super::functions::{Call, Lambda, MaybeArguments};
 
 use crate::syntax_analysis::parsing::primitives::{Dot, Super};
 use crate::tokenizer::Token;
 
-#[ast_leaf(( "IDENT" |"NUMBER" | "STRING" | "true" | "false" | "this" | "nil" | 1: "(" Expression ")" ))]
+/// The elements of a list literal, parsed like call arguments.
+pub type ListElements = MaybeArguments;
+
+#[ast_leaf(key ":" value)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct MapEntry {
+    #[Type]
+    pub ty: MapEntryType,
+    pub key: Expression,
+    pub value: Expression,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+impl Display for MapEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({} {})", self.key, self.value)
+    }
+}
+
+#[ast_leaf(first ((",") rest)*)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct MapEntryList {
+    #[Type]
+    pub ty: MapEntryListType,
+    pub first: MapEntry,
+    pub rest: Vec<(MapEntryListType, MapEntry)>,
+}
+
+/// The entries of a map literal.
+pub type MapEntries = Option<MapEntryList>;
+
+impl Parser for MapEntries {
+    fn parse(input: &mut ParseStream) -> Result<Self> {
+        if !input.peek::<MapEntryList>() {
+            return Ok(None);
+        }
+        let entries = input.parse::<MapEntryList>()?;
+        Ok(Some(entries))
+    }
+
+    fn peek(_: &ParseStream) -> bool {
+        true
+    }
+}
+
+#[ast_leaf(( "IDENT" |"NUMBER" | "STRING" | "true" | "false" | "this" | "nil" | 1: "(" Expression ")" | 1: "[" ListElements "]" | 1: "{" MapEntries "}" ))]
 #[derive(Debug, PartialEq, Clone)]
 pub struct PrimaryWithoutSuperExpression {
     #[Type]
@@ -28,6 +75,26 @@ impl Display for PrimaryWithoutSuperExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.token_type {
             PrimaryExpressionType::Expression(expr) => write!(f, "(group {})", expr),
+            PrimaryExpressionType::ListElements(elements) => {
+                write!(f, "(list")?;
+                if let Some(arguments) = elements {
+                    write!(f, " {}", arguments.first)?;
+                    for (_, argument) in &arguments.rest {
+                        write!(f, " {}", argument)?;
+                    }
+                }
+                write!(f, ")")
+            }
+            PrimaryExpressionType::MapEntries(entries) => {
+                write!(f, "(map")?;
+                if let Some(entries) = entries {
+                    write!(f, " {}", entries.first)?;
+                    for (_, entry) in &entries.rest {
+                        write!(f, " {}", entry)?;
+                    }
+                }
+                write!(f, ")")
+            }
             _ => {
                 let token = self.token_list.first().ok_or(std::fmt::Error)?;
                 write!(f, "{}", token)
@@ -40,6 +107,7 @@ impl Display for PrimaryWithoutSuperExpression {
 pub enum PrimaryExpression {
     PrimaryWithoutSuperExpression(PrimaryWithoutSuperExpression),
     Super(Identifier),
+    Lambda(Lambda),
 }
 
 impl Parser for PrimaryExpression {
@@ -49,6 +117,8 @@ impl Parser for PrimaryExpression {
             input.parse::<Dot>()?;
             let identifier = input.parse::<Identifier>()?;
             Ok(PrimaryExpression::Super(identifier))
+        } else if input.peek::<Lambda>() {
+            Ok(PrimaryExpression::Lambda(input.parse::<Lambda>()?))
         } else {
             let primary_without_super_expression =
                 input.parse::<PrimaryWithoutSuperExpression>()?;
@@ -59,17 +129,30 @@ impl Parser for PrimaryExpression {
     }
 
     fn peek(input: &ParseStream) -> bool {
-        input.peek::<Super>() || input.peek::<PrimaryWithoutSuperExpression>()
+        input.peek::<Super>()
+            || input.peek::<Lambda>()
+            || input.peek::<PrimaryWithoutSuperExpression>()
     }
 }
 
 impl crate::common::Visitable for PrimaryExpression {}
 
+impl PrimaryExpression {
+    pub fn first_token(&self) -> Option<&Token> {
+        match self {
+            PrimaryExpression::PrimaryWithoutSuperExpression(expr) => expr.token_list.first(),
+            PrimaryExpression::Super(identifier) => Some(&identifier.token),
+            PrimaryExpression::Lambda(lambda) => Some(&lambda.keyword),
+        }
+    }
+}
+
 impl Display for PrimaryExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PrimaryExpression::PrimaryWithoutSuperExpression(expr) => write!(f, "{}", expr),
             PrimaryExpression::Super(identifier) => write!(f, "super.{}", identifier.token.lexeme),
+            PrimaryExpression::Lambda(lambda) => write!(f, "{}", lambda),
         }
     }
 }
@@ -110,6 +193,16 @@ pub struct UnaryExpression {
     pub token_type: UnaryExpressionType,
 }
 
+impl UnaryExpression {
+    pub fn first_token(&self) -> Option<&Token> {
+        match &self.token_type {
+            UnaryExpressionType::UnaryExpressionSelf(expr) => expr.token_list.first(),
+            UnaryExpressionType::Call(call) => call.primary.first_token(),
+            _ => None,
+        }
+    }
+}
+
 impl Display for UnaryExpression {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.token_type {
@@ -127,6 +220,8 @@ pub struct Factor {
     pub token_type: FactorType,
     pub main_unary: UnaryExpression,
     pub unaries: Vec<(FactorType, UnaryExpression)>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 impl Display for Factor {
@@ -151,6 +246,8 @@ pub struct Term {
     pub token_type: TermType,
     pub main_factor: Factor,
     pub factors: Vec<(TermType, Factor)>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 impl Display for Term {
@@ -175,6 +272,8 @@ pub struct Comparison {
     pub token_type: ComparisonType,
     pub main_term: Term,
     pub terms: Vec<(ComparisonType, Term)>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 impl Display for Comparison {
@@ -202,6 +301,8 @@ pub struct Equality {
     pub token_type: EqualityType,
     pub main_comparison: Comparison,
     pub comparisons: Vec<(EqualityType, Comparison)>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 impl Display for Equality {
@@ -226,6 +327,8 @@ pub struct LogicalAnd {
     pub token_type: LogicalAndType,
     pub main_equality: Equality,
     pub equalities: Vec<(LogicalAndType, Equality)>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 impl Display for LogicalAnd {
@@ -249,6 +352,20 @@ pub struct LogicalOr {
     pub token_type: LogicalOrType,
     pub main_and: LogicalAnd,
     pub ands: Vec<(LogicalOrType, LogicalAnd)>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+impl LogicalOr {
+    pub fn first_token(&self) -> Option<&Token> {
+        let factor = &self
+            .main_and
+            .main_equality
+            .main_comparison
+            .main_term
+            .main_factor;
+        factor.main_unary.first_token()
+    }
 }
 
 impl Display for LogicalOr {
@@ -4,9 +4,11 @@ use std::fmt::Display;
 use super::super::parsing::{ParseStream, Parser, Result};
 use ast_leaf::ast_leaf;
 
-use crate::syntax_analysis::parsing::primitives::{Comma, Dot, Identifier, LeftParen, RightParen};
+use crate::syntax_analysis::parsing::primitives::{
+    Arrow, Comma, Dot, Fun, Identifier, LeftBracket, LeftParen, RightBracket, RightParen,
+};
 use crate::syntax_analysis::{Block, Expression, PrimaryExpression};
-use crate::tokenizer::Token;
+use crate::tokenizer::{Token, TokenEnum};
 
 #[ast_leaf(first ((",") rest)*)]
 #[derive(Debug, PartialEq, Clone)]
@@ -17,7 +19,7 @@ pub struct Arguments {
     pub rest: Vec<(ArgumentsType, Expression)>,
 }
 
-type MaybeArguments = Option<Arguments>;
+pub type MaybeArguments = Option<Arguments>;
 
 impl Parser for MaybeArguments {
     fn parse(input: &mut ParseStream) -> Result<Self> {
@@ -60,7 +62,24 @@ impl Parser for Accessor {
     }
 }
 
-#[ast_leaf((Accessor | ArgumentsList))]
+#[ast_leaf("[" expression "]")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct Index {
+    #[Type]
+    pub ty: IndexType,
+    pub expression: Expression,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+impl Index {
+    /// The opening bracket, which errors indexing the value point at.
+    pub fn bracket(&self) -> Option<&Token> {
+        self.token_list.first()
+    }
+}
+
+#[ast_leaf((Accessor | ArgumentsList | Index))]
 #[derive(Debug, PartialEq, Clone)]
 pub struct AccessorOrArguments {
     #[Type]
@@ -131,3 +150,82 @@ pub struct Function {
     #[TokenList]
     pub token_list: Vec<Token>,
 }
+
+/// What a lambda runs: a block, or the single expression an arrow function
+/// returns.
+#[derive(Debug, PartialEq, Clone)]
+pub enum LambdaBody {
+    Block(Block),
+    Expression(Box<Expression>),
+}
+
+/// An anonymous function, `fun (a, b) { ... }` or `(a, b) => a + b`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lambda {
+    /// `fun`, or the `=>` of an arrow function.
+    pub keyword: Token,
+    pub parameters: Parameters,
+    pub body: LambdaBody,
+}
+
+impl Lambda {
+    /// Whether the tokens ahead are `(a, b) =>`, an arrow function's head
+    /// rather than a grouping.
+    fn peek_arrow(input: &ParseStream) -> bool {
+        let is = |n: usize, token: TokenEnum| input.peek_n(n).is_some_and(|t| t.peek(token));
+        if !is(1, TokenEnum::LeftParen) {
+            return false;
+        }
+        let mut n = 2;
+        if is(n, TokenEnum::Identifier) {
+            n += 1;
+            while is(n, TokenEnum::Comma) && is(n + 1, TokenEnum::Identifier) {
+                n += 2;
+            }
+        }
+        is(n, TokenEnum::RightParen) && is(n + 1, TokenEnum::Arrow)
+    }
+}
+
+impl Parser for Lambda {
+    fn parse(input: &mut ParseStream) -> Result<Self> {
+        if input.peek::<Fun>() {
+            let keyword = input.parse::<Fun>()?.token;
+            input.parse::<LeftParen>()?;
+            let parameters = input.parse::<Parameters>()?;
+            input.parse::<RightParen>()?;
+            let block = input.parse::<Block>()?;
+            return Ok(Lambda {
+                keyword,
+                parameters,
+                body: LambdaBody::Block(block),
+            });
+        }
+        input.parse::<LeftParen>()?;
+        let parameters = input.parse::<Parameters>()?;
+        input.parse::<RightParen>()?;
+        let keyword = input.parse::<Arrow>()?.token;
+        let expression = input.parse::<Expression>()?;
+        Ok(Lambda {
+            keyword,
+            parameters,
+            body: LambdaBody::Expression(Box::new(expression)),
+        })
+    }
+
+    fn peek(input: &ParseStream) -> bool {
+        let is_fun = input.peek::<Fun>()
+            && input
+                .peek_n(2)
+                .is_some_and(|token| token.peek(TokenEnum::LeftParen));
+        is_fun || Self::peek_arrow(input)
+    }
+}
+
+impl crate::common::Visitable for Lambda {}
+
+impl Display for Lambda {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "<fn anonymous>")
+    }
+}
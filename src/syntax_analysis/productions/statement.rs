@@ -3,7 +3,8 @@ use std::fmt::Debug;
 use ast_leaf::ast_leaf;
 
 use super::super::parsing::primitives::{
-    Else, For, If, LeftBrace, LeftParen, Print, Return, RightBrace, RightParen, Semicolon, While,
+    Break, Catch, Continue, Else, Finally, For, Identifier, If, LeftBrace, LeftParen, Print,
+    Return, RightBrace, RightParen, Semicolon, Throw, Try, While,
 };
 use super::super::parsing::{ParseStream, Parser, Result};
 
@@ -17,6 +18,8 @@ pub struct PrintStatement {
     #[Type]
     pub token_type: PrintStatementType,
     pub expr: Expression,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 #[ast_leaf(expr ";")]
@@ -27,19 +30,42 @@ pub struct ExprStatement {
     pub expr: Expression,
 }
 
-#[ast_leaf((ExprStatement | PrintStatement | Block | IfStatement | WhileStatement | ForStatement | ReturnStatement))]
+#[ast_leaf((Block | ExprStatement | PrintStatement | IfStatement | WhileStatement | ForStatement | ReturnStatement | BreakStatement | ContinueStatement | ThrowStatement | TryStatement))]
 #[derive(Debug, PartialEq, Clone)]
 pub struct Statement {
     #[Type]
     pub token_type: StatementType,
 }
 
+impl Statement {
+    /// The token the statement starts with.
+    pub fn first_token(&self) -> Option<&Token> {
+        match &self.token_type {
+            StatementType::ExprStatement(node) => node.expr.first_token(),
+            StatementType::PrintStatement(node) => node.token_list.first(),
+            StatementType::Block(node) => node.token_list.first(),
+            StatementType::IfStatement(node) => node.token_list.first(),
+            StatementType::WhileStatement(node) => node.token_list.first(),
+            StatementType::ForStatement(node) => node.token_list.first(),
+            StatementType::ReturnStatement(node) => node.token_list.first(),
+            StatementType::BreakStatement(node) => node.token_list.first(),
+            StatementType::ContinueStatement(node) => node.token_list.first(),
+            StatementType::ThrowStatement(node) => node.token_list.first(),
+            StatementType::TryStatement(node) => node.token_list.first(),
+            StatementType::None => None,
+        }
+    }
+}
+
 #[ast_leaf("{" (declarations)* "}")]
+#[Synchronize]
 #[derive(Debug, PartialEq, Clone)]
 pub struct Block {
     #[Type]
     pub token_type: BlockType,
     pub declarations: Vec<(BlockType, Declaration)>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 type StatementReference = Box<Statement>;
@@ -63,6 +89,8 @@ pub struct IfStatement {
     pub bool_expr: Expression,
     pub true_statement: StatementReference,
     pub false_statement: Option<StatementReference>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 #[ast_leaf("while" "(" eval_expr ")" statement )]
@@ -72,6 +100,8 @@ pub struct WhileStatement {
     pub token_type: WhileStatementType,
     pub eval_expr: Expression,
     pub statement: StatementReference,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 #[ast_leaf((expr)?)]
@@ -90,6 +120,8 @@ pub struct ForStatement {
     pub condition: MaybeExpression,
     pub increment: MaybeExpression,
     pub statement: StatementReference,
+    #[TokenList]
+    pub token_list: Vec<Token>,
 }
 
 #[ast_leaf("return" (expr)?";")]
@@ -101,3 +133,63 @@ pub struct ReturnStatement {
     #[TokenList]
     pub token_list: Vec<Token>,
 }
+
+#[ast_leaf("break" ";")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct BreakStatement {
+    #[Type]
+    pub token_type: BreakStatementType,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+#[ast_leaf("continue" ";")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContinueStatement {
+    #[Type]
+    pub token_type: ContinueStatementType,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+#[ast_leaf("throw" expr ";")]
+#[derive(Debug, PartialEq, Clone)]
+pub struct ThrowStatement {
+    #[Type]
+    pub token_type: ThrowStatementType,
+    pub expr: Expression,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+#[ast_leaf("catch" "(" "IDENT" ")" body)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct CatchClause {
+    #[Type]
+    pub token_type: CatchClauseType,
+    pub body: Block,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+#[ast_leaf("finally" body)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct FinallyClause {
+    #[Type]
+    pub token_type: FinallyClauseType,
+    pub body: Block,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
+
+#[ast_leaf("try" body (catch_clause)? (finally_clause)?)]
+#[derive(Debug, PartialEq, Clone)]
+pub struct TryStatement {
+    #[Type]
+    pub token_type: TryStatementType,
+    pub body: Block,
+    pub catch_clause: Option<CatchClause>,
+    pub finally_clause: Option<FinallyClause>,
+    #[TokenList]
+    pub token_list: Vec<Token>,
+}
@@ -1,5 +1,7 @@
 mod scanner;
+mod span;
 mod token;
 
 pub use scanner::{scan_tokens, ScannerError};
-pub use token::{StaticToken as Token, TokenEnum, TokenValue};
+pub use span::Span;
+pub use token::{Comment, StaticToken as Token, TokenEnum, TokenValue};
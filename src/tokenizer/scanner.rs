@@ -1,7 +1,9 @@
 use std::collections::{HashSet, LinkedList};
+use std::rc::Rc;
 use thiserror::Error;
 
-use super::token::{EOFToken, StaticToken, Token};
+use super::span::Span;
+use super::token::{Comment, EOFToken, StaticToken, Token};
 
 fn skip_single_line_comment(str: &str) -> (usize, usize, usize) {
     let mut byte_idx = 0;
@@ -19,9 +21,11 @@ fn skip_single_line_comment(str: &str) -> (usize, usize, usize) {
 }
 
 fn skip_multi_line_comment(str: &str) -> (usize, usize, usize) {
-    let mut byte_idx = 0;
-    let mut char_idx = 0;
+    // Past the opening `/*`, so its `*` can't close the comment
+    let mut byte_idx = 2;
+    let mut char_idx = 2;
     let mut comment_lines_count = 0;
+    let mut previous = None;
 
     while let Some(c) = str.chars().nth(char_idx) {
         char_idx += 1;
@@ -31,13 +35,10 @@ fn skip_multi_line_comment(str: &str) -> (usize, usize, usize) {
             comment_lines_count += 1;
         }
 
-        if c == '/' {
-            if let Some(c2) = str.chars().nth(char_idx - 2) {
-                if c2 == '*' {
-                    break;
-                }
-            }
+        if c == '/' && previous == Some('*') {
+            break;
         }
+        previous = Some(c);
     }
 
     return (byte_idx, char_idx, comment_lines_count);
@@ -55,11 +56,20 @@ fn skip_comment(str: &str) -> Option<(usize, usize, usize)> {
 
 #[derive(Error, Debug)]
 pub enum ScannerError {
-    #[error("[line {1}] Error: Unexpected character: {0}")]
-    UnexpectedCharacter(char, usize),
+    #[error("[line {}] Error: Unexpected character: {0}", .1.line)]
+    UnexpectedCharacter(char, Span),
 
-    #[error("[line {0}] Error: Unterminated string.")]
-    NotTerminatedString(usize),
+    #[error("[line {}] Error: Unterminated string.", .0.line)]
+    NotTerminatedString(Span),
+}
+
+impl ScannerError {
+    pub fn span(&self) -> &Span {
+        match self {
+            ScannerError::UnexpectedCharacter(_, span) => span,
+            ScannerError::NotTerminatedString(span) => span,
+        }
+    }
 }
 
 static ALLOWED_NON_TOKEN_CHARS: [char; 4] = [' ', '\t', '\r', '\n'];
@@ -84,12 +94,17 @@ fn push_token(
     };
 }
 
-pub fn scan_tokens(file_content: &str) -> (Vec<StaticToken>, Vec<ScannerError>) {
+/// Scans `file_content`, giving every token and error a span in `file_name`.
+pub fn scan_tokens(file_name: &str, file_content: &str) -> (Vec<StaticToken>, Vec<ScannerError>) {
+    let file_name: Rc<str> = Rc::from(file_name);
     let mut tokens = LinkedList::new();
     let mut errors = LinkedList::new();
+    // Each comment with the index of the token it comes before
+    let mut comments = Vec::new();
     let mut current_byte_idx = 0;
     let mut line = 1;
     let mut current_lexeme_start_byte_idx: usize = 0;
+    let mut current_lexeme_span = Span::default();
     let mut inside_lexeme = false;
     let mut last_token: Option<Token> = None;
     let non_token_chars_set: HashSet<char> = ALLOWED_NON_TOKEN_CHARS.into_iter().collect();
@@ -107,6 +122,20 @@ pub fn scan_tokens(file_content: &str) -> (Vec<StaticToken>, Vec<ScannerError>)
             if let Some((byte_idx, char_idx, line_count)) =
                 skip_comment(&file_content[current_byte_idx..])
             {
+                let end = current_byte_idx + byte_idx;
+                let text = file_content[current_byte_idx..end].trim_end_matches(['\r', '\n']);
+                let span = Span::new(
+                    file_name.clone(),
+                    file_content,
+                    line,
+                    current_byte_idx,
+                    current_byte_idx + text.len(),
+                );
+                let comment = Comment {
+                    text: text.to_string(),
+                    span,
+                };
+                comments.push((tokens.len(), comment));
                 current_byte_idx += byte_idx;
                 char_index += char_idx;
                 line += line_count;
@@ -115,12 +144,24 @@ pub fn scan_tokens(file_content: &str) -> (Vec<StaticToken>, Vec<ScannerError>)
 
             inside_lexeme = true;
             current_lexeme_start_byte_idx = current_byte_idx;
+            current_lexeme_span = Span::new(
+                file_name.clone(),
+                file_content,
+                line,
+                current_byte_idx,
+                current_byte_idx,
+            );
         }
 
+        let current_lexeme_end_byte_idx = current_byte_idx + c.len_utf8();
         let current_lexeme =
-            &file_content[current_lexeme_start_byte_idx..current_byte_idx + c.len_utf8()];
+            &file_content[current_lexeme_start_byte_idx..current_lexeme_end_byte_idx];
+        let span = Span {
+            end: current_lexeme_end_byte_idx,
+            ..current_lexeme_span.clone()
+        };
 
-        if let Some(token) = Token::from_str(current_lexeme, line, current_lexeme_start_byte_idx) {
+        if let Some(token) = Token::from_str(current_lexeme, line, span.clone()) {
             last_token = Some(token);
             current_byte_idx += c.len_utf8();
             char_index += 1;
@@ -130,7 +171,7 @@ pub fn scan_tokens(file_content: &str) -> (Vec<StaticToken>, Vec<ScannerError>)
             inside_lexeme = false;
         } else {
             if !non_token_chars_set.contains(&c) {
-                errors.push_back(ScannerError::UnexpectedCharacter(c, line));
+                errors.push_back(ScannerError::UnexpectedCharacter(c, span));
             }
             current_byte_idx += c.len_utf8();
             char_index += 1;
@@ -146,9 +187,18 @@ pub fn scan_tokens(file_content: &str) -> (Vec<StaticToken>, Vec<ScannerError>)
         token_type: Box::new(EOFToken),
         lexeme: "".to_string(),
         line,
-        column_start: 0,
-        column_end: 0,
+        span: Span::new(
+            file_name,
+            file_content,
+            line,
+            file_content.len(),
+            file_content.len(),
+        ),
     });
 
-    (tokens.into_iter().collect(), errors.into_iter().collect())
+    let mut tokens: Vec<StaticToken> = tokens.into_iter().collect();
+    for (index, comment) in comments {
+        tokens[index].comments.push(comment);
+    }
+    (tokens, errors.into_iter().collect())
 }
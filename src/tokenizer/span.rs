@@ -0,0 +1,87 @@
+use std::{fmt::Display, rc::Rc};
+
+/// A piece of source text: the file it comes from, the line and column (both
+/// starting at 1) of its first character and its byte range in the file.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Span {
+    pub file: Rc<str>,
+    pub line: usize,
+    pub column: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
+impl Span {
+    /// Span of `source[start..end]`, with the column counted in characters
+    /// from the start of the line.
+    pub fn new(file: Rc<str>, source: &str, line: usize, start: usize, end: usize) -> Self {
+        let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+        Self {
+            file,
+            line,
+            column: source[line_start..start].chars().count() + 1,
+            start,
+            end,
+        }
+    }
+
+    /// Renders the line of `source` this span starts on with the span
+    /// underlined, the way rustc shows the code an error points at:
+    ///
+    /// ```text
+    ///  --> main.lox:1:7
+    ///   |
+    /// 1 | print x;
+    ///   |       ^
+    /// ```
+    ///
+    /// A span reaching past the end of its line is underlined up to the end
+    /// of the line.
+    pub fn snippet(&self, source: &str) -> String {
+        let start = self.start.min(source.len());
+        let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+        let line_end = source[start..]
+            .find('\n')
+            .map_or(source.len(), |index| start + index);
+        let line_text = source[line_start..line_end].trim_end_matches('\r');
+
+        let padding = source[line_start..start].chars().count();
+        let underlined = source[start..self.end.clamp(start, line_end)]
+            .chars()
+            .count()
+            .max(1);
+
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "{gutter}--> {}\n{gutter} |\n{} | {}\n{gutter} | {}{}",
+            self,
+            self.line,
+            line_text,
+            " ".repeat(padding),
+            "^".repeat(underlined)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_underlines_span() {
+        let source = "var a = 1;\nprint a + bc;\n";
+        let start = source.find("bc").unwrap();
+        let span = Span::new(Rc::from("main.lox"), source, 2, start, start + 2);
+        assert_eq!(span.column, 11);
+        assert_eq!(
+            span.snippet(source),
+            " --> main.lox:2:11\n  |\n2 | print a + bc;\n  |           ^^"
+        );
+    }
+}
@@ -7,7 +7,9 @@ use thiserror::Error;
 use token_type::ArrangedTokens;
 use token_type::PARSERS;
 
-pub use static_token::StaticToken;
+pub use static_token::{Comment, StaticToken};
+
+use super::Span;
 pub use token_type::{EOFToken, Token as TokenEnum, TokenType, TokenValue};
 
 #[derive(Error, Debug)]
@@ -21,8 +23,7 @@ pub struct Token {
     pub token_type: Box<dyn TokenType>,
     pub(super) lexeme: String,
     pub(super) line: usize,
-    pub(super) column_start: usize,
-    pub(super) column_end: usize,
+    pub(super) span: Span,
 }
 
 impl Display for Token {
@@ -33,7 +34,7 @@ impl Display for Token {
 }
 
 impl Token {
-    pub fn from_str(str: &str, line: usize, column_start: usize) -> Option<Token> {
+    pub fn from_str(str: &str, line: usize, span: Span) -> Option<Token> {
         let mut token: Option<Box<dyn TokenType>> = None;
         for &parser in PARSERS.iter() {
             token = parser.parse_string(str);
@@ -51,8 +52,7 @@ impl Token {
             token_type: token,
             lexeme: str.to_string(),
             line,
-            column_start,
-            column_end: column_start + str.len(),
+            span,
         })
     }
 
@@ -64,7 +64,7 @@ impl Token {
             match error {
                 TokenErrors::NotTerminatedString => {
                     return Err(super::scanner::ScannerError::NotTerminatedString(
-                        token.line,
+                        token.span,
                     ));
                 }
             }
@@ -79,8 +79,7 @@ impl Token {
                     token_type,
                     lexeme: token.lexeme,
                     line: token.line,
-                    column_start: token.column_start,
-                    column_end: token.column_end,
+                    span: token.span,
                 };
                 Ok((new_token, None))
             }
@@ -89,15 +88,13 @@ impl Token {
                     token_type: first_type,
                     lexeme: token.lexeme.clone(),
                     line: token.line,
-                    column_start: token.column_start,
-                    column_end: token.column_end,
+                    span: token.span.clone(),
                 };
                 let second_token = Token {
                     token_type: second_type,
                     lexeme: token.lexeme,
                     line: token.line,
-                    column_start: token.column_start,
-                    column_end: token.column_end,
+                    span: token.span,
                 };
                 Ok((first_token, Some(second_token)))
             }
@@ -4,15 +4,37 @@ use crate::tokenizer::token::token_type::TokenValueError;
 
 pub use super::token_type::{Token as TokenEnum, TokenType, TokenValue};
 use super::Token;
+use crate::tokenizer::Span;
+
+/// A `//` or `/* */` comment, kept by the scanner so tools like the
+/// formatter can put it back.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Comment {
+    pub text: String,
+    pub span: Span,
+}
+
+impl Comment {
+    /// Whether the comment runs to the end of its line, so nothing can follow
+    /// it there.
+    pub fn is_line_comment(&self) -> bool {
+        self.text.starts_with("//")
+    }
+
+    pub fn end_line(&self) -> usize {
+        self.span.line + self.text.matches('\n').count()
+    }
+}
 
 #[derive(PartialEq, Clone)]
 pub struct StaticToken {
     pub token_type: TokenEnum,
     pub lexeme: String,
     pub line: usize,
-    pub column_start: usize,
-    pub column_end: usize,
+    pub span: Span,
     pub token_value: TokenValue,
+    /// The comments between the previous token and this one.
+    pub comments: Vec<Comment>,
     literal_display: Option<String>,
     string_display: String,
 }
@@ -24,9 +46,9 @@ impl From<Token> for StaticToken {
             token_type: token.token_type.token_type(),
             lexeme: token.lexeme,
             line: token.line,
-            column_start: token.column_start,
-            column_end: token.column_end,
+            span: token.span,
             token_value: token.token_type.get_value(),
+            comments: Vec::new(),
             literal_display: token.token_type.literal_value(),
             string_display,
         }
@@ -40,9 +62,9 @@ impl From<&Token> for StaticToken {
             token_type: token.token_type.token_type(),
             lexeme: token.lexeme.clone(),
             line: token.line,
-            column_start: token.column_start,
-            column_end: token.column_end,
+            span: token.span.clone(),
             token_value: token.token_type.get_value(),
+            comments: Vec::new(),
             literal_display: token.token_type.literal_value(),
             string_display,
         }
@@ -5,19 +5,26 @@ use super::{Token, TokenType};
 #[derive(Debug)]
 pub enum KeywordToken {
     And,
+    Break,
+    Catch,
     Class,
+    Continue,
     Else,
     False,
+    Finally,
     Fun,
     For,
     If,
+    Import,
     Nil,
     Or,
     Return,
     Super,
     Print,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 }
@@ -26,19 +33,26 @@ impl Display for KeywordToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             KeywordToken::And => write!(f, "AND"),
+            KeywordToken::Break => write!(f, "BREAK"),
+            KeywordToken::Catch => write!(f, "CATCH"),
             KeywordToken::Class => write!(f, "CLASS"),
+            KeywordToken::Continue => write!(f, "CONTINUE"),
             KeywordToken::Else => write!(f, "ELSE"),
             KeywordToken::False => write!(f, "FALSE"),
+            KeywordToken::Finally => write!(f, "FINALLY"),
             KeywordToken::Fun => write!(f, "FUN"),
             KeywordToken::For => write!(f, "FOR"),
             KeywordToken::If => write!(f, "IF"),
+            KeywordToken::Import => write!(f, "IMPORT"),
             KeywordToken::Nil => write!(f, "NIL"),
             KeywordToken::Or => write!(f, "OR"),
             KeywordToken::Return => write!(f, "RETURN"),
             KeywordToken::Super => write!(f, "SUPER"),
             KeywordToken::Print => write!(f, "PRINT"),
             KeywordToken::This => write!(f, "THIS"),
+            KeywordToken::Throw => write!(f, "THROW"),
             KeywordToken::True => write!(f, "TRUE"),
+            KeywordToken::Try => write!(f, "TRY"),
             KeywordToken::Var => write!(f, "VAR"),
             KeywordToken::While => write!(f, "WHILE"),
         }
@@ -49,19 +63,26 @@ impl TokenType for KeywordToken {
     fn token_type(&self) -> Token {
         match self {
             KeywordToken::And => Token::And,
+            KeywordToken::Break => Token::Break,
+            KeywordToken::Catch => Token::Catch,
             KeywordToken::Class => Token::Class,
+            KeywordToken::Continue => Token::Continue,
             KeywordToken::Else => Token::Else,
             KeywordToken::False => Token::False,
+            KeywordToken::Finally => Token::Finally,
             KeywordToken::Fun => Token::Fun,
             KeywordToken::For => Token::For,
             KeywordToken::If => Token::If,
+            KeywordToken::Import => Token::Import,
             KeywordToken::Nil => Token::Nil,
             KeywordToken::Or => Token::Or,
             KeywordToken::Return => Token::Return,
             KeywordToken::Super => Token::Super,
             KeywordToken::Print => Token::Print,
             KeywordToken::This => Token::This,
+            KeywordToken::Throw => Token::Throw,
             KeywordToken::True => Token::True,
+            KeywordToken::Try => Token::Try,
             KeywordToken::Var => Token::Var,
             KeywordToken::While => Token::While,
         }
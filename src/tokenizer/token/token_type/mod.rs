@@ -106,7 +106,10 @@ pub enum Token {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
     Dot,
     Minus,
     Plus,
@@ -124,22 +127,30 @@ pub enum Token {
     EqualEqual,
     GreaterEqual,
     LessEqual,
+    Arrow,
 
     // KeywordToken
     And,
+    Break,
+    Catch,
     Class,
+    Continue,
     Else,
     False,
+    Finally,
     Fun,
     For,
     If,
+    Import,
     Nil,
     Or,
     Print,
     Return,
     Super,
     This,
+    Throw,
     True,
+    Try,
     Var,
     While,
 
@@ -156,7 +167,10 @@ impl Display for Token {
             Token::RightParen => ")",
             Token::LeftBrace => "{",
             Token::RightBrace => "}",
+            Token::LeftBracket => "[",
+            Token::RightBracket => "]",
             Token::Comma => ",",
+            Token::Colon => ":",
             Token::Dot => ".",
             Token::Minus => "-",
             Token::Plus => "+",
@@ -172,20 +186,28 @@ impl Display for Token {
             Token::EqualEqual => "==",
             Token::GreaterEqual => ">=",
             Token::LessEqual => "<=",
+            Token::Arrow => "=>",
             Token::And => "and",
+            Token::Break => "break",
+            Token::Catch => "catch",
             Token::Class => "class",
+            Token::Continue => "continue",
             Token::Else => "else",
             Token::False => "false",
+            Token::Finally => "finally",
             Token::Fun => "fun",
             Token::For => "for",
             Token::If => "if",
+            Token::Import => "import",
             Token::Nil => "nil",
             Token::Or => "or",
             Token::Print => "print",
             Token::Return => "return",
             Token::Super => "super",
             Token::This => "this",
+            Token::Throw => "throw",
             Token::True => "true",
+            Token::Try => "try",
             Token::Var => "var",
             Token::While => "while",
             Token::Number => "number",
@@ -6,19 +6,26 @@ impl KeywordTokenParser {
     pub fn from_str(str: &str) -> Option<KeywordToken> {
         match str {
             "and" => Some(KeywordToken::And),
+            "break" => Some(KeywordToken::Break),
+            "catch" => Some(KeywordToken::Catch),
             "class" => Some(KeywordToken::Class),
+            "continue" => Some(KeywordToken::Continue),
             "else" => Some(KeywordToken::Else),
             "false" => Some(KeywordToken::False),
+            "finally" => Some(KeywordToken::Finally),
             "fun" => Some(KeywordToken::Fun),
             "for" => Some(KeywordToken::For),
             "if" => Some(KeywordToken::If),
+            "import" => Some(KeywordToken::Import),
             "nil" => Some(KeywordToken::Nil),
             "or" => Some(KeywordToken::Or),
             "return" => Some(KeywordToken::Return),
             "super" => Some(KeywordToken::Super),
             "print" => Some(KeywordToken::Print),
             "this" => Some(KeywordToken::This),
+            "throw" => Some(KeywordToken::Throw),
             "true" => Some(KeywordToken::True),
+            "try" => Some(KeywordToken::Try),
             "var" => Some(KeywordToken::Var),
             "while" => Some(KeywordToken::While),
             _ => None,
@@ -14,7 +14,10 @@ impl SingleCharTokenParser {
             ')' => Some(SingleCharToken::RightParen),
             '{' => Some(SingleCharToken::LeftBrace),
             '}' => Some(SingleCharToken::RightBrace),
+            '[' => Some(SingleCharToken::LeftBracket),
+            ']' => Some(SingleCharToken::RightBracket),
             ',' => Some(SingleCharToken::Comma),
+            ':' => Some(SingleCharToken::Colon),
             '.' => Some(SingleCharToken::Dot),
             '-' => Some(SingleCharToken::Minus),
             '+' => Some(SingleCharToken::Plus),
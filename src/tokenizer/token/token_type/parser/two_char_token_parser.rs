@@ -13,6 +13,7 @@ impl TwoCharTokenParser {
             "==" => Some(TwoCharToken::EqualEqual),
             ">=" => Some(TwoCharToken::GreaterEqual),
             "<=" => Some(TwoCharToken::LessEqual),
+            "=>" => Some(TwoCharToken::Arrow),
             _ => None,
         }
     }
@@ -8,7 +8,10 @@ pub enum SingleCharToken {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
+    Colon,
     Dot,
     Minus,
     Plus,
@@ -29,7 +32,10 @@ impl Display for SingleCharToken {
             SingleCharToken::RightParen => write!(f, "RIGHT_PAREN"),
             SingleCharToken::LeftBrace => write!(f, "LEFT_BRACE"),
             SingleCharToken::RightBrace => write!(f, "RIGHT_BRACE"),
+            SingleCharToken::LeftBracket => write!(f, "LEFT_BRACKET"),
+            SingleCharToken::RightBracket => write!(f, "RIGHT_BRACKET"),
             SingleCharToken::Comma => write!(f, "COMMA"),
+            SingleCharToken::Colon => write!(f, "COLON"),
             SingleCharToken::Dot => write!(f, "DOT"),
             SingleCharToken::Minus => write!(f, "MINUS"),
             SingleCharToken::Plus => write!(f, "PLUS"),
@@ -52,7 +58,10 @@ impl TokenType for SingleCharToken {
             SingleCharToken::RightParen => Token::RightParen,
             SingleCharToken::LeftBrace => Token::LeftBrace,
             SingleCharToken::RightBrace => Token::RightBrace,
+            SingleCharToken::LeftBracket => Token::LeftBracket,
+            SingleCharToken::RightBracket => Token::RightBracket,
             SingleCharToken::Comma => Token::Comma,
+            SingleCharToken::Colon => Token::Colon,
             SingleCharToken::Dot => Token::Dot,
             SingleCharToken::Minus => Token::Minus,
             SingleCharToken::Plus => Token::Plus,
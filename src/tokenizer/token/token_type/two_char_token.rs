@@ -8,6 +8,7 @@ pub enum TwoCharToken {
     EqualEqual,
     GreaterEqual,
     LessEqual,
+    Arrow,
 }
 
 impl Display for TwoCharToken {
@@ -17,6 +18,7 @@ impl Display for TwoCharToken {
             TwoCharToken::EqualEqual => write!(f, "EQUAL_EQUAL"),
             TwoCharToken::GreaterEqual => write!(f, "GREATER_EQUAL"),
             TwoCharToken::LessEqual => write!(f, "LESS_EQUAL"),
+            TwoCharToken::Arrow => write!(f, "ARROW"),
         }
     }
 }
@@ -28,6 +30,7 @@ impl TokenType for TwoCharToken {
             TwoCharToken::EqualEqual => Token::EqualEqual,
             TwoCharToken::GreaterEqual => Token::GreaterEqual,
             TwoCharToken::LessEqual => Token::LessEqual,
+            TwoCharToken::Arrow => Token::Arrow,
         }
     }
 }